@@ -0,0 +1,328 @@
+/*****************************************************************************************************************
+
+This source file implements the reader of the R1CS circuits and their witnesses exported by the external
+toolchains in the common .r1cs/.wtns binary layout.
+
+Both files start with the 4 byte magic, the u32 version and the u32 number of the sections, every
+section being the u32 section type and the u64 section size followed by the section data, all the
+integers being little-endian. The sections may come in any order, the unknown ones are skipped.
+
+The .r1cs header section (type 1) holds the u32 byte size n8 of the field elements, the field prime,
+the u32 numbers of the wires, the public outputs, the public inputs and the private inputs, the u64
+number of the labels and the u32 number of the constraints. The constraints section (type 2) holds
+for every constraint the linear combinations a, b, c, each as the u32 number of the terms followed by
+the terms of the u32 wire index and the coefficient. The wire 0 is the constant one, the public outputs
+and the public inputs follow it.
+
+The .wtns header section (type 1) holds n8, the field prime and the u32 number of the witness values,
+the values section (type 2) holds the values of all the wires.
+
+The field elements are n8 byte little-endian integers in the standard (non Montgomery) form. The files
+are rejected if their prime is not the modulus of the field they are read for.
+
+*****************************************************************************************************************/
+
+use algebra::{PrimeField, FftField, FpParameters, ToBytes, CanonicalSerialize, CanonicalDeserialize};
+use std::io::{Read, Write, Error as IoError};
+use std::collections::HashMap;
+use std::fmt;
+use crate::r1cs::{R1CS, R1CSBuilder, LinearCombination, Variable};
+
+pub const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+pub const WTNS_MAGIC: &[u8; 4] = b"wtns";
+pub const R1CS_VERSION: u32 = 1;
+pub const WTNS_VERSION: u32 = 2;
+
+#[derive(Debug)]
+pub enum FormatError
+{
+    Io(IoError),            // file read failure
+    Magic,                  // unexpected file type
+    Version(u32),           // unsupported format version
+    Section(u32),           // missing or malformed section
+    Field,                  // field prime mismatch
+    Element,                // field element is not reduced
+    Wire(usize),            // wire index out of range
+    Witness,                // witness size does not match the circuit
+}
+
+impl From<IoError> for FormatError
+{
+    fn from(e: IoError) -> Self {FormatError::Io(e)}
+}
+
+impl fmt::Display for FormatError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            FormatError::Io(e) => write!(f, "read failure: {}", e),
+            FormatError::Magic => write!(f, "unexpected file type"),
+            FormatError::Version(v) => write!(f, "unsupported format version {}", v),
+            FormatError::Section(s) => write!(f, "missing or malformed section {}", s),
+            FormatError::Field => write!(f, "the file field prime is not the field modulus"),
+            FormatError::Element => write!(f, "field element is not reduced"),
+            FormatError::Wire(w) => write!(f, "wire {} is out of range", w),
+            FormatError::Witness => write!(f, "witness size does not match the circuit"),
+        }
+    }
+}
+
+// R1CS circuit in the external wire layout
+#[derive(Clone, Debug)]
+pub struct R1CSFile<F: PrimeField>
+{
+    pub wires: usize,           // number of the wires including the constant one
+    pub public_outputs: usize,  // number of the public outputs
+    pub public_inputs: usize,   // number of the public inputs
+    pub private_inputs: usize,  // number of the private inputs
+    pub labels: u64,            // number of the labels
+    pub constraints: Vec<[Vec<(usize, F)>; 3]>, // a, b, c linear combinations of the wires
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, FormatError>
+{
+    let mut x = [0u8; 4];
+    reader.read_exact(&mut x)?;
+    Ok(u32::from_le_bytes(x))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, FormatError>
+{
+    let mut x = [0u8; 8];
+    reader.read_exact(&mut x)?;
+    Ok(u64::from_le_bytes(x))
+}
+
+// field element byte size of the file format
+fn n8<F: PrimeField>() -> usize
+{
+    ((F::Params::MODULUS_BITS as usize + 63) / 64) * 8
+}
+
+// This function reads the sections of the file checking its magic and version
+fn sections<R: Read>(mut reader: R, magic: &[u8; 4], version: u32) -> Result<HashMap<u32, Vec<u8>>, FormatError>
+{
+    let mut m = [0u8; 4];
+    reader.read_exact(&mut m)?;
+    if &m != magic {return Err(FormatError::Magic)}
+    let v = read_u32(&mut reader)?;
+    if v != version {return Err(FormatError::Version(v))}
+
+    let mut sections = HashMap::new();
+    for _ in 0..read_u32(&mut reader)?
+    {
+        let typ = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)?;
+        let mut data = Vec::new();
+        (&mut reader).take(size).read_to_end(&mut data)?;
+        if data.len() as u64 != size {return Err(FormatError::Section(typ))}
+        if sections.insert(typ, data).is_some() {return Err(FormatError::Section(typ))}
+    }
+    Ok(sections)
+}
+
+// This function reads the field prime checking it is the modulus of F
+fn read_prime<F: PrimeField, R: Read>(reader: &mut R) -> Result<(), FormatError>
+{
+    if read_u32(reader)? as usize != n8::<F>() {return Err(FormatError::Field)}
+    let mut prime = vec![0u8; n8::<F>()];
+    reader.read_exact(&mut prime)?;
+    let mut modulus = Vec::new();
+    F::Params::MODULUS.write(&mut modulus)?;
+    modulus.resize(n8::<F>(), 0);
+    if prime != modulus {return Err(FormatError::Field)}
+    Ok(())
+}
+
+fn write_prime<F: PrimeField, W: Write>(writer: &mut W) -> Result<(), FormatError>
+{
+    let mut modulus = Vec::new();
+    F::Params::MODULUS.write(&mut modulus)?;
+    modulus.resize(n8::<F>(), 0);
+    writer.write_all(&(n8::<F>() as u32).to_le_bytes())?;
+    writer.write_all(&modulus)?;
+    Ok(())
+}
+
+fn read_element<F: PrimeField, R: Read>(reader: &mut R) -> Result<F, FormatError>
+{
+    let mut bytes = vec![0u8; n8::<F>()];
+    reader.read_exact(&mut bytes)?;
+    let size = (F::Params::MODULUS_BITS as usize + 7) / 8;
+    if bytes[size..].iter().any(|b| *b != 0) {return Err(FormatError::Element)}
+    F::deserialize(&mut &bytes[..size]).map_err(|_| FormatError::Element)
+}
+
+fn write_element<F: PrimeField, W: Write>(writer: &mut W, x: &F) -> Result<(), FormatError>
+{
+    let mut bytes = Vec::new();
+    x.serialize(&mut bytes).map_err(|_| FormatError::Element)?;
+    bytes.resize(n8::<F>(), 0);
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+fn write_section<W: Write>(writer: &mut W, typ: u32, data: &[u8]) -> Result<(), FormatError>
+{
+    writer.write_all(&typ.to_le_bytes())?;
+    writer.write_all(&(data.len() as u64).to_le_bytes())?;
+    writer.write_all(data)?;
+    Ok(())
+}
+
+impl<F: PrimeField> R1CSFile<F>
+{
+    // number of the public wires excluding the constant one
+    pub fn public(&self) -> usize {self.public_outputs + self.public_inputs}
+
+    // This function reads the .r1cs file
+    //     reader: file source
+    //     RETURN: R1CS circuit, the error if the file prime is not the modulus of F
+    pub fn read<R: Read>(reader: R) -> Result<Self, FormatError>
+    {
+        let sections = sections(reader, R1CS_MAGIC, R1CS_VERSION)?;
+
+        let mut header = &sections.get(&1).ok_or(FormatError::Section(1))?[..];
+        read_prime::<F, _>(&mut header)?;
+        let wires = read_u32(&mut header)? as usize;
+        let public_outputs = read_u32(&mut header)? as usize;
+        let public_inputs = read_u32(&mut header)? as usize;
+        let private_inputs = read_u32(&mut header)? as usize;
+        let labels = read_u64(&mut header)?;
+        let m = read_u32(&mut header)?;
+        if wires == 0 || 1 + public_outputs + public_inputs + private_inputs > wires {return Err(FormatError::Section(1))}
+
+        let mut data = &sections.get(&2).ok_or(FormatError::Section(2))?[..];
+        let mut lc = || -> Result<Vec<(usize, F)>, FormatError>
+        {
+            (0..read_u32(&mut data)?).map
+            (
+                |_|
+                {
+                    let w = read_u32(&mut data)? as usize;
+                    if w >= wires {return Err(FormatError::Wire(w))}
+                    Ok((w, read_element(&mut data)?))
+                }
+            ).collect()
+        };
+        let constraints = (0..m).map(|_| Ok([lc()?, lc()?, lc()?])).collect::<Result<Vec<_>, FormatError>>()?;
+        if !data.is_empty() {return Err(FormatError::Section(2))}
+
+        Ok(R1CSFile {wires, public_outputs, public_inputs, private_inputs, labels, constraints})
+    }
+
+    // This function writes the .r1cs file with the identity wire to label map
+    //     writer: file destination
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), FormatError>
+    {
+        writer.write_all(R1CS_MAGIC)?;
+        writer.write_all(&R1CS_VERSION.to_le_bytes())?;
+        writer.write_all(&3u32.to_le_bytes())?;
+
+        let mut header = Vec::new();
+        write_prime::<F, _>(&mut header)?;
+        for x in [self.wires, self.public_outputs, self.public_inputs, self.private_inputs].iter()
+        {
+            header.extend_from_slice(&(*x as u32).to_le_bytes());
+        }
+        header.extend_from_slice(&self.labels.to_le_bytes());
+        header.extend_from_slice(&(self.constraints.len() as u32).to_le_bytes());
+        write_section(&mut writer, 1, &header)?;
+
+        let mut data = Vec::new();
+        for constraint in self.constraints.iter()
+        {
+            for lc in constraint.iter()
+            {
+                data.extend_from_slice(&(lc.len() as u32).to_le_bytes());
+                for (w, c) in lc.iter()
+                {
+                    data.extend_from_slice(&(*w as u32).to_le_bytes());
+                    write_element(&mut data, c)?;
+                }
+            }
+        }
+        write_section(&mut writer, 2, &data)?;
+
+        let labels = (0..self.wires as u64).map(|i| i.to_le_bytes().to_vec()).flatten().collect::<Vec<_>>();
+        write_section(&mut writer, 3, &labels)
+    }
+}
+
+impl<F: PrimeField + FftField> R1CSFile<F>
+{
+    // This function loads the circuit with the witness into the R1CS builder, the public
+    // outputs and inputs become the public variables in their order and the other wires
+    // become the private variables
+    //     witness: values of all the wires, the wire 0 being one
+    //     RETURN: R1CS builder
+    pub fn builder(&self, witness: &[F]) -> Result<R1CSBuilder<F>, FormatError>
+    {
+        if witness.len() != self.wires || witness[0] != F::one() {return Err(FormatError::Witness)}
+
+        let mut cb = R1CSBuilder::<F>::new();
+        let vars = (0..self.wires).map
+        (
+            |w|
+            {
+                if w == 0 {Variable::Public(0)}
+                else if w <= self.public() {cb.public("", witness[w])}
+                else {cb.private("", witness[w])}
+            }
+        ).collect::<Vec<_>>();
+
+        for constraint in self.constraints.iter()
+        {
+            let [a, b, c] = constraint;
+            let lc = |terms: &Vec<(usize, F)>| LinearCombination {terms: terms.iter().map(|(w, c)| (*c, vars[*w])).collect()};
+            cb.enforce(lc(a), lc(b), lc(c));
+        }
+        Ok(cb)
+    }
+
+    // This function compiles the circuit with the witness into the layout of the Marlin index
+    //     witness: values of all the wires, the wire 0 being one
+    //     RETURN: R1CS matrices with the witness
+    pub fn compile(&self, witness: &[F]) -> Result<R1CS<F>, FormatError>
+    {
+        self.builder(witness)?.finalize().ok_or(FormatError::Section(2))
+    }
+}
+
+// This function reads the .wtns file
+//     reader: file source
+//     RETURN: values of all the wires, the error if the file prime is not the modulus of F
+pub fn read_wtns<F: PrimeField, R: Read>(reader: R) -> Result<Vec<F>, FormatError>
+{
+    let sections = sections(reader, WTNS_MAGIC, WTNS_VERSION)?;
+
+    let mut header = &sections.get(&1).ok_or(FormatError::Section(1))?[..];
+    read_prime::<F, _>(&mut header)?;
+    let n = read_u32(&mut header)?;
+
+    let mut data = &sections.get(&2).ok_or(FormatError::Section(2))?[..];
+    let witness = (0..n).map(|_| read_element(&mut data)).collect::<Result<Vec<F>, _>>()?;
+    if !data.is_empty() {return Err(FormatError::Section(2))}
+    Ok(witness)
+}
+
+// This function writes the .wtns file
+//     writer: file destination
+//     witness: values of all the wires
+pub fn write_wtns<F: PrimeField, W: Write>(mut writer: W, witness: &[F]) -> Result<(), FormatError>
+{
+    writer.write_all(WTNS_MAGIC)?;
+    writer.write_all(&WTNS_VERSION.to_le_bytes())?;
+    writer.write_all(&2u32.to_le_bytes())?;
+
+    let mut header = Vec::new();
+    write_prime::<F, _>(&mut header)?;
+    header.extend_from_slice(&(witness.len() as u32).to_le_bytes());
+    write_section(&mut writer, 1, &header)?;
+
+    let mut data = Vec::new();
+    for x in witness.iter() {write_element(&mut data, x)?}
+    write_section(&mut writer, 2, &data)
+}
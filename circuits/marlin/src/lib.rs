@@ -1,2 +1,4 @@
 pub mod gate;
 pub mod domains;
+pub mod r1cs;
+pub mod circom;
@@ -0,0 +1,317 @@
+/*****************************************************************************************************************
+
+This source file implements the Marlin R1CS builder.
+
+The builder allocates the public and the private variables together with their witness values and
+records the rank-1 constraints <a, w> * <b, w> = <c, w> over the linear combinations a, b, c of the
+variables. The public variable 0 is the constant one.
+
+On finalization the builder emits the constraint matrices and the witness in the layout the Marlin
+index and prover expect. The matrices are square of the size n of the evaluation domain H, the column
+of the variable being its position in the witness vector. The public inputs are read by the prover
+from the witness positions i * n / |X| where X is the public input domain, so that the public
+variables are placed at these positions, the unused ones of them staying zero, and the private
+variables fill the remaining positions in the order of their allocation.
+
+*****************************************************************************************************************/
+
+use algebra::{FftField, Zero, One};
+use ff_fft::{EvaluationDomain, Radix2EvaluationDomain as D};
+use sprs::CsMat;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Add, Sub, Neg, Mul};
+
+// circuit variable
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Variable
+{
+    Public(usize),
+    Private(usize),
+}
+
+// the constant one variable
+pub const ONE: Variable = Variable::Public(0);
+
+// linear combination of the variables
+#[derive(Clone, Debug)]
+pub struct LinearCombination<F: FftField>
+{
+    pub terms: Vec<(F, Variable)>,
+}
+
+impl<F: FftField> LinearCombination<F>
+{
+    pub fn zero() -> Self
+    {
+        LinearCombination {terms: Vec::new()}
+    }
+
+    // constant multiple of the one variable
+    pub fn constant(c: F) -> Self
+    {
+        LinearCombination {terms: vec![(c, ONE)]}
+    }
+
+    // This function adds the term to the linear combination
+    pub fn term(mut self, c: F, v: Variable) -> Self
+    {
+        self.terms.push((c, v));
+        self
+    }
+
+    // This function merges the terms of the same variables dropping the zero ones
+    pub fn compact(&self) -> BTreeMap<Variable, F>
+    {
+        let mut terms = BTreeMap::new();
+        for (c, v) in self.terms.iter()
+        {
+            *terms.entry(*v).or_insert(F::zero()) += c;
+        }
+        terms.into_iter().filter(|(_, c)| !c.is_zero()).collect()
+    }
+}
+
+impl<F: FftField> From<Variable> for LinearCombination<F>
+{
+    fn from(v: Variable) -> Self
+    {
+        LinearCombination {terms: vec![(F::one(), v)]}
+    }
+}
+
+impl<F: FftField> Add for LinearCombination<F>
+{
+    type Output = Self;
+    fn add(mut self, other: Self) -> Self
+    {
+        self.terms.extend(other.terms);
+        self
+    }
+}
+
+impl<F: FftField> Add<Variable> for LinearCombination<F>
+{
+    type Output = Self;
+    fn add(self, v: Variable) -> Self {self.term(F::one(), v)}
+}
+
+impl<F: FftField> Sub for LinearCombination<F>
+{
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {self + -other}
+}
+
+impl<F: FftField> Sub<Variable> for LinearCombination<F>
+{
+    type Output = Self;
+    fn sub(self, v: Variable) -> Self {self.term(-F::one(), v)}
+}
+
+impl<F: FftField> Neg for LinearCombination<F>
+{
+    type Output = Self;
+    fn neg(self) -> Self {self * -F::one()}
+}
+
+impl<F: FftField> Mul<F> for LinearCombination<F>
+{
+    type Output = Self;
+    fn mul(mut self, c: F) -> Self
+    {
+        for t in self.terms.iter_mut() {t.0 *= &c}
+        self
+    }
+}
+
+// finalized R1CS in the layout of the Marlin index
+pub struct R1CS<F: FftField>
+{
+    pub a: CsMat<F>,
+    pub b: CsMat<F>,
+    pub c: CsMat<F>,
+    pub witness: Vec<F>,
+    pub public_inputs: usize,   // number of the public inputs including the constant one
+}
+
+impl<F: FftField> R1CS<F>
+{
+    // This function computes the values of the public inputs in the order of their allocation
+    pub fn public(&self) -> Vec<F>
+    {
+        let ratio = self.witness.len() / D::<F>::compute_size_of_domain(self.public_inputs).unwrap();
+        (0..self.public_inputs).map(|i| self.witness[i * ratio]).collect()
+    }
+
+    // This function verifies the consistency of the witness against the constraints
+    //     RETURN: verification status
+    pub fn verify(&self) -> bool
+    {
+        let eval = |m: &CsMat<F>| -> Vec<F>
+        {
+            m.outer_iterator().map(|row| row.iter().fold(F::zero(), |acc, (j, c)| acc + &(self.witness[j] * c))).collect()
+        };
+        let (a, b, c) = (eval(&self.a), eval(&self.b), eval(&self.c));
+        a.iter().zip(b.iter()).zip(c.iter()).all(|((a, b), c)| *a * b == *c)
+    }
+}
+
+pub struct R1CSBuilder<F: FftField>
+{
+    public: Vec<F>,                             // public variable values
+    private: Vec<F>,                            // private variable values
+    names: HashMap<String, Variable>,           // named variables
+    constraints: Vec<[LinearCombination<F>; 3]>,
+}
+
+impl<F: FftField> Default for R1CSBuilder<F>
+{
+    fn default() -> Self {Self::new()}
+}
+
+impl<F: FftField> R1CSBuilder<F>
+{
+    pub fn new() -> Self
+    {
+        R1CSBuilder
+        {
+            public: vec![F::one()],
+            private: Vec::new(),
+            names: HashMap::new(),
+            constraints: Vec::new(),
+        }
+    }
+
+    // number of the recorded constraints
+    pub fn constraints(&self) -> usize {self.constraints.len()}
+
+    // This function allocates the public variable
+    //     name: unique variable name, empty for the unnamed variable
+    //     value: witness value
+    pub fn public(&mut self, name: &str, value: F) -> Variable
+    {
+        self.public.push(value);
+        self.name(name, Variable::Public(self.public.len() - 1))
+    }
+
+    // This function allocates the private variable
+    //     name: unique variable name, empty for the unnamed variable
+    //     value: witness value
+    pub fn private(&mut self, name: &str, value: F) -> Variable
+    {
+        self.private.push(value);
+        self.name(name, Variable::Private(self.private.len() - 1))
+    }
+
+    fn name(&mut self, name: &str, v: Variable) -> Variable
+    {
+        if !name.is_empty()
+        {
+            assert!(!self.names.contains_key(name), "variable {} is already allocated", name);
+            self.names.insert(name.to_string(), v);
+        }
+        v
+    }
+
+    // This function looks up the named variable
+    pub fn variable(&self, name: &str) -> Option<Variable> {self.names.get(name).copied()}
+
+    // witness value of the variable
+    pub fn value(&self, v: Variable) -> F
+    {
+        match v
+        {
+            Variable::Public(i) => self.public[i],
+            Variable::Private(i) => self.private[i],
+        }
+    }
+
+    // This function assigns the witness value to the variable, the constant one can not be reassigned
+    pub fn assign(&mut self, v: Variable, value: F)
+    {
+        match v
+        {
+            Variable::Public(0) => panic!("the constant one can not be reassigned"),
+            Variable::Public(i) => self.public[i] = value,
+            Variable::Private(i) => self.private[i] = value,
+        }
+    }
+
+    // This function evaluates the linear combination at the witness
+    pub fn eval(&self, lc: &LinearCombination<F>) -> F
+    {
+        lc.terms.iter().fold(F::zero(), |acc, (c, v)| acc + &(*c * &self.value(*v)))
+    }
+
+    // This function records the constraint <a, w> * <b, w> = <c, w>
+    pub fn enforce<A, B, C>(&mut self, a: A, b: B, c: C)
+        where A: Into<LinearCombination<F>>, B: Into<LinearCombination<F>>, C: Into<LinearCombination<F>>
+    {
+        self.constraints.push([a.into(), b.into(), c.into()]);
+    }
+
+    // This function verifies the consistency of the witness against the recorded constraints
+    //     RETURN: index of the first unsatisfied constraint, none if all are satisfied
+    pub fn unsatisfied(&self) -> Option<usize>
+    {
+        self.constraints.iter().position(|[a, b, c]| self.eval(a) * &self.eval(b) != self.eval(c))
+    }
+
+    // This function computes the witness positions of the variables
+    //     RETURN: domain size, positions of the public and the private variables
+    fn layout(&self) -> Option<(usize, Vec<usize>, Vec<usize>)>
+    {
+        let x = D::<F>::compute_size_of_domain(self.public.len())?;
+        let n = D::<F>::compute_size_of_domain(std::cmp::max(self.constraints.len(), x + self.private.len()))?;
+        let ratio = n / x;
+        let public = (0..self.public.len()).map(|i| i * ratio).collect();
+        let private = (0..n).filter(|j| j % ratio != 0).take(self.private.len()).collect();
+        Some((n, public, private))
+    }
+
+    // This function emits the constraint matrices and the witness
+    //     RETURN: R1CS in the layout of the Marlin index, none if the size exceeds the field domains
+    pub fn finalize(&self) -> Option<R1CS<F>>
+    {
+        let (n, public, private) = self.layout()?;
+        let column = |v: &Variable| match v
+        {
+            Variable::Public(i) => public[*i],
+            Variable::Private(i) => private[*i],
+        };
+
+        let mut witness = vec![F::zero(); n];
+        for (i, p) in public.iter().enumerate() {witness[*p] = self.public[i]}
+        for (i, p) in private.iter().enumerate() {witness[*p] = self.private[i]}
+
+        let matrix = |k: usize| -> CsMat<F>
+        {
+            let mut indptr = vec![0];
+            let mut indices = Vec::new();
+            let mut data = Vec::new();
+            for row in 0..n
+            {
+                if row < self.constraints.len()
+                {
+                    let mut terms = self.constraints[row][k].compact().into_iter().map(|(v, c)| (column(&v), c)).collect::<Vec<_>>();
+                    terms.sort_by_key(|(j, _)| *j);
+                    for (j, c) in terms.into_iter()
+                    {
+                        indices.push(j);
+                        data.push(c);
+                    }
+                }
+                indptr.push(indices.len());
+            }
+            CsMat::new((n, n), indptr, indices, data)
+        };
+
+        Some(R1CS
+        {
+            a: matrix(0),
+            b: matrix(1),
+            c: matrix(2),
+            witness,
+            public_inputs: self.public.len(),
+        })
+    }
+}
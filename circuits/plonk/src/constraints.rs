@@ -7,13 +7,16 @@ This source file implements Plonk circuit constraint primitive.
 use algebra::{FftField, SquareRootField};
 use oracle::poseidon::{SpongeConstants, PlonkSpongeConstants, ArithmeticSpongeParams};
 use ff_fft::{EvaluationDomain, DensePolynomial, Evaluations, Radix2EvaluationDomain as D};
-pub use super::polynomial::{WitnessOverDomains, WitnessShifts, WitnessEvals};
-pub use super::gate::{CircuitGate, GateType};
+pub use super::polynomial::{WitnessOverDomains, WitnessShifts, WitnessEvals, SelectorEvals};
+pub use super::gate::{CircuitGate, GateType, Selector};
 pub use super::domains::EvaluationDomains;
 pub use super::wires::GateWires;
+pub use super::custom::GateRegistry;
+use std::collections::BTreeMap;
 use blake2::{Blake2b, Digest};
 use oracle::utils::EvalUtils;
 use array_init::array_init;
+use rayon::prelude::*;
 
 #[derive(Clone)]
 pub struct ConstraintSystem<F: FftField>
@@ -27,56 +30,21 @@ pub struct ConstraintSystem<F: FftField>
     pub sigmam: [DensePolynomial<F>; 3],    // permutation polynomial array
     pub zkpm:   DensePolynomial<F>,         // zero-knowledge polynomial
 
-    // generic constraint selector polynomials
-    pub qlm:    DensePolynomial<F>,         // left input wire polynomial
-    pub qrm:    DensePolynomial<F>,         // right input wire polynomial
-    pub qom:    DensePolynomial<F>,         // output wire polynomial
-    pub qmm:    DensePolynomial<F>,         // multiplication polynomial
-    pub qc:     DensePolynomial<F>,         // constant wire polynomial
-
-    // poseidon selector polynomials
-    pub rcm:    [DensePolynomial<F>; PlonkSpongeConstants::SPONGE_WIDTH], // round constant polynomials
-    pub psm:    DensePolynomial<F>,         // poseidon constraint selector polynomial
-
-    // EC point addition constraint polynomials
-    pub addm:   DensePolynomial<F>,         // EC point addition constraint selector polynomial
-
-    // variable base scalar multiplication constraint polynomials
-    pub mul1m:  DensePolynomial<F>,         // mul1m constraint selector polynomial
-    pub mul2m:  DensePolynomial<F>,         // mul1m constraint selector polynomial
-    pub emul1m: DensePolynomial<F>,         // emul1m constraint selector polynomial
-    pub emul2m: DensePolynomial<F>,         // emul2m constraint selector polynomial
-    pub emul3m: DensePolynomial<F>,         // emul3m constraint selector polynomial
+    // built-in gate selector polynomials keyed by the selector, see Selector
+    pub selm:   BTreeMap<Selector, DensePolynomial<F>>,
 
     // POLYNOMIALS OVER LAGRANGE BASE
 
-    // generic constraint selector polynomials
-    pub qll:    Evaluations<F, D<F>>,       // left input wire polynomial over domain.d4
-    pub qrl:    Evaluations<F, D<F>>,       // right input wire polynomial over domain.d4
-    pub qol:    Evaluations<F, D<F>>,       // output wire polynomial over domain.d4
-    pub qml:    Evaluations<F, D<F>>,       // multiplication evaluations over domain.d4
-
-    // permutation polynomials
     pub sigmal1:[Vec<F>; 3],                // permutation polynomial array evaluations over domain d1
-    pub sigmal4:[Evaluations<F, D<F>>; 3],  // permutation polynomial array evaluations over domain d8
     pub sid:    Vec<F>,                     // SID polynomial
 
-    // poseidon selector polynomials
-    pub ps4:    Evaluations<F, D<F>>,       // poseidon selector over domain.d4
-    pub ps8:    Evaluations<F, D<F>>,       // poseidon selector over domain.d8
+    // selector evaluations over domain.d4 and domain.d8, resident or computed per proof
+    pub quot_mode: QuotientMode<F>,
 
-    // ECC arithmetic selector polynomials
-    pub addl4:  Evaluations<F, D<F>>,       // EC point addition selector evaluations w over domain.d4
-    pub mul1l:  Evaluations<F, D<F>>,       // scalar multiplication selector evaluations over domain.d4
-    pub mul2l:  Evaluations<F, D<F>>,       // scalar multiplication selector evaluations over domain.d8
-    pub emul1l: Evaluations<F, D<F>>,       // endoscalar multiplication selector evaluations over domain.d4
-    pub emul2l: Evaluations<F, D<F>>,       // endoscalar multiplication selector evaluations over domain.d4
-    pub emul3l: Evaluations<F, D<F>>,       // endoscalar multiplication selector evaluations over domain.d8
+    // CUSTOM GATES, registered on top of the built-in gates above
 
-    pub l04:    Evaluations<F, D<F>>,       // 0-th Lagrange evaluated over domain.d4
-    pub l08:    Evaluations<F, D<F>>,       // 0-th Lagrange evaluated over domain.d8
-    pub l1:     Evaluations<F, D<F>>,       // 1-st Lagrange evaluated over domain.d8
-    pub zkpl:   Evaluations<F, D<F>>,       // zero-knowledge polynomial over domain.d8
+    pub custom: GateRegistry<F>,                            // registered custom gates
+    pub customm: BTreeMap<usize, Vec<DensePolynomial<F>>>,  // custom gate selector polynomials keyed by the gate id
 
     pub r:      F,                          // coordinate shift for right wires
     pub o:      F,                          // coordinate shift for output wires
@@ -86,6 +54,19 @@ pub struct ConstraintSystem<F: FftField>
     pub fr_sponge_params: ArithmeticSpongeParams<F>,
 }
 
+// The evaluation mode of the quotient polynomial contributions, trading the prover memory for time.
+// The resident selector evaluations over domain.d4 and domain.d8 take about 110 field elements per gate
+// for the life of the constraint system, and the prover witness evaluations about 90 more per proof.
+// Chunked, the selector and witness evaluations are computed per proof over the chunks of domain.d8,
+// the cosets of the subgroup of the size d8.size/chunks, about 40 field elements per gate in all over
+// 8 chunks, at the cost of the selector FFTs per proof
+#[derive(Clone)]
+pub enum QuotientMode<F: FftField>
+{
+    Resident(SelectorEvals<F>),     // selector evaluations kept in the constraint system
+    Chunked(usize),                 // number of the chunks of domain.d8: 1, 2, 4 or 8
+}
+
 pub fn zk_w<F:FftField>(domain : D<F>) -> F {
     domain.group_gen.pow(&[domain.size - 3])
 }
@@ -105,15 +86,98 @@ pub fn zk_polynomial<F:FftField>(domain : D<F>) -> DensePolynomial<F> {
     ])
 }
 
+// This function evaluates the polynomial over the coset of the domain
+//     p: polynomial of the degree less than the domain size
+//     g: coset offset
+//     d: domain
+//     RETURN: evaluations at g d
+fn evaluate_coset<F: FftField>(p: &DensePolynomial<F>, g: F, d: D<F>) -> Evaluations<F, D<F>>
+{
+    if p.is_zero() {return Evaluations::<F, D<F>>::from_vec_and_domain(vec![F::zero(); d.size()], d)}
+    if g.is_one() {return p.evaluate_over_domain_by_ref(d)}
+    let mut gi = F::one();
+    let coeffs = p.coeffs.iter().map(|c| {let s = *c * &gi; gi *= &g; s}).collect();
+    DensePolynomial::<F>::from_coefficients_vec(coeffs).evaluate_over_domain(d)
+}
+
+// This function computes the witness evaluations and their shifts to the next row
+//     evals: evaluations of l, r, o, z
+//     next: index shift of the next row
+//     RETURN: evaluations and their shifts
+fn witness_shifts<F: FftField>(evals: [Evaluations<F, D<F>>; 4], next: usize) -> WitnessShifts<F>
+{
+    let [l, r, o, z] = evals;
+    WitnessShifts
+    {
+        next: WitnessEvals
+        {
+            l: l.shift(next),
+            r: r.shift(next),
+            o: o.shift(next),
+            z: z.shift(next),
+        },
+        this: WitnessEvals {l, r, o, z},
+    }
+}
+
 impl<F: FftField + SquareRootField> ConstraintSystem<F>
 {
     pub fn create
+    (
+        gates: Vec<CircuitGate<F>>,
+        fr_sponge_params: ArithmeticSpongeParams<F>,
+        public: usize,
+    ) -> Option<Self>
+    {
+        Self::create_with_registry(gates, GateRegistry::new(), fr_sponge_params, public)
+    }
+
+    // This function creates the constraint system with custom gates
+    //     gates: circuit gates, GateType::Custom gates have to reference the registry
+    //     custom: registry of the custom gates
+    //     fr_sponge_params: random oracle argument parameters
+    //     public: number of public inputs
+    //     RETURN: constraint system, None if the gates are inconsistent with the registry
+    pub fn create_with_registry
+    (
+        gates: Vec<CircuitGate<F>>,
+        custom: GateRegistry<F>,
+        fr_sponge_params: ArithmeticSpongeParams<F>,
+        public: usize,
+    ) -> Option<Self>
+    {
+        Self::create_with_quotient_chunks(gates, custom, fr_sponge_params, public, None)
+    }
+
+    // This function creates the constraint system with custom gates in the quotient evaluation mode,
+    // the chunked constraint system never holding the selector evaluations over domain.d4 and domain.d8
+    //     gates: circuit gates, GateType::Custom gates have to reference the registry
+    //     custom: registry of the custom gates
+    //     fr_sponge_params: random oracle argument parameters
+    //     public: number of public inputs
+    //     chunks: quotient evaluation mode, see set_quotient_chunks
+    //     RETURN: constraint system, None if the gates are inconsistent with the registry
+    pub fn create_with_quotient_chunks
     (
         mut gates: Vec<CircuitGate<F>>,
+        custom: GateRegistry<F>,
         fr_sponge_params: ArithmeticSpongeParams<F>,
         public: usize,
+        chunks: Option<usize>,
     ) -> Option<Self>
     {
+        // the quotient polynomial size is bounded by the poseidon constraint degree
+        if custom.gates.iter().any(|(_, g)| g.degree() > PlonkSpongeConstants::SPONGE_BOX + 1) {return None}
+        if gates.iter().any
+        (
+            |gate| gate.typ == GateType::Custom &&
+                match gate.custom.and_then(|id| custom.get(id))
+                {
+                    Some(g) => g.selector(gate).len() != g.selectors(),
+                    None => true
+                }
+        ) {return None}
+
         let domain = EvaluationDomains::<F>::create(gates.len())?;
         let mut sid = domain.d1.elements().map(|elm| {elm}).collect::<Vec<_>>();
 
@@ -151,67 +215,47 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
         // x^3 - x^2(w1+w2+w3) + x(w1w2+w1w3+w2w3) - w1w2w3
         let zkpm = zk_polynomial(domain.d1);
 
-        // compute generic constraint polynomials
-        let qlm = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.ql()).collect(), domain.d1).interpolate();
-        let qrm = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.qr()).collect(), domain.d1).interpolate();
-        let qom = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.qo()).collect(), domain.d1).interpolate();
-        let qmm = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.qm()).collect(), domain.d1).interpolate();
-
-        // compute poseidon constraint polynomials
-        let psm = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.ps()).collect(), domain.d1).interpolate();
+        // compute built-in gate selector polynomials
+        let selm = Selector::ALL.iter().map
+        (
+            |sel| (*sel, Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.selector(*sel)).collect(), domain.d1).interpolate())
+        ).collect();
 
-        // compute ECC arithmetic constraint polynomials
-        let addm = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.add1()).collect(), domain.d1).interpolate();
-        let mul1m = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.vbmul1()).collect(), domain.d1).interpolate();
-        let mul2m = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.vbmul2()).collect(), domain.d1).interpolate();
-        let emul1m = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.endomul1()).collect(), domain.d1).interpolate();
-        let emul2m = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.endomul2()).collect(), domain.d1).interpolate();
-        let emul3m = Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.endomul3()).collect(), domain.d1).interpolate();
+        // compute custom constraint polynomials
+        let mut customm = BTreeMap::new();
+        for (id, (_, gate)) in custom.gates.iter().enumerate()
+        {
+            let selectors = gates.iter().map
+            (
+                |g|
+                {
+                    if g.typ == GateType::Custom && g.custom == Some(id) {gate.selector(g)}
+                    else {vec![F::zero(); gate.selectors()]}
+                }
+            ).collect::<Vec<_>>();
+            let selm = (0..gate.selectors()).map
+            (
+                |i| Evaluations::<F, D<F>>::from_vec_and_domain(selectors.iter().map(|s| s[i]).collect(), domain.d1).interpolate()
+            ).collect::<Vec<_>>();
+            customm.insert(id, selm);
+        }
 
-        Some(ConstraintSystem
+        let mut cs = ConstraintSystem
         {
             domain,
             public,
             sid,
             sigmal1,
-            sigmal4: array_init(|i| sigmam[i].evaluate_over_domain_by_ref(domain.d8)),
             sigmam,
+            quot_mode: QuotientMode::Chunked(8),
+
+            // built-in gate selector polynomials
+            selm,
+
+            // custom constraint polynomials
+            custom,
+            customm,
 
-            // generic constraint polynomials
-            qll: qlm.evaluate_over_domain_by_ref(domain.d4),
-            qrl: qrm.evaluate_over_domain_by_ref(domain.d4),
-            qol: qom.evaluate_over_domain_by_ref(domain.d4),
-            qml: qmm.evaluate_over_domain_by_ref(domain.d4),
-            qlm,
-            qrm,
-            qom,
-            qmm,
-            qc: Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.qc()).collect(), domain.d1).interpolate(),
-
-            // poseidon constraint polynomials
-            rcm: array_init(|i| Evaluations::<F, D<F>>::from_vec_and_domain(gates.iter().map(|gate| gate.rc()[i]).collect(), domain.d1).interpolate()),
-            ps4: psm.evaluate_over_domain_by_ref(domain.d4),
-            ps8: psm.evaluate_over_domain_by_ref(domain.d8),
-            psm,
-
-            // ECC arithmetic constraint polynomials
-            addl4: addm.evaluate_over_domain_by_ref(domain.d4),
-            addm,
-            mul1l: mul1m.evaluate_over_domain_by_ref(domain.d4),
-            mul2l: mul2m.evaluate_over_domain_by_ref(domain.d8),
-            mul1m,
-            mul2m,
-            emul1l: emul1m.evaluate_over_domain_by_ref(domain.d4),
-            emul2l: emul2m.evaluate_over_domain_by_ref(domain.d4),
-            emul3l: emul3m.evaluate_over_domain_by_ref(domain.d8),
-            emul1m,
-            emul2m,
-            emul3m,
-
-            l04: DensePolynomial::from_coefficients_slice(&[F::one()]).evaluate_over_domain_by_ref(domain.d4),
-            l08: DensePolynomial::from_coefficients_slice(&[F::one()]).evaluate_over_domain_by_ref(domain.d8),
-            l1: DensePolynomial::from_coefficients_slice(&[F::zero(), F::one()]).evaluate_over_domain_by_ref(domain.d8),
-            zkpl: zkpm.evaluate_over_domain_by_ref(domain.d8),
             zkpm,
 
             gates,
@@ -219,7 +263,71 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
             o,
             endo: F::zero(),
             fr_sponge_params,
-        })
+        };
+        cs.set_quotient_chunks(chunks);
+        Some(cs)
+    }
+
+    // This function returns the built-in gate selector polynomial
+    pub fn sel(&self, sel: Selector) -> &DensePolynomial<F>
+    {
+        &self.selm[&sel]
+    }
+
+    // This function sets the evaluation mode of the quotient polynomial contributions
+    //     chunks: None to keep the selector evaluations resident, or the number of the chunks of domain.d8,
+    //         1, 2, 4 or 8, to compute the evaluations per proof, the more chunks the less memory
+    pub fn set_quotient_chunks(&mut self, chunks: Option<usize>)
+    {
+        self.quot_mode = match chunks
+        {
+            None => QuotientMode::Resident(self.selector_evals((F::one(), self.domain.d4), (F::one(), self.domain.d8))),
+            Some(chunks) =>
+            {
+                assert!([1, 2, 4, 8].contains(&chunks), "domain d8 has to be split into 1, 2, 4 or 8 chunks!");
+                QuotientMode::Chunked(chunks)
+            }
+        }
+    }
+
+    // This function evaluates the selector polynomials over the cosets of the domains
+    //     d4: coset offset and domain in place of domain.d4
+    //     d8: coset offset and domain in place of domain.d8
+    //     RETURN: selector evaluations
+    fn selector_evals(&self, d4: (F, D<F>), d8: (F, D<F>)) -> SelectorEvals<F>
+    {
+        let e4 = |p: &DensePolynomial<F>| evaluate_coset(p, d4.0, d4.1);
+        let e8 = |p: &DensePolynomial<F>| evaluate_coset(p, d8.0, d8.1);
+        let one = DensePolynomial::from_coefficients_slice(&[F::one()]);
+
+        SelectorEvals
+        {
+            qll: e4(self.sel(Selector::Ql)),
+            qrl: e4(self.sel(Selector::Qr)),
+            qol: e4(self.sel(Selector::Qo)),
+            qml: e4(self.sel(Selector::Qm)),
+            sigmal4: array_init(|i| e8(&self.sigmam[i])),
+            ps4: e4(self.sel(Selector::Ps)),
+            ps8: e8(self.sel(Selector::Ps)),
+            addl8: e8(self.sel(Selector::Add)),
+            mul1l: e4(self.sel(Selector::Mul1)),
+            mul2l: e8(self.sel(Selector::Mul2)),
+            emul1l: e4(self.sel(Selector::Emul1)),
+            emul2l: e4(self.sel(Selector::Emul2)),
+            emul3l: e8(self.sel(Selector::Emul3)),
+            customl: self.customm.iter().map
+            (
+                |(id, selm)|
+                {
+                    let degree = self.custom.gates[*id].1.degree();
+                    (*id, selm.iter().map(|s| if degree <= 3 {e4(s)} else {e8(s)}).collect())
+                }
+            ).collect(),
+            l04: e4(&one),
+            l08: e8(&one),
+            l1: e8(&DensePolynomial::from_coefficients_slice(&[F::zero(), F::one()])),
+            zkpl: e8(&self.zkpm),
+        }
     }
 
     // This function verifies the consistency of the wire
@@ -284,53 +392,86 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
         z: &DensePolynomial<F>,
     ) -> WitnessOverDomains<F>
     {
-        // compute shifted witness polynomials
-        let l4 = l.evaluate_over_domain_by_ref(self.domain.d4);
-        let r4 = r.evaluate_over_domain_by_ref(self.domain.d4);
-        let o4 = o.evaluate_over_domain_by_ref(self.domain.d4);
         let z4 = DensePolynomial::<F>::zero().evaluate_over_domain_by_ref(D::<F>::new(1).unwrap());
-
-        let l8 = l.evaluate_over_domain_by_ref(self.domain.d8);
-        let r8 = r.evaluate_over_domain_by_ref(self.domain.d8);
-        let o8 = o.evaluate_over_domain_by_ref(self.domain.d8);
-        let z8 = z.evaluate_over_domain_by_ref(self.domain.d8);
+        let e4 = |p: &DensePolynomial<F>| p.evaluate_over_domain_by_ref(self.domain.d4);
+        let e8 = |p: &DensePolynomial<F>| p.evaluate_over_domain_by_ref(self.domain.d8);
 
         WitnessOverDomains
         {
-            d4: WitnessShifts
+            // dummy z evaluations
+            d4: witness_shifts([e4(l), e4(r), e4(o), z4], 4),
+            d8: witness_shifts([e8(l), e8(r), e8(o), e8(z)], 8),
+        }
+    }
+
+    // This function evaluates the witness and the selector polynomials over the chunk of domain.d8, the
+    // coset w^k D of the subgroup D of the size d8.size/chunks, w the generator of domain.d8. Over the chunk,
+    // the next row is the shift by 8/chunks
+    //     l, r, o, z: witness polynomials
+    //     chunks: number of the chunks
+    //     k: chunk index
+    //     RETURN: witness and selector evaluations over the chunk both in place of domain.d4 and domain.d8
+    pub fn evaluate_chunk
+    (
+        &self,
+        l: &DensePolynomial<F>,
+        r: &DensePolynomial<F>,
+        o: &DensePolynomial<F>,
+        z: &DensePolynomial<F>,
+        chunks: usize,
+        k: usize,
+    ) -> (WitnessOverDomains<F>, SelectorEvals<F>)
+    {
+        let d = D::<F>::new(self.domain.d8.size() / chunks).unwrap();
+        let g = self.domain.d8.group_gen.pow(&[k as u64]);
+        let e = |p: &DensePolynomial<F>| evaluate_coset(p, g, d);
+
+        let d8 = witness_shifts([e(l), e(r), e(o), e(z)], 8 / chunks);
+        let dummy = |w: &WitnessEvals<F>| WitnessEvals
+            {l: w.l.clone(), r: w.r.clone(), o: w.o.clone(), z: DensePolynomial::<F>::zero().evaluate_over_domain_by_ref(D::<F>::new(1).unwrap())};
+        (
+            WitnessOverDomains {d4: WitnessShifts {this: dummy(&d8.this), next: dummy(&d8.next)}, d8},
+            self.selector_evals((g, d), (g, d)),
+        )
+    }
+
+    // This function computes the sum of the quotient polynomial contributions evaluated over domain.d4 and
+    // domain.d8, from the resident selector evaluations or chunk by chunk, see QuotientMode. Over the chunks,
+    // the contributions are evaluated at the same points of domain.d8 and interpolated together, the result
+    // being the same polynomial as of the resident evaluations
+    //     l, r, o, z: witness polynomials
+    //     quot: contributions over domain.d4 and domain.d8 of the witness and selector evaluations
+    //     RETURN: sum of the interpolated contributions
+    pub fn quot_evals<Q>
+    (
+        &self,
+        l: &DensePolynomial<F>,
+        r: &DensePolynomial<F>,
+        o: &DensePolynomial<F>,
+        z: &DensePolynomial<F>,
+        mut quot: Q,
+    ) -> DensePolynomial<F>
+    where Q: FnMut(&WitnessOverDomains<F>, &SelectorEvals<F>) -> (Evaluations<F, D<F>>, Evaluations<F, D<F>>)
+    {
+        match &self.quot_mode
+        {
+            QuotientMode::Resident(selectors) =>
             {
-                next: WitnessEvals
-                {
-                    l: l4.shift(4),
-                    r: r4.shift(4),
-                    o: o4.shift(4),
-                    z: z4.clone() // dummy evaluation
-                },
-                this: WitnessEvals
-                {
-                    l: l4,
-                    r: r4,
-                    o: o4,
-                    z: z4 // dummy evaluation
-                },
-            },
-            d8: WitnessShifts
+                let (t4, t8) = quot(&self.evaluate(l, r, o, z), selectors);
+                &t4.interpolate() + &t8.interpolate()
+            }
+            QuotientMode::Chunked(chunks) =>
             {
-                next: WitnessEvals
+                let mut t = vec![F::zero(); self.domain.d8.size()];
+                for k in 0..*chunks
                 {
-                    l: l8.shift(8),
-                    r: r8.shift(8),
-                    o: o8.shift(8),
-                    z: z8.shift(8),
-                },
-                this: WitnessEvals
-                {
-                    l: l8,
-                    r: r8,
-                    o: o8,
-                    z: z8,
-                },
-            },
+                    let (polys, selectors) = self.evaluate_chunk(l, r, o, z, *chunks, k);
+                    let (t4, t8) = quot(&polys, &selectors);
+                    t.par_chunks_mut(*chunks).zip(t4.evals.par_iter().zip(t8.evals.par_iter())).
+                        for_each(|(t, (t4, t8))| t[k] = *t4 + t8);
+                }
+                Evaluations::<F, D<F>>::from_vec_and_domain(t, self.domain.d8).interpolate()
+            }
         }
     }
 }
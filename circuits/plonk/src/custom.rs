@@ -0,0 +1,120 @@
+/*****************************************************************************************************************
+
+This source file implements the Plonk custom gate interface.
+
+A custom gate is defined outside of this crate by implementing the CustomGate trait and adding the
+implementation to a GateRegistry. The registry is handed to the constraint system, which then takes care
+of the selector polynomials, witness verification, quotient contribution and linearization of each
+registered gate without any changes to GateType, ConstraintSystem or the protocol crates.
+
+The registry only covers the gates added on top of the built-in ones. The generic, Poseidon, EC addition,
+variable base and endomorphism optimised scalar multiplication gates keep their GateType variants, their
+selector polynomials and commitments being keyed by the Selector enum in ConstraintSystem and VerifierIndex.
+The Selector order is the order of their linearization scalars, the serialized verifier indexes and the
+in-circuit verifier depending on that fixed layout. Their powers of alpha come first, the registered gates
+consuming the ones that follow.
+
+Every custom gate owns one or more selector polynomials. The values of the selector polynomials at
+the rows of the gate are computed by CustomGate::selector, all other rows are zero. The constraint has
+to be linear in the selector polynomials so that the verifier can compute the commitment to the
+linearization polynomial as
+
+    sum(selector_comm[i] * scalars[i])
+
+*****************************************************************************************************************/
+
+use algebra::FftField;
+use ff_fft::{Evaluations, Radix2EvaluationDomain as D};
+use crate::polynomial::WitnessShifts;
+use crate::scalars::ProofEvaluations;
+use crate::gate::CircuitGate;
+use std::sync::Arc;
+
+pub trait CustomGate<F: FftField>: Send + Sync
+{
+    // number of the selector polynomials of the gate
+    fn selectors(&self) -> usize {1}
+
+    // values of the selector polynomials at the row of the gate
+    //     gate: circuit gate instance of this custom gate
+    //     RETURN: vector of selectors() values
+    fn selector(&self, _gate: &CircuitGate<F>) -> Vec<F> {vec![F::one()]}
+
+    // number of the powers of alpha the constraint consumes
+    fn alphas(&self) -> usize;
+
+    // total degree of the constraint in the witness and selector polynomials
+    fn degree(&self) -> usize;
+
+    // This function verifies the consistency of the wire
+    // assignements (witness) against the constraint
+    //     this: gate instance
+    //     next: next gate of the circuit
+    //     witness: wire assignement witness
+    //     RETURN: verification status
+    fn verify(&self, this: &CircuitGate<F>, next: &CircuitGate<F>, witness: &Vec<F>) -> bool;
+
+    // This function computes the quotient polynomial contribution of the constraint
+    //     polys: witness evaluations over domain d4 if degree() <= 3, over domain d8 otherwise, the witness
+    //         polynomials being of degree n+1 when blinded with the multiples of the vanishing polynomial
+    //     selectors: selector evaluations over the same domain as polys
+    //     alpha: alphas() powers of alpha
+    //     RETURN: constraint evaluations
+    fn quot(&self, polys: &WitnessShifts<F>, selectors: &[Evaluations<F, D<F>>], alpha: &[F]) -> Evaluations<F, D<F>>;
+
+    // This function computes the linearization scalars of the constraint
+    //     evals: witness evaluations at zeta and zeta*omega
+    //     alpha: alphas() powers of alpha
+    //     RETURN: vector of selectors() scalars, one per selector polynomial
+    fn scalars(&self, evals: &Vec<ProofEvaluations<F>>, alpha: &[F]) -> Vec<F>;
+}
+
+#[derive(Clone)]
+pub struct GateRegistry<F: FftField>
+{
+    pub gates: Vec<(String, Arc<dyn CustomGate<F>>)>,   // registered gates, the gate id is the position
+}
+
+impl<F: FftField> Default for GateRegistry<F>
+{
+    fn default() -> Self {Self::new()}
+}
+
+impl<F: FftField> GateRegistry<F>
+{
+    pub fn new() -> Self
+    {
+        GateRegistry {gates: Vec::new()}
+    }
+
+    // This function registers the custom gate
+    //     name: unique name of the gate
+    //     gate: gate implementation
+    //     RETURN: gate id to be used with CircuitGate::create_custom
+    pub fn register(&mut self, name: &str, gate: Arc<dyn CustomGate<F>>) -> Option<usize>
+    {
+        if self.id(name).is_some() {return None}
+        self.gates.push((name.to_string(), gate));
+        Some(self.gates.len() - 1)
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize>
+    {
+        self.gates.iter().position(|(n, _)| n == name)
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Arc<dyn CustomGate<F>>>
+    {
+        self.gates.get(id).map(|(_, g)| g)
+    }
+
+    pub fn len(&self) -> usize {self.gates.len()}
+
+    pub fn is_empty(&self) -> bool {self.gates.is_empty()}
+
+    // total number of the powers of alpha consumed by the registered gates
+    pub fn alphas(&self) -> usize
+    {
+        self.gates.iter().map(|(_, g)| g.alphas()).sum()
+    }
+}
@@ -0,0 +1,155 @@
+/*****************************************************************************************************************
+
+This source file implements the strict field element bit decomposition gadget.
+
+The decomposition is constrained by the unpack custom gate, one gate per bit, processing the bits from
+the most significant one. The gate wires are l = acc, r = b, o = eq, where acc accumulates the already
+processed bits and eq indicates whether the processed bits are equal to the bits of the field modulus.
+With q the bit of the modulus at the position of the gate, the constraints are
+
+    b * b - b = 0
+    acc_next - 2 * acc - b = 0
+    eq_next - eq * (1 - q + q * b) = 0
+    (1 - q) * eq * b = 0
+
+The decomposition starts with acc = 0, eq = 1 and ends with acc = x, eq = 0, which asserts that the
+bits represent an integer smaller than the modulus, so that the decomposition is unique.
+
+The gate has two selector polynomials, the gate indicator and the modulus bit, which keeps the
+constraints linear in the selectors, as required by the custom gate interface.
+
+*****************************************************************************************************************/
+
+use algebra::{FftField, PrimeField, BigInteger, FpParameters};
+use ff_fft::{Evaluations, Radix2EvaluationDomain as D};
+use oracle::utils::EvalUtils;
+use crate::polynomial::WitnessShifts;
+use crate::scalars::ProofEvaluations;
+use crate::custom::CustomGate;
+use crate::gate::{CircuitGate, GateType};
+use super::builder::{CircuitBuilder, Var};
+use std::sync::Arc;
+
+// length of the transcript challenges in bits
+pub const CHALLENGE_BITS: usize = 128;
+
+pub struct UnpackGate;
+
+impl<F: FftField> CustomGate<F> for UnpackGate
+{
+    fn selectors(&self) -> usize {2}
+    fn selector(&self, gate: &CircuitGate<F>) -> Vec<F> {vec![F::one(), gate.c[0]]}
+    fn alphas(&self) -> usize {4}
+    fn degree(&self) -> usize {3}
+
+    fn verify(&self, this: &CircuitGate<F>, next: &CircuitGate<F>, witness: &Vec<F>) -> bool
+    {
+        let (acc, b, eq, q) = (witness[this.wires.l.0], witness[this.wires.r.0], witness[this.wires.o.0], this.c[0]);
+
+        b.square() == b
+        &&
+        witness[next.wires.l.0] == acc.double() + &b
+        &&
+        witness[next.wires.o.0] == eq * &(F::one() - &q + &(q * &b))
+        &&
+        (F::one() - &q) * &eq * &b == F::zero()
+    }
+
+    fn quot(&self, polys: &WitnessShifts<F>, selectors: &[Evaluations<F, D<F>>], alpha: &[F]) -> Evaluations<F, D<F>>
+    {
+        let (acc, b, eq) = (&polys.this.l, &polys.this.r, &polys.this.o);
+        let eqb = eq * b;
+
+        // constraints scaled by the gate indicator
+        let s =
+            &(&(&(&(b * b) - b).scale(alpha[0])
+            +
+            &(&(&polys.next.l - &acc.scale((2 as u64).into())) - b).scale(alpha[1]))
+            +
+            &(&polys.next.o - eq).scale(alpha[2]))
+            +
+            &eqb.scale(alpha[3]);
+
+        // constraints scaled by the modulus bit
+        let q = &(eq - &eqb).scale(alpha[2]) - &eqb.scale(alpha[3]);
+
+        &(&s * &selectors[0]) + &(&q * &selectors[1])
+    }
+
+    fn scalars(&self, evals: &Vec<ProofEvaluations<F>>, alpha: &[F]) -> Vec<F>
+    {
+        let (acc, b, eq) = (evals[0].l, evals[0].r, evals[0].o);
+        let eqb = eq * &b;
+        vec!
+        [
+            (b.square() - &b) * &alpha[0] +
+                &((evals[1].l - &acc.double() - &b) * &alpha[1]) +
+                &((evals[1].o - &eq) * &alpha[2]) +
+                &(eqb * &alpha[3]),
+            (eq - &eqb) * &alpha[2] - &(eqb * &alpha[3])
+        ]
+    }
+}
+
+// This function decomposes the variable into bits
+//     x: variable to decompose
+//     RETURN: bits in little endian order and the accumulators of the bits processed so far,
+//             acc[i] packs the i most significant bits
+fn decompose<F: FftField + PrimeField>(cb: &mut CircuitBuilder<F>, x: Var) -> (Vec<Var>, Vec<Var>)
+{
+    let m = F::Params::MODULUS_BITS as usize;
+    let id = cb.custom_gate("unpack", Arc::new(UnpackGate));
+    let zero = cb.constant(F::zero());
+    let one = cb.constant(F::one());
+
+    // little endian bits of x and the modulus
+    let xb = cb.value(x).into_repr().to_bits().into_iter().rev().collect::<Vec<_>>();
+    let qb = F::Params::MODULUS.to_bits().into_iter().rev().collect::<Vec<_>>();
+
+    let (mut acc, mut eq) = (F::zero(), F::one());
+    let mut bits = vec![zero; m];
+    let mut accs = Vec::with_capacity(m + 1);
+    for k in (0..m).rev()
+    {
+        let (b, q) = (if xb[k] {F::one()} else {F::zero()}, if qb[k] {F::one()} else {F::zero()});
+        let v = cb.custom_row(id, vec![q], [acc, b, eq]);
+        if k == m - 1
+        {
+            cb.copy(v[0], zero);
+            cb.copy(v[2], one);
+        }
+        bits[k] = v[1];
+        accs.push(v[0]);
+
+        acc = acc.double() + &b;
+        eq = eq * &(F::one() - &q + &(q * &b));
+    }
+
+    // the decomposition has to pack into x and to be smaller than the modulus
+    let v = cb.row(GateType::Generic, vec![F::zero(), F::zero(), F::one(), F::zero(), F::zero()], [acc, F::zero(), eq]);
+    cb.copy(v[0], x);
+    accs.push(v[0]);
+
+    (bits, accs)
+}
+
+// This function computes the strict bit decomposition of the variable
+//     RETURN: MODULUS_BITS bits in little endian order
+pub fn unpack<F: FftField + PrimeField>(cb: &mut CircuitBuilder<F>, x: Var) -> Vec<Var>
+{
+    decompose(cb, x).0
+}
+
+// This function computes the k least significant bits of the variable
+//     RETURN: the k least significant bits packed into a variable and the bits in little endian order
+pub fn low_bits<F: FftField + PrimeField>(cb: &mut CircuitBuilder<F>, x: Var, k: usize) -> (Var, Vec<Var>)
+{
+    let (mut bits, accs) = decompose(cb, x);
+    let m = bits.len();
+    bits.truncate(k);
+
+    // x - 2^k * (x >> k)
+    let two: F = (2 as u64).into();
+    let low = cb.linear(x, F::one(), accs[m - k], -two.pow(&[k as u64]), F::zero());
+    (low, bits)
+}
@@ -0,0 +1,54 @@
+/*****************************************************************************************************************
+
+This source file implements the inner product argument challenge polynomial gadget
+
+    b(x) = prod_{i=0}^{k-1} (1 + chal_i * x^{2^{k-1-i}})
+
+over the scalar field of the commitment curve, the same as commitment::b_poly. The evaluation of b
+is the scalar field part of the deferred inner product argument check of a proof, the commitment
+to b being the accumulated point sg of the proof. The challenges of the accumulator are constrained
+from the prechallenges the verifier gadget defers, chal_i = ScalarChallenge(prechallenge_i).to_field(endo_r),
+so that the evaluation is bound to the verified proof.
+
+*****************************************************************************************************************/
+
+use algebra::{FftField, PrimeField};
+use super::builder::{CircuitBuilder, Var};
+use super::endoscalar::to_field;
+
+// This function evaluates the challenge polynomial
+//     chals: inner product argument challenges
+//     x: evaluation point
+//     RETURN: b(x)
+pub fn b_poly<F: FftField>(cb: &mut CircuitBuilder<F>, chals: &[Var], x: Var) -> Var
+{
+    let k = chals.len();
+    let mut pow_twos = vec![x];
+    for i in 1..k
+    {
+        let p = pow_twos[i - 1];
+        pow_twos.push(cb.mul(p, p));
+    }
+
+    let mut res: Option<Var> = None;
+    for i in 0..k
+    {
+        // 1 + chal_i * x^{2^{k-1-i}}
+        let (c, p) = (chals[i], pow_twos[k - 1 - i]);
+        let term = F::one() + &(cb.value(c) * &cb.value(p));
+        let term = cb.generic(c, p, term, [F::zero(), F::zero(), -F::one(), F::one(), F::one()]);
+        res = Some(match res {None => term, Some(res) => cb.mul(res, term)});
+    }
+    match res {Some(res) => res, None => cb.constant(F::one())}
+}
+
+// This function evaluates the challenge polynomial of the inner product argument prechallenges
+//     endo: endomorphism coefficient endo_r of the circuit field
+//     prechallenges: inner product argument scalar challenges, as deferred by the verifier gadget
+//     x: evaluation point
+//     RETURN: b(x) for chal_i = ScalarChallenge(prechallenge_i).to_field(endo)
+pub fn b_poly_prechallenges<F: FftField + PrimeField>(cb: &mut CircuitBuilder<F>, endo: F, prechallenges: &[Var], x: Var) -> Var
+{
+    let chals = prechallenges.iter().map(|p| to_field(cb, endo, *p)).collect::<Vec<_>>();
+    b_poly(cb, &chals, x)
+}
@@ -0,0 +1,316 @@
+/*****************************************************************************************************************
+
+This source file implements the Plonk circuit builder used by the gadgets.
+
+The builder allocates circuit rows together with their witness values, so that every gadget computes
+its constraints and its witness in one pass. A variable is a single wire cell (row, column) of the
+circuit. Equality of variables is recorded as a copy constraint, the copy constraints are turned into
+the permutation cycles of the gate wires when the circuit is finalized. A copy constraint on distinct
+values does not stop the builder: it is recorded, see CircuitBuilder::unsatisfied, and the finalized
+witness violates the permutation, so that the gadgets can be run on the invalid inputs as well.
+
+Public input rows can be allocated at any point, on finalization they are moved in front of all the
+other rows, the relative order of the other rows is preserved so that the gates referencing the next
+row remain valid. The circuit is padded to have at least three spare rows at the end of the domain,
+as required by the zero-knowledge rows of the permutation argument.
+
+*****************************************************************************************************************/
+
+use algebra::{Field, FftField, SquareRootField};
+use oracle::poseidon::ArithmeticSpongeParams;
+use crate::gate::{CircuitGate, GateType};
+use crate::wires::GateWires;
+use crate::custom::{CustomGate, GateRegistry};
+use crate::constraints::ConstraintSystem;
+use std::sync::Arc;
+
+// circuit variable, the wire cell index 3*row + column
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Var(pub usize);
+
+impl Var
+{
+    pub fn row(&self) -> usize {self.0 / 3}
+    pub fn col(&self) -> usize {self.0 % 3}
+}
+
+#[derive(Clone)]
+struct Row<F: FftField>
+{
+    typ: GateType,
+    custom: Option<usize>,
+    c: Vec<F>,
+    public: bool,
+}
+
+pub struct CircuitBuilder<F: FftField>
+{
+    rows: Vec<Row<F>>,
+    values: Vec<F>,                 // witness values of the cells
+    parent: Vec<usize>,             // copy constraint union-find forest over the cells
+    constants: Vec<(F, Var)>,       // allocated constants
+    advice: Option<(usize, usize)>, // advice row with free cells
+    unsatisfied: Option<(Var, Var)>,// first copy constraint on distinct values
+    pub registry: GateRegistry<F>,  // custom gates used by the gadgets
+}
+
+// finalized circuit
+pub struct Circuit<F: FftField>
+{
+    pub gates: Vec<CircuitGate<F>>,
+    pub witness: Vec<F>,
+    pub registry: GateRegistry<F>,
+    pub public: usize,
+}
+
+impl<F: FftField + SquareRootField> Circuit<F>
+{
+    // This function creates the constraint system of the circuit
+    pub fn constraint_system(&self, fr_sponge_params: ArithmeticSpongeParams<F>) -> Option<ConstraintSystem<F>>
+    {
+        ConstraintSystem::<F>::create_with_registry(self.gates.clone(), self.registry.clone(), fr_sponge_params, self.public)
+    }
+}
+
+impl<F: FftField> Default for CircuitBuilder<F>
+{
+    fn default() -> Self {Self::new()}
+}
+
+impl<F: FftField> CircuitBuilder<F>
+{
+    pub fn new() -> Self
+    {
+        CircuitBuilder
+        {
+            rows: Vec::new(),
+            values: Vec::new(),
+            parent: Vec::new(),
+            constants: Vec::new(),
+            advice: None,
+            unsatisfied: None,
+            registry: GateRegistry::new(),
+        }
+    }
+
+    // number of the allocated rows
+    pub fn rows(&self) -> usize {self.rows.len()}
+
+    // witness value of the variable
+    pub fn value(&self, v: Var) -> F {self.values[v.0]}
+
+    // This function registers the custom gate with the builder registry
+    //     RETURN: gate id, the same for all the calls with the same name
+    pub fn custom_gate(&mut self, name: &str, gate: Arc<dyn CustomGate<F>>) -> usize
+    {
+        match self.registry.id(name)
+        {
+            Some(id) => id,
+            None => self.registry.register(name, gate).unwrap()
+        }
+    }
+
+    // This function allocates a circuit row
+    //     typ: gate type
+    //     c: constraints vector
+    //     w: witness values of the l, r, o wires
+    //     RETURN: l, r, o variables
+    pub fn row(&mut self, typ: GateType, c: Vec<F>, w: [F; 3]) -> [Var; 3]
+    {
+        self.push(Row {typ, custom: None, c, public: false}, w)
+    }
+
+    // This function allocates a circuit row of the registered custom gate
+    pub fn custom_row(&mut self, id: usize, c: Vec<F>, w: [F; 3]) -> [Var; 3]
+    {
+        self.push(Row {typ: GateType::Custom, custom: Some(id), c, public: false}, w)
+    }
+
+    fn push(&mut self, row: Row<F>, w: [F; 3]) -> [Var; 3]
+    {
+        let i = self.rows.len();
+        self.rows.push(row);
+        self.values.extend(w.iter());
+        self.parent.extend([3*i, 3*i+1, 3*i+2].iter());
+        [Var(3*i), Var(3*i+1), Var(3*i+2)]
+    }
+
+    fn root(&mut self, mut x: usize) -> usize
+    {
+        while self.parent[x] != x
+        {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    // This function constrains the variables to be equal
+    pub fn copy(&mut self, a: Var, b: Var)
+    {
+        if self.values[a.0] != self.values[b.0] && self.unsatisfied.is_none() {self.unsatisfied = Some((a, b))}
+        let (a, b) = (self.root(a.0), self.root(b.0));
+        if a != b {self.parent[a.max(b)] = a.min(b)}
+    }
+
+    // This function returns the first copy constraint on distinct values
+    //     RETURN: constrained variables, none if all the copy constraints are satisfied
+    pub fn unsatisfied(&self) -> Option<(Var, Var)> {self.unsatisfied}
+
+    pub fn assert_eq(&mut self, a: Var, b: Var) {self.copy(a, b)}
+
+    // This function allocates an unconstrained variable
+    pub fn witness(&mut self, x: F) -> Var
+    {
+        match self.advice
+        {
+            Some((row, col)) if col < 3 =>
+            {
+                self.advice = Some((row, col + 1));
+                self.values[3*row + col] = x;
+                Var(3*row + col)
+            }
+            _ =>
+            {
+                let v = self.row(GateType::Zero, Vec::new(), [x, F::zero(), F::zero()]);
+                self.advice = Some((v[0].row(), 1));
+                v[0]
+            }
+        }
+    }
+
+    // This function allocates the public input variable equal to the variable
+    pub fn public(&mut self, v: Var) -> Var
+    {
+        let x = self.value(v);
+        let p = self.push
+        (
+            Row {typ: GateType::Generic, custom: None, c: vec![F::one(), F::zero(), F::zero(), F::zero(), F::zero()], public: true},
+            [x, F::zero(), F::zero()]
+        );
+        self.copy(p[0], v);
+        p[0]
+    }
+
+    // This function allocates generic gate constraining
+    //     ql*l + qr*r + qo*o + qm*l*r + qc = 0
+    // on the variables l, r and the output value o
+    //     RETURN: output variable
+    pub fn generic(&mut self, l: Var, r: Var, o: F, q: [F; 5]) -> Var
+    {
+        let (x, y) = (self.value(l), self.value(r));
+        let v = self.row(GateType::Generic, q.to_vec(), [x, y, o]);
+        self.copy(v[0], l);
+        self.copy(v[1], r);
+        v[2]
+    }
+
+    // This function allocates the constant variable
+    pub fn constant(&mut self, c: F) -> Var
+    {
+        if let Some((_, v)) = self.constants.iter().find(|(x, _)| *x == c) {return *v}
+        let v = self.row(GateType::Generic, vec![F::one(), F::zero(), F::zero(), F::zero(), -c], [c, F::zero(), F::zero()]);
+        self.constants.push((c, v[0]));
+        v[0]
+    }
+
+    // o = ca*a + cb*b + k
+    pub fn linear(&mut self, a: Var, ca: F, b: Var, cb: F, k: F) -> Var
+    {
+        let o = ca * &self.value(a) + &(cb * &self.value(b)) + &k;
+        self.generic(a, b, o, [ca, cb, -F::one(), F::zero(), k])
+    }
+
+    pub fn add(&mut self, a: Var, b: Var) -> Var {self.linear(a, F::one(), b, F::one(), F::zero())}
+    pub fn sub(&mut self, a: Var, b: Var) -> Var {self.linear(a, F::one(), b, -F::one(), F::zero())}
+    pub fn add_constant(&mut self, a: Var, k: F) -> Var {self.linear(a, F::one(), a, F::zero(), k)}
+    pub fn scale(&mut self, a: Var, k: F) -> Var {self.linear(a, k, a, F::zero(), F::zero())}
+
+    pub fn mul(&mut self, a: Var, b: Var) -> Var
+    {
+        let o = self.value(a) * &self.value(b);
+        self.generic(a, b, o, [F::zero(), F::zero(), -F::one(), F::one(), F::zero()])
+    }
+
+    // This function returns the inverse of the non-zero variable
+    pub fn inv(&mut self, a: Var) -> Var
+    {
+        let x = self.value(a);
+        let y = x.inverse().expect("inversion of zero");
+        let v = self.row(GateType::Generic, vec![F::zero(), F::zero(), F::zero(), F::one(), -F::one()], [x, y, F::zero()]);
+        self.copy(v[0], a);
+        v[1]
+    }
+
+    // a/b for non-zero b
+    pub fn div(&mut self, a: Var, b: Var) -> Var
+    {
+        let y = self.value(a) * &self.value(b).inverse().expect("division by zero");
+        let v = self.row(GateType::Generic, vec![F::zero(), F::zero(), -F::one(), F::one(), F::zero()], [y, self.value(b), self.value(a)]);
+        self.copy(v[1], b);
+        self.copy(v[2], a);
+        v[0]
+    }
+
+    // This function constrains the variable to be boolean
+    pub fn boolean(&mut self, a: Var)
+    {
+        let x = self.value(a);
+        self.generic(a, a, F::zero(), [-F::one(), F::zero(), F::zero(), F::one(), F::zero()]);
+        assert!(x.square() == x, "non-boolean value");
+    }
+
+    // This function constrains the variable to be zero
+    pub fn assert_zero(&mut self, a: Var)
+    {
+        let z = self.constant(F::zero());
+        self.copy(a, z);
+    }
+
+    // This function finalizes the circuit
+    //     RETURN: circuit gates, witness and custom gate registry
+    pub fn finalize(mut self) -> Circuit<F>
+    {
+        let public = self.rows.iter().filter(|r| r.public).count();
+        let n = (self.rows.len() + 3).next_power_of_two();
+
+        // public input rows go first
+        let mut order = (0..self.rows.len()).filter(|&i| self.rows[i].public).collect::<Vec<_>>();
+        order.extend((0..self.rows.len()).filter(|&i| !self.rows[i].public));
+        let mut position = vec![0; self.rows.len()];
+        order.iter().enumerate().for_each(|(j, &i)| position[i] = j);
+
+        // witness index of the cell
+        let index = |cell: usize| (cell % 3) * n + position[cell / 3];
+
+        // permutation cycles of the copy constraints
+        let mut cycles = std::collections::BTreeMap::<usize, Vec<usize>>::new();
+        for cell in 0..self.values.len()
+        {
+            let root = self.root(cell);
+            cycles.entry(root).or_insert_with(Vec::new).push(index(cell));
+        }
+        let mut perm = vec![0; 3*n];
+        (0..3*n).for_each(|i| perm[i] = i);
+        for cycle in cycles.values()
+        {
+            for (i, w) in cycle.iter().enumerate() {perm[*w] = cycle[(i + 1) % cycle.len()]}
+        }
+
+        let mut witness = vec![F::zero(); 3*n];
+        for cell in 0..self.values.len() {witness[index(cell)] = self.values[cell]}
+
+        let mut gates = order.iter().enumerate().map
+        (
+            |(j, &i)|
+            {
+                let row = &self.rows[i];
+                let wires = GateWires::wires((j, perm[j]), (n+j, perm[n+j]), (2*n+j, perm[2*n+j]));
+                CircuitGate {typ: row.typ.clone(), custom: row.custom, wires, c: row.c.clone()}
+            }
+        ).collect::<Vec<_>>();
+        (gates.len()..gates.len() + 3).for_each(|j| gates.push(CircuitGate::zero(GateWires::wires((j, j), (n+j, n+j), (2*n+j, 2*n+j)))));
+
+        Circuit {gates, witness, registry: self.registry, public}
+    }
+}
@@ -0,0 +1,157 @@
+/*****************************************************************************************************************
+
+This source file implements the elliptic curve gadgets over the circuit field, the curve being the
+short Weierstrass curve y^2 = x^3 + b with its base field equal to the circuit field.
+
+1. non-special point addition, constrained by the EC addition custom gates, the abscissas of the
+   summands being constrained distinct by the inverse of their difference
+2. point doubling, constrained by the generic gates
+3. scalar multiplication by the endomorphism mapped scalar challenge, constrained by the group
+   endomorphism optimised scalar multiplication custom gates
+4. curve membership of the points
+
+For the scalar challenge c with the bits c_i, ScalarChallenge::to_field computes the scalar
+
+    a * endo_r + b
+
+with a = b = 2 initially, then for i = 63..0 a and b are doubled, and +-1, depending on c_{2i}, is added
+to a if c_{2i+1} = 1, otherwise to b. Since [endo_r]T = (endo_q * xT, yT), the accumulator starts as
+
+    2 * (T + (endo_q * xT, yT))
+
+and every pair of bits is processed by four endomul gates computing
+
+    Acc = (Acc + Q) + Acc,  Q = ((1 + (endo_q - 1) * c_{2i+1}) * xT, (2 * c_{2i} - 1) * yT)
+
+so that the result is [to_field(c)]T. The endomorphism coefficient endo_q of the gadget has to be the
+one of the constraint system.
+
+*****************************************************************************************************************/
+
+use algebra::{Field, FftField};
+use crate::gate::GateType;
+use super::builder::{CircuitBuilder, Var};
+
+pub type Point = (Var, Var);
+
+// This function constrains the addition of points with distinct abscissas
+pub fn add<F: FftField>(cb: &mut CircuitBuilder<F>, p: Point, q: Point) -> Point
+{
+    // x2 - x1 has to be invertible, the addition gates not constraining the slope otherwise
+    let d = cb.sub(q.0, p.0);
+    cb.inv(d);
+
+    let (x1, y1, x2, y2) = (cb.value(p.0), cb.value(p.1), cb.value(q.0), cb.value(q.1));
+    let s = (y2 - &y1) / &(x2 - &x1);
+    let x3 = s.square() - &x1 - &x2;
+    let y3 = s * &(x1 - &x3) - &y1;
+
+    let a = cb.row(GateType::Add1, Vec::new(), [y1, y2, y3]);
+    let b = cb.row(GateType::Add2, Vec::new(), [x1, x2, x3]);
+    cb.copy(a[0], p.1);
+    cb.copy(a[1], q.1);
+    cb.copy(b[0], p.0);
+    cb.copy(b[1], q.0);
+    (b[2], a[2])
+}
+
+// This function computes y^2 - x^3 - b of the point
+fn curve_equation<F: FftField>(cb: &mut CircuitBuilder<F>, p: Point, b: F) -> Var
+{
+    let xx = cb.mul(p.0, p.0);
+    let xxx = cb.mul(xx, p.0);
+    let yy = cb.mul(p.1, p.1);
+    cb.linear(yy, F::one(), xxx, -F::one(), -b)
+}
+
+// This function constrains the point to be on the curve
+//     p: point
+//     b: curve coefficient b
+pub fn on_curve<F: FftField>(cb: &mut CircuitBuilder<F>, p: Point, b: F)
+{
+    let e = curve_equation(cb, p, b);
+    cb.assert_zero(e);
+}
+
+// This function constrains the point to be on the curve or to be the dummy point (0, 0) the sponge
+// absorbs in place of the missing commitment: x * e = y * e = 0 for e = y^2 - x^3 - b, the only off
+// curve solution being x = y = 0
+//     p: point
+//     b: curve coefficient b
+pub fn on_curve_or_dummy<F: FftField>(cb: &mut CircuitBuilder<F>, p: Point, b: F)
+{
+    let e = curve_equation(cb, p, b);
+    let (xe, ye) = (cb.mul(p.0, e), cb.mul(p.1, e));
+    cb.assert_zero(xe);
+    cb.assert_zero(ye);
+}
+
+// This function constrains the point doubling
+pub fn double<F: FftField>(cb: &mut CircuitBuilder<F>, p: Point) -> Point
+{
+    let (x, y) = p;
+    let xx = cb.mul(x, x);
+
+    // 2 * s * y = 3 * x^2
+    let (two, three): (F, F) = ((2 as u64).into(), (3 as u64).into());
+    let s = cb.value(xx) * &three / &cb.value(y).double();
+    let v = cb.row(GateType::Generic, vec![F::zero(), F::zero(), -three, two, F::zero()], [s, cb.value(y), cb.value(xx)]);
+    cb.copy(v[1], y);
+    cb.copy(v[2], xx);
+    let s = v[0];
+
+    let ss = cb.mul(s, s);
+    let x3 = cb.linear(ss, F::one(), x, -two, F::zero());
+    let t = cb.sub(x, x3);
+    let u = cb.mul(s, t);
+    let y3 = cb.sub(u, y);
+    (x3, y3)
+}
+
+// This function constrains the scalar multiplication by the scalar challenge
+//     endo: endomorphism coefficient endo_q of the circuit field
+//     t: point to multiply
+//     bits: scalar challenge bits in little endian order, even length
+//     RETURN: [to_field(c)]T
+pub fn endo_scale<F: FftField>(cb: &mut CircuitBuilder<F>, endo: F, t: Point, bits: &[Var]) -> Point
+{
+    let (xt, yt) = (cb.value(t.0), cb.value(t.1));
+
+    // 2 * (T + phi(T))
+    let phi = (cb.scale(t.0, endo), t.1);
+    let sum = add(cb, t, phi);
+    let mut acc = double(cb, sum);
+
+    for i in (0..bits.len() / 2).rev()
+    {
+        let (b2i, b2i1) = (cb.value(bits[2*i]), cb.value(bits[2*i+1]));
+        let (xp, yp) = (cb.value(acc.0), cb.value(acc.1));
+
+        let xq = (F::one() + &((endo - &F::one()) * &b2i1)) * &xt;
+        let yq = if b2i == F::one() {yt} else {-yt};
+        let s1 = (yp - &yq) / &(xp - &xq);
+
+        // R = P + Q, S = R + P
+        let xr = s1.square() - &xp - &xq;
+        let yr = s1 * &(xp - &xr) - &yp;
+        let s2 = (yp - &yr) / &(xp - &xr);
+        let xs = s2.square() - &xr - &xp;
+        let ys = s2 * &(xr - &xs) - &yr;
+
+        let a = cb.row(GateType::Endomul1, Vec::new(), [b2i1, xt, F::zero()]);
+        let b = cb.row(GateType::Endomul2, Vec::new(), [b2i, xq, yt]);
+        let c = cb.row(GateType::Endomul3, Vec::new(), [xp, s1, yp]);
+        let d = cb.row(GateType::Endomul4, Vec::new(), [xs, xq, ys]);
+
+        cb.copy(a[0], bits[2*i+1]);
+        cb.copy(a[1], t.0);
+        cb.copy(b[0], bits[2*i]);
+        cb.copy(b[2], t.1);
+        cb.copy(c[0], acc.0);
+        cb.copy(c[2], acc.1);
+        cb.copy(d[1], b[1]);
+
+        acc = (d[0], d[2]);
+    }
+    acc
+}
@@ -0,0 +1,144 @@
+/*****************************************************************************************************************
+
+This source file implements the scalar challenge to endomorphism scalar conversion gadget, the in-circuit
+counterpart of ScalarChallenge::to_field.
+
+For the challenge c with the bits c_i, i < 128, ScalarChallenge::to_field computes a * endo + b with
+a = b = 2 initially, then for i = 63..0 a and b are doubled, and +-1, depending on c_{2i}, is added to a
+if c_{2i+1} = 1, otherwise to b.
+
+Every step is constrained by the endoscalar custom gate. The gate wires are l = a, r = b, o = n, where
+n = 2 * c_{2i+1} + c_{2i} is the crumb of the step. With
+
+    da(n) = n * (n - 1) * (4 * n - 11) / 6,     da(0) = 0,  da(1) = 0,  da(2) = -1, da(3) = 1
+    db(n) = (n - 2) * (n - 3) * (4 * n - 1) / 6, db(0) = -1, db(1) = 1,  db(2) = 0,  db(3) = 0
+
+the constraints, scaled by 6, are
+
+    n * (n - 1) * (n - 2) * (n - 3) = 0
+    6 * (a_next - 2 * a) - (4 * n^3 - 15 * n^2 + 11 * n) = 0
+    6 * (b_next - 2 * b) - (4 * n^3 - 21 * n^2 + 29 * n - 6) = 0
+
+The steps are followed by the generic gate computing a * endo + b. The crumbs are packed back into the
+challenge by the generic gates, which also asserts that the challenge fits into 128 bits.
+
+*****************************************************************************************************************/
+
+use algebra::{FftField, PrimeField, BigInteger};
+use ff_fft::{Evaluations, Radix2EvaluationDomain as D};
+use oracle::utils::EvalUtils;
+use crate::polynomial::WitnessShifts;
+use crate::scalars::ProofEvaluations;
+use crate::custom::CustomGate;
+use crate::gate::{CircuitGate, GateType};
+use super::builder::{CircuitBuilder, Var};
+use super::bits::CHALLENGE_BITS;
+use std::sync::Arc;
+
+pub struct EndoScalarGate;
+
+impl<F: FftField> CustomGate<F> for EndoScalarGate
+{
+    fn alphas(&self) -> usize {3}
+    fn degree(&self) -> usize {5}
+
+    fn verify(&self, this: &CircuitGate<F>, next: &CircuitGate<F>, witness: &Vec<F>) -> bool
+    {
+        let (a, b, n) = (witness[this.wires.l.0], witness[this.wires.r.0], witness[this.wires.o.0]);
+        let (a_next, b_next) = (witness[next.wires.l.0], witness[next.wires.r.0]);
+        let [crumb, da, db] = polys(n);
+
+        crumb == F::zero()
+        &&
+        (a_next - &a.double()) * &six::<F>() == da
+        &&
+        (b_next - &b.double()) * &six::<F>() == db
+    }
+
+    fn quot(&self, polys: &WitnessShifts<F>, selectors: &[Evaluations<F, D<F>>], alpha: &[F]) -> Evaluations<F, D<F>>
+    {
+        let (a, b, n) = (&polys.this.l, &polys.this.r, &polys.this.o);
+        let c = |x: u64| -> F {x.into()};
+        let (n2, n3) = (n.square(), n.pow(3));
+        let n4 = n2.square();
+
+        // n^4 - 6n^3 + 11n^2 - 6n
+        let crumb = &(&(&n4 - &n3.scale(c(6))) + &n2.scale(c(11))) - &n.scale(c(6));
+        // 6(a_next - 2a) - 4n^3 + 15n^2 - 11n
+        let da = &(&(&(&polys.next.l - &a.scale(c(2))).scale(c(6)) - &n3.scale(c(4))) + &n2.scale(c(15))) - &n.scale(c(11));
+        // 6(b_next - 2b) - 4n^3 + 21n^2 - 29n, the constant 6 is added below
+        let db = &(&(&(&polys.next.r - &b.scale(c(2))).scale(c(6)) - &n3.scale(c(4))) + &n2.scale(c(21))) - &n.scale(c(29));
+
+        &(&(&(&crumb.scale(alpha[0]) + &da.scale(alpha[1])) + &db.scale(alpha[2])) * &selectors[0])
+            + &selectors[0].scale(c(6) * &alpha[2])
+    }
+
+    fn scalars(&self, evals: &Vec<ProofEvaluations<F>>, alpha: &[F]) -> Vec<F>
+    {
+        let (a, b, n) = (evals[0].l, evals[0].r, evals[0].o);
+        let [crumb, da, db] = polys(n);
+        vec!
+        [
+            crumb * &alpha[0] +
+                &(((evals[1].l - &a.double()) * &six::<F>() - &da) * &alpha[1]) +
+                &(((evals[1].r - &b.double()) * &six::<F>() - &db) * &alpha[2])
+        ]
+    }
+}
+
+fn six<F: FftField>() -> F {(6 as u64).into()}
+
+// This function evaluates the crumb constraint and the scaled a, b increments at the crumb
+fn polys<F: FftField>(n: F) -> [F; 3]
+{
+    let c = |x: u64| -> F {x.into()};
+    let (n2, n3) = (n.square(), n.square() * &n);
+    [
+        n2.square() - &(n3 * &c(6)) + &(n2 * &c(11)) - &(n * &c(6)),
+        n3 * &c(4) - &(n2 * &c(15)) + &(n * &c(11)),
+        n3 * &c(4) - &(n2 * &c(21)) + &(n * &c(29)) - &c(6),
+    ]
+}
+
+// This function constrains the conversion of the scalar challenge into the endomorphism scalar
+//     endo: endomorphism coefficient endo_r of the circuit field
+//     x: scalar challenge of CHALLENGE_BITS bits
+//     RETURN: ScalarChallenge(x).to_field(endo)
+pub fn to_field<F: FftField + PrimeField>(cb: &mut CircuitBuilder<F>, endo: F, x: Var) -> Var
+{
+    let id = cb.custom_gate("endoscalar", Arc::new(EndoScalarGate));
+    let two = cb.constant((2 as u64).into());
+
+    let repr = cb.value(x).into_repr();
+    let bits = repr.to_bits().into_iter().rev().collect::<Vec<_>>();
+    assert!(bits[CHALLENGE_BITS..].iter().all(|b| !b), "scalar challenge too long");
+
+    // the step rows have to be contiguous and followed by the output row
+    let (mut a, mut b) = (cb.value(two), cb.value(two));
+    let mut crumbs = Vec::with_capacity(CHALLENGE_BITS / 2);
+    for i in (0..CHALLENGE_BITS / 2).rev()
+    {
+        let (lo, hi) = (bits[2*i], bits[2*i+1]);
+        let n: F = ((2 * hi as u64) + lo as u64).into();
+        let v = cb.custom_row(id, Vec::new(), [a, b, n]);
+        if i == CHALLENGE_BITS / 2 - 1
+        {
+            cb.copy(v[0], two);
+            cb.copy(v[1], two);
+        }
+        crumbs.push(v[2]);
+
+        a.double_in_place();
+        b.double_in_place();
+        let s = if lo {F::one()} else {-F::one()};
+        if hi {a += &s} else {b += &s}
+    }
+    let v = cb.row(GateType::Generic, vec![endo, F::one(), -F::one(), F::zero(), F::zero()], [a, b, a * &endo + &b]);
+
+    // pack the crumbs into the challenge
+    let four: F = (4 as u64).into();
+    let packed = crumbs[1..].iter().fold(crumbs[0], |acc, n| cb.linear(acc, four, *n, F::one(), F::zero()));
+    cb.assert_eq(packed, x);
+
+    v[2]
+}
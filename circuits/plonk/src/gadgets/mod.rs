@@ -0,0 +1,17 @@
+/*****************************************************************************************************************
+
+This source file implements the Plonk circuit gadgets.
+
+The gadgets build the circuit and its witness together, on top of the CircuitBuilder. They are
+the building blocks of the in-circuit verifier of the dlog Plonk proofs used for the recursive
+proof composition over a cycle of curves.
+
+*****************************************************************************************************************/
+
+pub mod builder;
+pub mod poseidon;
+pub mod bits;
+pub mod endo;
+pub mod endoscalar;
+pub mod bpoly;
+pub mod verifier;
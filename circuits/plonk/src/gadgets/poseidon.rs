@@ -0,0 +1,116 @@
+/*****************************************************************************************************************
+
+This source file implements the Poseidon sponge gadget.
+
+The gadget mirrors ArithmeticSponge with PlonkSpongeConstants state machine, so that the in-circuit
+transcript squeezes exactly the same values as the native one. The permutation is constrained by the
+Poseidon custom gates: the first round constant is added by the generic gates, then ROUNDS_FULL
+Poseidon gates are followed by the zero gate holding the permuted state.
+
+The Poseidon gates use the sponge parameters of the constraint system, the circuit has to be compiled
+with the same parameters as the ones given to the gadget.
+
+*****************************************************************************************************************/
+
+use algebra::FftField;
+use oracle::poseidon::{ArithmeticSponge, ArithmeticSpongeParams, Sponge, SpongeConstants, SpongeState, PlonkSpongeConstants as SC};
+use crate::gate::GateType;
+use super::builder::{CircuitBuilder, Var};
+
+#[derive(Clone)]
+pub struct SpongeGadget<F: FftField>
+{
+    pub params: ArithmeticSpongeParams<F>,
+    pub sponge_state: SpongeState,
+    pub state: Vec<Var>,
+}
+
+impl<F: FftField> SpongeGadget<F>
+{
+    pub fn new(cb: &mut CircuitBuilder<F>, params: ArithmeticSpongeParams<F>) -> Self
+    {
+        let zero = cb.constant(F::zero());
+        SpongeGadget
+        {
+            params,
+            sponge_state: SpongeState::Absorbed(0),
+            state: vec![zero; SC::SPONGE_WIDTH],
+        }
+    }
+
+    // This function constrains the Poseidon permutation of the sponge state
+    pub fn permute(&mut self, cb: &mut CircuitBuilder<F>)
+    {
+        let rc = &self.params.round_constants;
+        let state = self.state.iter().zip(rc[0].iter()).map(|(s, c)| cb.add_constant(*s, *c)).collect::<Vec<_>>();
+
+        let mut sponge = ArithmeticSponge::<F, SC>::new();
+        sponge.state = state.iter().map(|s| cb.value(*s)).collect();
+
+        for j in 0..SC::ROUNDS_FULL
+        {
+            let w = [sponge.state[0], sponge.state[1], sponge.state[2]];
+            let v = cb.row(GateType::Poseidon, vec![rc[j+1][0], rc[j+1][1], rc[j+1][2]], w);
+            if j == 0 {(0..SC::SPONGE_WIDTH).for_each(|i| cb.copy(v[i], state[i]))}
+            sponge.full_round(j, &self.params);
+        }
+        let v = cb.row(GateType::Zero, Vec::new(), [sponge.state[0], sponge.state[1], sponge.state[2]]);
+        self.state = v.to_vec();
+    }
+
+    pub fn absorb(&mut self, cb: &mut CircuitBuilder<F>, x: &[Var])
+    {
+        for x in x.iter()
+        {
+            match self.sponge_state
+            {
+                SpongeState::Absorbed(n) =>
+                {
+                    if n == SC::SPONGE_RATE
+                    {
+                        self.permute(cb);
+                        self.sponge_state = SpongeState::Absorbed(1);
+                        self.state[0] = cb.add(self.state[0], *x);
+                    }
+                    else
+                    {
+                        self.sponge_state = SpongeState::Absorbed(n + 1);
+                        self.state[n] = cb.add(self.state[n], *x);
+                    }
+                }
+                SpongeState::Squeezed(_) =>
+                {
+                    self.state[0] = cb.add(self.state[0], *x);
+                    self.sponge_state = SpongeState::Absorbed(1);
+                }
+            }
+        }
+    }
+
+    pub fn squeeze(&mut self, cb: &mut CircuitBuilder<F>) -> Var
+    {
+        match self.sponge_state
+        {
+            SpongeState::Squeezed(n) =>
+            {
+                if n == SC::SPONGE_RATE
+                {
+                    self.permute(cb);
+                    self.sponge_state = SpongeState::Squeezed(1);
+                    self.state[0]
+                }
+                else
+                {
+                    self.sponge_state = SpongeState::Squeezed(n + 1);
+                    self.state[n]
+                }
+            }
+            SpongeState::Absorbed(_) =>
+            {
+                self.permute(cb);
+                self.sponge_state = SpongeState::Squeezed(1);
+                self.state[0]
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+/*****************************************************************************************************************
+
+This source file implements the in-circuit verifier gadget for the dlog Plonk proofs.
+
+The proof is created over the curve whose base field is the circuit field, that is the other curve of
+the cycle. The gadget performs the part of the verification which is native to the circuit field:
+
+1. constrains the proof points to be on the curve, the quotient commitment points being allowed to be
+   the dummy point (0, 0) of the padding as well
+2. replays the Fq-sponge transcript of ProverProof::oracles, squeezing beta, gamma, the alpha and zeta
+   scalar challenges and the digest passed to the Fr-sponge
+3. replays the opening proof transcript of SRS::verify, squeezing the group map input, the inner
+   product argument prechallenges and the final scalar challenge
+4. folds the inner product argument L and R commitments
+
+    sum_i [chal_i^{-1}]L_i + [chal_i]R_i
+
+   with chal_i = ScalarChallenge(prechallenge_i).to_field(endo_r), the scalar multiplications being
+   constrained by the endomorphism optimised gates. The inverse challenge scaling is checked as
+   [chal_i]([chal_i^{-1}]L_i) = L_i with the advice point [chal_i^{-1}]L_i.
+
+All the checks which are native to the scalar field of the curve, the public input commitment, the
+combined inner product, the linearization and the final multi-scalar multiplication of the opening
+proof, are deferred: the gadget returns the values needed to perform them. In particular the
+prechallenges and the point sg of the proof form the accumulator which is checked by passing them
+as the previous challenges of a proof over the curve (see ProverProof::create), so that the check
+is amortized into the inner product argument of that proof. The circuit of that proof has to evaluate
+the challenge polynomial of the accumulator from the deferred prechallenges, see
+bpoly::b_poly_prechallenges.
+
+*****************************************************************************************************************/
+
+use algebra::{FftField, PrimeField};
+use oracle::poseidon::ArithmeticSpongeParams;
+use super::builder::{CircuitBuilder, Var};
+use super::poseidon::SpongeGadget;
+use super::bits::{low_bits, CHALLENGE_BITS};
+use super::endo::{Point, add, endo_scale, on_curve, on_curve_or_dummy};
+
+// dlog Plonk proof as seen by the circuit over the base field of the proof curve
+#[derive(Clone)]
+pub struct ProofInput<F>
+{
+    pub p_comm: Vec<(F, F)>,                    // public input commitment
+    pub l_comm: Vec<(F, F)>,                    // l wire commitment
+    pub r_comm: Vec<(F, F)>,                    // r wire commitment
+    pub o_comm: Vec<(F, F)>,                    // o wire commitment
+    pub z_comm: Vec<(F, F)>,                    // permutation commitment
+    pub t_comm: Vec<(F, F)>,                    // quotient commitment padded with the dummy points, shifted part last
+    pub combined_inner_product: Vec<F>,         // shifted combined inner product, as absorbed by FqSponge::absorb_fr
+    pub lr: Vec<((F, F), (F, F))>,              // inner product argument L & R commitments
+    pub lr_inv: Vec<(F, F)>,                    // advice [chal_i^{-1}]L_i
+    pub delta: (F, F),                          // opening proof delta
+    pub sg: (F, F),                             // opening proof challenge polynomial commitment
+}
+
+// values of the verification deferred to the other curve of the cycle
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deferred<V>
+{
+    pub beta: V,                                // permutation challenges
+    pub gamma: V,
+    pub alpha_chal: V,                          // alpha scalar challenge
+    pub zeta_chal: V,                           // zeta scalar challenge
+    pub digest: V,                              // Fq-sponge digest
+    pub combined_inner_product: Vec<V>,         // combined inner product advice
+    pub u: V,                                   // group map input of the opening proof point U
+    pub prechallenges: Vec<V>,                  // inner product argument scalar challenges
+    pub c: V,                                   // opening proof scalar challenge
+    pub folded: (V, V),                         // folded inner product argument commitments
+    pub sg: (V, V),                             // challenge polynomial commitment
+    pub p_comm: Vec<(V, V)>,                    // public input commitment advice
+}
+
+impl<V: Clone> Deferred<V>
+{
+    pub fn map<W, M: FnMut(&V) -> W>(&self, mut f: M) -> Deferred<W>
+    {
+        Deferred
+        {
+            beta: f(&self.beta),
+            gamma: f(&self.gamma),
+            alpha_chal: f(&self.alpha_chal),
+            zeta_chal: f(&self.zeta_chal),
+            digest: f(&self.digest),
+            combined_inner_product: self.combined_inner_product.iter().map(|x| f(x)).collect(),
+            u: f(&self.u),
+            prechallenges: self.prechallenges.iter().map(|x| f(x)).collect(),
+            c: f(&self.c),
+            folded: (f(&self.folded.0), f(&self.folded.1)),
+            sg: (f(&self.sg.0), f(&self.sg.1)),
+            p_comm: self.p_comm.iter().map(|(x, y)| (f(x), f(y))).collect(),
+        }
+    }
+
+    // deferred values in the order of the public inputs of the verifier circuit
+    pub fn to_vec(&self) -> Vec<V>
+    {
+        let mut v = vec![self.beta.clone(), self.gamma.clone(), self.alpha_chal.clone(), self.zeta_chal.clone(), self.digest.clone()];
+        v.extend(self.combined_inner_product.iter().cloned());
+        v.push(self.u.clone());
+        v.extend(self.prechallenges.iter().cloned());
+        v.extend(vec![self.c.clone(), self.folded.0.clone(), self.folded.1.clone(), self.sg.0.clone(), self.sg.1.clone()]);
+        self.p_comm.iter().for_each(|(x, y)| {v.push(x.clone()); v.push(y.clone())});
+        v
+    }
+}
+
+// in-circuit counterpart of DefaultFqSponge
+#[derive(Clone)]
+pub struct FqSpongeGadget<F: FftField>
+{
+    pub sponge: SpongeGadget<F>,
+}
+
+impl<F: FftField + PrimeField> FqSpongeGadget<F>
+{
+    pub fn new(cb: &mut CircuitBuilder<F>, params: ArithmeticSpongeParams<F>) -> Self
+    {
+        FqSpongeGadget {sponge: SpongeGadget::new(cb, params)}
+    }
+
+    pub fn absorb(&mut self, cb: &mut CircuitBuilder<F>, x: &[Var])
+    {
+        self.sponge.absorb(cb, x)
+    }
+
+    pub fn absorb_g(&mut self, cb: &mut CircuitBuilder<F>, g: &[Point])
+    {
+        for (x, y) in g.iter() {self.sponge.absorb(cb, &[*x, *y])}
+    }
+
+    // This function squeezes the scalar challenge
+    //     RETURN: the challenge and its bits in little endian order
+    pub fn challenge(&mut self, cb: &mut CircuitBuilder<F>) -> (Var, Vec<Var>)
+    {
+        let x = self.sponge.squeeze(cb);
+        low_bits(cb, x, CHALLENGE_BITS)
+    }
+
+    pub fn challenge_fq(&mut self, cb: &mut CircuitBuilder<F>) -> Var
+    {
+        self.sponge.squeeze(cb)
+    }
+}
+
+// This function allocates the point constrained to be on the curve with the coefficient b
+fn point<F: FftField>(cb: &mut CircuitBuilder<F>, p: &(F, F), b: F) -> Point
+{
+    let p = (cb.witness(p.0), cb.witness(p.1));
+    on_curve(cb, p, b);
+    p
+}
+
+fn points<F: FftField>(cb: &mut CircuitBuilder<F>, p: &[(F, F)], b: F) -> Vec<Point>
+{
+    p.iter().map(|p| point(cb, p, b)).collect()
+}
+
+// This function constrains the circuit field native part of the proof verification
+//     params: Fq-sponge parameters of the proof, the same as the constraint system ones
+//     endo: endomorphism coefficient endo_q of the proof curve, the same as the constraint system one
+//     b: coefficient b of the proof curve y^2 = x^3 + b
+//     proof: the proof to verify
+//     RETURN: deferred values
+pub fn verify<F: FftField + PrimeField>
+(
+    cb: &mut CircuitBuilder<F>,
+    params: ArithmeticSpongeParams<F>,
+    endo: F,
+    b: F,
+    proof: &ProofInput<F>,
+) -> Deferred<Var>
+{
+    assert!(proof.lr.len() > 0 && proof.lr.len() == proof.lr_inv.len());
+
+    let p_comm = points(cb, &proof.p_comm, b);
+    let l_comm = points(cb, &proof.l_comm, b);
+    let r_comm = points(cb, &proof.r_comm, b);
+    let o_comm = points(cb, &proof.o_comm, b);
+    let z_comm = points(cb, &proof.z_comm, b);
+    let t_comm = proof.t_comm.iter().map
+    (
+        |p|
+        {
+            let p = (cb.witness(p.0), cb.witness(p.1));
+            on_curve_or_dummy(cb, p, b);
+            p
+        }
+    ).collect::<Vec<_>>();
+
+    let mut sponge = FqSpongeGadget::new(cb, params);
+
+    // absorb the public input, l, r, o polycommitments and sample beta, gamma
+    sponge.absorb_g(cb, &p_comm);
+    sponge.absorb_g(cb, &l_comm);
+    sponge.absorb_g(cb, &r_comm);
+    sponge.absorb_g(cb, &o_comm);
+    let beta = sponge.challenge(cb).0;
+    let gamma = sponge.challenge(cb).0;
+
+    // absorb the z commitment and sample alpha
+    sponge.absorb_g(cb, &z_comm);
+    let alpha_chal = sponge.challenge(cb).0;
+
+    // absorb the quotient commitment and sample zeta
+    sponge.absorb_g(cb, &t_comm);
+    let zeta_chal = sponge.challenge(cb).0;
+    let digest = sponge.clone().challenge_fq(cb);
+
+    // opening proof transcript
+    let combined_inner_product = proof.combined_inner_product.iter().map(|x| cb.witness(*x)).collect::<Vec<_>>();
+    sponge.absorb(cb, &combined_inner_product);
+    let u = sponge.challenge_fq(cb);
+
+    let mut prechallenges = Vec::new();
+    let mut folded: Option<Point> = None;
+    for ((l, r), l_inv) in proof.lr.iter().zip(proof.lr_inv.iter())
+    {
+        let (l, r, l_inv) = (point(cb, l, b), point(cb, r, b), point(cb, l_inv, b));
+        sponge.absorb_g(cb, &[l, r]);
+        let (pre, bits) = sponge.challenge(cb);
+        prechallenges.push(pre);
+
+        let r = endo_scale(cb, endo, r, &bits);
+        let l = {
+            let check = endo_scale(cb, endo, l_inv, &bits);
+            cb.assert_eq(check.0, l.0);
+            cb.assert_eq(check.1, l.1);
+            l_inv
+        };
+        let term = add(cb, l, r);
+        folded = Some(match folded {None => term, Some(acc) => add(cb, acc, term)});
+    }
+
+    let delta = point(cb, &proof.delta, b);
+    sponge.absorb_g(cb, &[delta]);
+    let c = sponge.challenge(cb).0;
+
+    Deferred
+    {
+        beta,
+        gamma,
+        alpha_chal,
+        zeta_chal,
+        digest,
+        combined_inner_product,
+        u,
+        prechallenges,
+        c,
+        folded: folded.unwrap(),
+        sg: point(cb, &proof.sg, b),
+        p_comm,
+    }
+}
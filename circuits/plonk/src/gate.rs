@@ -32,12 +32,40 @@ pub enum GateType
     Endomul2,   // Gate constraining EC variable base scalar multiplication with group endomorphim optimization
     Endomul3,   // Gate constraining EC variable base scalar multiplication with group endomorphim optimization
     Endomul4,   // Gate constraining EC variable base scalar multiplication with group endomorphim optimization
+
+    Custom,     // Gate constraining a registered custom constraint, see custom.rs
+}
+
+// Selector polynomials of the built-in gates, keying the selector polynomials of the constraint system
+// and their commitments in the verifier index. The order is the one of the linearization scalars
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Selector
+{
+    Qm, Ql, Qr, Qo, Qc,     // generic gate selectors
+    Ps, Rc0, Rc1, Rc2,      // Poseidon gate selector and round constants
+    Add,                    // EC addition gate selector
+    Mul1, Mul2,             // EC variable base scalar multiplication gate selectors
+    Emul1, Emul2, Emul3,    // EC group endomorphism optimised variable base scalar multiplication gate selectors
+}
+
+impl Selector
+{
+    // all selectors in the order of the linearization scalars
+    pub const ALL: [Selector; 15] =
+    [
+        Selector::Qm, Selector::Ql, Selector::Qr, Selector::Qo, Selector::Qc,
+        Selector::Ps, Selector::Rc0, Selector::Rc1, Selector::Rc2,
+        Selector::Add,
+        Selector::Mul1, Selector::Mul2,
+        Selector::Emul1, Selector::Emul2, Selector::Emul3,
+    ];
 }
 
 #[derive(Clone)]
 pub struct CircuitGate<F: FftField>
 {
     pub typ: GateType,      // type of the gate
+    pub custom: Option<usize>, // custom gate registry id, GateType::Custom gates only
     pub wires: GateWires,   // gate wires
     pub c: Vec<F>,          // constraints vector
 }
@@ -48,6 +76,9 @@ impl<F: FftField> ToBytes for CircuitGate<F> {
     fn write<W: Write>(&self, mut w: W) -> IoResult<()> {
         let typ : u8 = ToPrimitive::to_u8(&self.typ).unwrap();
         typ.write(&mut w)?;
+        if self.typ == GateType::Custom {
+            (self.custom.unwrap_or(0) as u32).write(&mut w)?;
+        }
         self.wires.write(&mut w)?;
 
         (self.c.len() as u8).write(&mut w)?;
@@ -67,6 +98,7 @@ impl<F: FftField> FromBytes for CircuitGate<F> {
                 Some(x) => Ok(x),
                 None => Err(Error::new(ErrorKind::Other, "Invalid gate type"))
             }?;
+        let custom = if typ == GateType::Custom {Some(u32::read(&mut r)? as usize)} else {None};
 
         let wires = GateWires::read(&mut r)?;
 
@@ -78,6 +110,7 @@ impl<F: FftField> FromBytes for CircuitGate<F> {
 
         Ok(CircuitGate {
             typ,
+            custom,
             wires,
             c
         })
@@ -92,11 +125,35 @@ impl<F: FftField> CircuitGate<F>
         CircuitGate
         {
             typ: GateType::Zero,
+            custom: None,
             c: Vec::new(),
             wires,
         }
     }
 
+    // This function returns the value of the built-in gate selector polynomial at the row of the gate
+    pub fn selector(&self, sel: Selector) -> F
+    {
+        match sel
+        {
+            Selector::Qm    => self.qm(),
+            Selector::Ql    => self.ql(),
+            Selector::Qr    => self.qr(),
+            Selector::Qo    => self.qo(),
+            Selector::Qc    => self.qc(),
+            Selector::Ps    => self.ps(),
+            Selector::Rc0   => self.rc()[0],
+            Selector::Rc1   => self.rc()[1],
+            Selector::Rc2   => self.rc()[2],
+            Selector::Add   => self.add1(),
+            Selector::Mul1  => self.vbmul1(),
+            Selector::Mul2  => self.vbmul2(),
+            Selector::Emul1 => self.endomul1(),
+            Selector::Emul2 => self.endomul2(),
+            Selector::Emul3 => self.endomul3(),
+        }
+    }
+
     // This function verifies the consistency of the wire
     // assignements (witness) against the constraints
     pub fn verify(&self, next: &Self, witness: &Vec<F>, cs: &ConstraintSystem<F>) -> bool
@@ -115,6 +172,7 @@ impl<F: FftField> CircuitGate<F>
             GateType::Endomul2  => self.verify_endomul2(next, witness),
             GateType::Endomul3  => self.verify_endomul3(next, witness),
             GateType::Endomul4  => self.verify_endomul4(next, witness),
+            GateType::Custom    => self.verify_custom(next, witness, cs),
         }
     }
 }
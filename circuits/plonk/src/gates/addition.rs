@@ -39,12 +39,14 @@ impl<F: FftField> CircuitGate<F>
             CircuitGate
             {
                 typ: GateType::Add1,
+                custom: None,
                 wires: wires[0],
                 c: vec![]
             },
             CircuitGate
             {
                 typ: GateType::Add2,
+                custom: None,
                 wires: wires[1],
                 c: vec![]
             },
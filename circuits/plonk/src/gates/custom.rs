@@ -0,0 +1,47 @@
+/*****************************************************************************************************************
+
+This source file implements Plonk custom constraint gate primitive.
+
+The constraint itself is implemented by the CustomGate trait object registered with the constraint
+system, the gate only references it by the registry id.
+
+Constraint vector format:
+
+    gate specific coefficients, interpreted by CustomGate::selector
+
+*****************************************************************************************************************/
+
+use algebra::FftField;
+use crate::gate::{CircuitGate, GateType};
+use crate::constraints::ConstraintSystem;
+use crate::wires::GateWires;
+
+impl<F: FftField> CircuitGate<F>
+{
+    pub fn create_custom
+    (
+        id: usize,
+        wires: GateWires,
+        c: Vec<F>,
+    ) -> Self
+    {
+        CircuitGate
+        {
+            typ: GateType::Custom,
+            custom: Some(id),
+            wires,
+            c,
+        }
+    }
+
+    pub fn verify_custom(&self, next: &Self, witness: &Vec<F>, cs: &ConstraintSystem<F>) -> bool
+    {
+        self.typ == GateType::Custom
+        &&
+        match self.custom.and_then(|id| cs.custom.get(id))
+        {
+            Some(gate) => gate.verify(self, next, witness),
+            None => false
+        }
+    }
+}
@@ -20,24 +20,28 @@ impl<F: FftField> CircuitGate<F>
             CircuitGate
             {
                 typ: GateType::Endomul1,
+                custom: None,
                 wires: wires[0],
                 c: vec![]
             },
             CircuitGate
             {
                 typ: GateType::Endomul2,
+                custom: None,
                 wires: wires[1],
                 c: vec![]
             },
             CircuitGate
             {
                 typ: GateType::Endomul3,
+                custom: None,
                 wires: wires[2],
                 c: vec![]
             },
             CircuitGate
             {
                 typ: GateType::Endomul4,
+                custom: None,
                 wires: wires[3],
                 c: vec![]
             },
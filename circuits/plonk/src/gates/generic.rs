@@ -31,6 +31,7 @@ impl<F: FftField> CircuitGate<F>
         CircuitGate
         {
             typ: GateType::Generic,
+            custom: None,
             wires,
             c: vec!
             [
@@ -24,6 +24,7 @@ impl<F: FftField> CircuitGate<F>
         CircuitGate
         {
             typ: GateType::Poseidon,
+            custom: None,
             wires,
             c: vec![rc[0], rc[1], rc[2]]
         }
@@ -51,18 +51,21 @@ impl<F: FftField> CircuitGate<F>
             CircuitGate
             {
                 typ: GateType::Vbmul1,
+                custom: None,
                 wires: wires[0],
                 c: vec![]
             },
             CircuitGate
             {
                 typ: GateType::Vbmul2,
+                custom: None,
                 wires: wires[1],
                 c: vec![]
             },
             CircuitGate
             {
                 typ: GateType::Vbmul3,
+                custom: None,
                 wires: wires[2],
                 c: vec![]
             },
@@ -3,9 +3,12 @@ extern crate num_derive;
 
 pub mod gate;
 pub mod gates;
+pub mod custom;
+pub mod gadgets;
 pub mod constraints;
 pub mod polynomials;
 pub mod polynomial;
 pub mod domains;
 pub mod scalars;
 pub mod wires;
+pub mod r1cs;
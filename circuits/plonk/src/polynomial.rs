@@ -6,6 +6,7 @@ This source file implements Plonk prover polynomials primitive.
 
 use algebra::FftField;
 use ff_fft::{Evaluations, Radix2EvaluationDomain as D};
+use std::collections::BTreeMap;
 
 #[derive(Clone)]
 pub struct WitnessEvals<F: FftField>
@@ -29,3 +30,39 @@ pub struct WitnessOverDomains<F: FftField>
     pub d4: WitnessShifts<F>,       // evaluations over domain d4
     pub d8: WitnessShifts<F>,       // evaluations over domain d8
 }
+
+// The selector evaluations the quotient polynomial contributions are computed of. Over the chunk of
+// domain d8, see ConstraintSystem::quot_evals, both the domain.d4 and the domain.d8 evaluations are
+// over the chunk
+#[derive(Clone)]
+pub struct SelectorEvals<F: FftField>
+{
+    // generic constraint selector polynomials
+    pub qll:    Evaluations<F, D<F>>,       // left input wire polynomial over domain.d4
+    pub qrl:    Evaluations<F, D<F>>,       // right input wire polynomial over domain.d4
+    pub qol:    Evaluations<F, D<F>>,       // output wire polynomial over domain.d4
+    pub qml:    Evaluations<F, D<F>>,       // multiplication evaluations over domain.d4
+
+    // permutation polynomials
+    pub sigmal4:[Evaluations<F, D<F>>; 3],  // permutation polynomial array evaluations over domain d8
+
+    // poseidon selector polynomials
+    pub ps4:    Evaluations<F, D<F>>,       // poseidon selector over domain.d4
+    pub ps8:    Evaluations<F, D<F>>,       // poseidon selector over domain.d8
+
+    // ECC arithmetic selector polynomials
+    pub addl8:  Evaluations<F, D<F>>,       // EC point addition selector evaluations w over domain.d8
+    pub mul1l:  Evaluations<F, D<F>>,       // scalar multiplication selector evaluations over domain.d4
+    pub mul2l:  Evaluations<F, D<F>>,       // scalar multiplication selector evaluations over domain.d8
+    pub emul1l: Evaluations<F, D<F>>,       // endoscalar multiplication selector evaluations over domain.d4
+    pub emul2l: Evaluations<F, D<F>>,       // endoscalar multiplication selector evaluations over domain.d4
+    pub emul3l: Evaluations<F, D<F>>,       // endoscalar multiplication selector evaluations over domain.d8
+
+    // custom gate selector evaluations over domain.d4 or domain.d8 according to the gate degree, keyed by the gate id
+    pub customl: BTreeMap<usize, Vec<Evaluations<F, D<F>>>>,
+
+    pub l04:    Evaluations<F, D<F>>,       // 0-th Lagrange evaluated over domain.d4
+    pub l08:    Evaluations<F, D<F>>,       // 0-th Lagrange evaluated over domain.d8
+    pub l1:     Evaluations<F, D<F>>,       // 1-st Lagrange evaluated over domain.d8
+    pub zkpl:   Evaluations<F, D<F>>,       // zero-knowledge polynomial over domain.d8
+}
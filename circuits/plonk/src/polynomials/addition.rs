@@ -14,6 +14,9 @@ constraint polynomials.
 1. First gate constrains the point addition
 2. Second gate constrains the abscissas distinctness check
 
+The constraints are evaluated over domain d8: their degree 4 overflows domain d4 when the witness
+polynomials are blinded with the multiples of the vanishing polynomial.
+
 Constraint equations on wires l, r, o, l_next, r_next, o_next where
     l=y1, r=y2, o=y3, l_next=x1, r_next=x2, o_next=x3:
 
@@ -24,31 +27,31 @@ Constraint equations on wires l, r, o, l_next, r_next, o_next where
 
 use algebra::{FftField, SquareRootField};
 use ff_fft::{Evaluations, DensePolynomial, Radix2EvaluationDomain as D};
-use crate::polynomial::WitnessOverDomains;
+use crate::polynomial::{WitnessOverDomains, SelectorEvals};
 use oracle::utils::{EvalUtils, PolyUtils};
-use crate::constraints::ConstraintSystem;
+use crate::constraints::{ConstraintSystem, Selector};
 use crate::scalars::ProofEvaluations;
 
 impl<F: FftField + SquareRootField> ConstraintSystem<F>
 {
     // EC Affine addition constraint quotient poly contribution computation
-    pub fn ecad_quot(&self, polys: &WitnessOverDomains<F>, alpha: &[F]) -> Evaluations<F, D<F>>
+    pub fn ecad_quot(&self, polys: &WitnessOverDomains<F>, sel: &SelectorEvals<F>, alpha: &[F]) -> Evaluations<F, D<F>>
     {
-        if self.addm.is_zero() {return self.addl4.clone()}
+        if self.sel(Selector::Add).is_zero() {return sel.addl8.clone()}
         /*
             (r_next - l_next) * (o + l) - (l - r) * (l_next - o_next) = 0
             (l_next + r_next + o_next) * (l_next - o_next) * (l_next - o_next) - (o + l) * (o + l) = 0
         */
-        let ylo = &(&polys.d4.this.l + &polys.d4.this.o);
-        let xlo = &(&polys.d4.next.l - &polys.d4.next.o);
+        let ylo = &(&polys.d8.this.l + &polys.d8.this.o);
+        let xlo = &(&polys.d8.next.l - &polys.d8.next.o);
 
-            &(&(&(&(&polys.d4.next.r - &polys.d4.next.l) * ylo)
+            &(&(&(&(&polys.d8.next.r - &polys.d8.next.l) * ylo)
             -
-            &(&(&polys.d4.next.l - &polys.d4.next.o) * &(&polys.d4.this.r - &polys.d4.this.l))).scale(alpha[0])
+            &(&(&polys.d8.next.l - &polys.d8.next.o) * &(&polys.d8.this.r - &polys.d8.this.l))).scale(alpha[0])
             -
-            &(&(ylo * ylo) - &(&(&polys.d4.next.l + &(&polys.d4.next.r + &polys.d4.next.o)) * &(xlo * xlo))).scale(alpha[1]))
+            &(&(ylo * ylo) - &(&(&polys.d8.next.l + &(&polys.d8.next.r + &polys.d8.next.o)) * &(xlo * xlo))).scale(alpha[1]))
             *
-            &self.addl4
+            &sel.addl8
     }
 
     pub fn ecad_scalars(evals: &Vec<ProofEvaluations<F>>, alpha: &[F]) -> Vec<F>
@@ -65,6 +68,6 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
     // EC Affine addition constraint linearization poly contribution computation
     pub fn ecad_lnrz(&self, evals: &Vec<ProofEvaluations<F>>, alpha: &[F]) -> DensePolynomial<F>
     {
-        self.addm.scale(Self::ecad_scalars(evals, alpha)[0])
+        self.sel(Selector::Add).scale(Self::ecad_scalars(evals, alpha)[0])
     }
 }
@@ -0,0 +1,62 @@
+/*****************************************************************************************************************
+
+This source file implements the registered custom constraint polynomials.
+
+Every custom gate consumes the next alphas() powers of alpha, in the order of registration, and
+contributes its quotient evaluations over domain d4 if its degree does not exceed 4, over domain d8
+otherwise. The linearization polynomial contribution is the sum of the gate selector polynomials
+scaled with the CustomGate::scalars values.
+
+*****************************************************************************************************************/
+
+use algebra::{FftField, SquareRootField};
+use ff_fft::{Evaluations, DensePolynomial, EvaluationDomain, Radix2EvaluationDomain as D};
+use crate::polynomial::{WitnessOverDomains, SelectorEvals};
+use crate::constraints::ConstraintSystem;
+use crate::custom::GateRegistry;
+use crate::scalars::ProofEvaluations;
+use oracle::utils::PolyUtils;
+
+impl<F: FftField + SquareRootField> ConstraintSystem<F>
+{
+    // custom constraints quotient poly contribution computation
+    pub fn custom_quot(&self, polys: &WitnessOverDomains<F>, sel: &SelectorEvals<F>, alpha: &[F]) -> (Evaluations<F, D<F>>, Evaluations<F, D<F>>)
+    {
+        let (d4, d8) = (polys.d4.this.l.domain(), polys.d8.this.l.domain());
+        let mut t4 = Evaluations::<F, D<F>>::from_vec_and_domain(vec![F::zero(); d4.size()], d4);
+        let mut t8 = Evaluations::<F, D<F>>::from_vec_and_domain(vec![F::zero(); d8.size()], d8);
+
+        let mut offset = 0;
+        for (id, (_, gate)) in self.custom.gates.iter().enumerate()
+        {
+            let alpha = &alpha[offset..offset + gate.alphas()];
+            offset += gate.alphas();
+            if self.customm[&id].iter().all(|s| s.is_zero()) {continue}
+
+            if gate.degree() <= 3 {t4 = &t4 + &gate.quot(&polys.d4, &sel.customl[&id], alpha)}
+            else {t8 = &t8 + &gate.quot(&polys.d8, &sel.customl[&id], alpha)}
+        }
+        (t4, t8)
+    }
+
+    pub fn custom_scalars(custom: &GateRegistry<F>, evals: &Vec<ProofEvaluations<F>>, alpha: &[F]) -> Vec<F>
+    {
+        let mut offset = 0;
+        custom.gates.iter().map
+        (
+            |(_, gate)|
+            {
+                let alpha = &alpha[offset..offset + gate.alphas()];
+                offset += gate.alphas();
+                gate.scalars(evals, alpha)
+            }
+        ).flatten().collect()
+    }
+
+    // custom constraints linearization poly contribution computation
+    pub fn custom_lnrz(&self, evals: &Vec<ProofEvaluations<F>>, alpha: &[F]) -> DensePolynomial<F>
+    {
+        self.customm.values().flatten().zip(Self::custom_scalars(&self.custom, evals, alpha).iter()).
+            fold(DensePolynomial::<F>::zero(), |x, (s, a)| &x + &s.scale(*a))
+    }
+}
@@ -7,18 +7,18 @@ scalar multiplication custom Plonk polynomials.
 
 use algebra::{FftField, SquareRootField};
 use ff_fft::{Evaluations, DensePolynomial, Radix2EvaluationDomain as D};
-use crate::polynomial::WitnessOverDomains;
+use crate::polynomial::{WitnessOverDomains, SelectorEvals};
 use oracle::utils::{EvalUtils, PolyUtils};
-use crate::constraints::ConstraintSystem;
+use crate::constraints::{ConstraintSystem, Selector};
 use crate::scalars::ProofEvaluations;
 
 impl<F: FftField + SquareRootField> ConstraintSystem<F>
 {
     // endomorphism optimised scalar multiplication constraint quotient poly contribution computation
-    pub fn endomul_quot(&self, polys: &WitnessOverDomains<F>, alpha: &[F]) -> (Evaluations<F, D<F>>, Evaluations<F, D<F>>)
+    pub fn endomul_quot(&self, polys: &WitnessOverDomains<F>, sel: &SelectorEvals<F>, alpha: &[F]) -> (Evaluations<F, D<F>>, Evaluations<F, D<F>>)
     {
-        if self.emul1m.is_zero() && self.emul2m.is_zero() && self.emul3m.is_zero()
-        {return (self.emul1l.clone(), self.emul3l.clone())}
+        if self.sel(Selector::Emul1).is_zero() && self.sel(Selector::Emul2).is_zero() && self.sel(Selector::Emul3).is_zero()
+        {return (sel.emul1l.clone(), sel.emul3l.clone())}
 
         let xr = &(&polys.d8.this.r.square() - &polys.d8.this.l) - &polys.d8.next.r;
         let t = &polys.d8.this.l - &xr;
@@ -26,18 +26,18 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
 
         (
             // verify booleanity of the scalar bits
-            &(&(&(&(&(&polys.d4.this.l - &self.l04) * &polys.d4.this.l).scale(alpha[0])
+            &(&(&(&(&(&polys.d4.this.l - &sel.l04) * &polys.d4.this.l).scale(alpha[0])
             +
-            &(&(&polys.d4.next.l - &self.l04) * &polys.d4.next.l).scale(alpha[1]))
+            &(&(&polys.d4.next.l - &sel.l04) * &polys.d4.next.l).scale(alpha[1]))
             +
             // xQ - (1 + (endo - 1) * b2i1) * xT
-            &(&polys.d4.next.r - &(&(&self.l04 + &polys.d4.this.l.scale(self.endo - &F::one())) * &polys.d4.this.r)).scale(alpha[2]))
+            &(&polys.d4.next.r - &(&(&sel.l04 + &polys.d4.this.l.scale(self.endo - &F::one())) * &polys.d4.this.r)).scale(alpha[2]))
             *
-            &self.emul1l)
+            &sel.emul1l)
             +
             // (xP - xQ) × λ1 - yP + (yT * (2 * b2i - 1))
             &(&(&(&(&(&polys.d4.next.l - &polys.d4.this.r) * &polys.d4.next.r) - &polys.d4.next.o) +
-                &(&polys.d4.this.o * &(&polys.d4.this.l.scale((2 as u64).into()) - &self.l04))) * &self.emul2l).scale(alpha[3])
+                &(&polys.d4.this.o * &(&polys.d4.this.l.scale((2 as u64).into()) - &sel.l04))) * &sel.emul2l).scale(alpha[3])
             ,
             // u^2 - t^2 * (xR + xP + xS)
             &(&(&u.square() - &(&t.square() * &(&(&xr + &polys.d8.this.l) + &polys.d8.next.l))).scale(alpha[4])
@@ -45,7 +45,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
             // (xP - xS) * u - t * (yS + yP)
             &(&(&(&polys.d8.this.l - &polys.d8.next.l) * &u) - &(&t * &(&polys.d8.this.o + &polys.d8.next.o))).scale(alpha[5]))
             *
-            &self.emul3l
+            &sel.emul3l
         )
     }
 
@@ -78,6 +78,6 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
     pub fn endomul_lnrz(&self, evals: &Vec<ProofEvaluations<F>>, alpha: &[F]) -> DensePolynomial<F>
     {
         let scalars = Self::endomul_scalars(evals, self.endo, alpha);
-        &(&self.emul1m.scale(scalars[0]) + &self.emul2m.scale(scalars[1])) + &self.emul3m.scale(scalars[2])
+        &(&self.sel(Selector::Emul1).scale(scalars[0]) + &self.sel(Selector::Emul2).scale(scalars[1])) + &self.sel(Selector::Emul3).scale(scalars[2])
     }
 }
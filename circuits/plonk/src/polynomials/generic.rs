@@ -6,24 +6,24 @@ This source file implements generic constraint polynomials.
 
 use algebra::{FftField, SquareRootField};
 use ff_fft::{Evaluations, DensePolynomial, Radix2EvaluationDomain as D};
-use crate::polynomial::WitnessOverDomains;
-use crate::constraints::ConstraintSystem;
+use crate::polynomial::{WitnessOverDomains, SelectorEvals};
+use crate::constraints::{ConstraintSystem, Selector};
 use crate::scalars::ProofEvaluations;
 use oracle::utils::PolyUtils;
 
 impl<F: FftField + SquareRootField> ConstraintSystem<F>
 {
     // generic constraint quotient poly contribution computation
-    pub fn gnrc_quot(&self, polys: &WitnessOverDomains<F>, p: &DensePolynomial<F>) -> (Evaluations<F, D<F>>, DensePolynomial<F>)
+    pub fn gnrc_quot(&self, polys: &WitnessOverDomains<F>, sel: &SelectorEvals<F>, p: &DensePolynomial<F>) -> (Evaluations<F, D<F>>, DensePolynomial<F>)
     {
         (
-            &(&(&polys.d4.this.l * &polys.d4.this.r) * &self.qml) +
+            &(&(&polys.d4.this.l * &polys.d4.this.r) * &sel.qml) +
             &(
-                &(&(&polys.d4.this.l * &self.qll) +
-                &(&polys.d4.this.r * &self.qrl)) +
-                &(&polys.d4.this.o * &self.qol)
+                &(&(&polys.d4.this.l * &sel.qll) +
+                &(&polys.d4.this.r * &sel.qrl)) +
+                &(&polys.d4.this.o * &sel.qol)
             ),
-            &self.qc + &p
+            self.sel(Selector::Qc) + &p
         )
     }
 
@@ -36,7 +36,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
     pub fn gnrc_lnrz(&self, evals: &ProofEvaluations<F>) -> DensePolynomial<F>
     {
         let scalars = Self::gnrc_scalars(evals);
-        &(&(&(&self.qmm.scale(scalars[0]) + &self.qlm.scale(scalars[1])) +
-            &self.qrm.scale(scalars[2])) + &self.qom.scale(scalars[3])) + &self.qc
+        &(&(&(&self.sel(Selector::Qm).scale(scalars[0]) + &self.sel(Selector::Ql).scale(scalars[1])) +
+            &self.sel(Selector::Qr).scale(scalars[2])) + &self.sel(Selector::Qo).scale(scalars[3])) + self.sel(Selector::Qc)
     }
 }
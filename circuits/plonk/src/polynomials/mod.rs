@@ -4,3 +4,4 @@ pub mod addition;
 pub mod permutation;
 pub mod varbasemul;
 pub mod endosclmul;
+pub mod custom;
@@ -7,7 +7,7 @@ This source file implements permutation constraint polynomial.
 use algebra::{FftField, SquareRootField};
 use ff_fft::{DensePolynomial, Evaluations, Radix2EvaluationDomain as D};
 use crate::scalars::{ProofEvaluations, RandomOracles};
-use crate::polynomial::WitnessOverDomains;
+use crate::polynomial::{WitnessOverDomains, SelectorEvals};
 use oracle::utils::{EvalUtils, PolyUtils};
 use crate::constraints::ConstraintSystem;
 
@@ -18,22 +18,23 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
     (
         &self,
         lagrange: &WitnessOverDomains<F>,
+        sel: &SelectorEvals<F>,
         oracles: &RandomOracles<F>,
     ) -> Evaluations<F, D<F>>
     {
-        let l0 = &self.l08.scale(oracles.gamma);
+        let l0 = &sel.l08.scale(oracles.gamma);
 
-        &((&(&(&(&(&lagrange.d8.this.l + &(l0 + &self.l1.scale(oracles.beta))) *
-        &(&lagrange.d8.this.r + &(l0 + &self.l1.scale(oracles.beta * &self.r)))) *
-        &(&lagrange.d8.this.o + &(l0 + &self.l1.scale(oracles.beta * &self.o)))) *
+        &((&(&(&(&(&lagrange.d8.this.l + &(l0 + &sel.l1.scale(oracles.beta))) *
+        &(&lagrange.d8.this.r + &(l0 + &sel.l1.scale(oracles.beta * &self.r)))) *
+        &(&lagrange.d8.this.o + &(l0 + &sel.l1.scale(oracles.beta * &self.o)))) *
         &lagrange.d8.this.z)
         -
-        &(&(&(&(&lagrange.d8.this.l + &(l0 + &self.sigmal4[0].scale(oracles.beta))) *
-        &(&lagrange.d8.this.r + &(l0 + &self.sigmal4[1].scale(oracles.beta)))) *
-        &(&lagrange.d8.this.o + &(l0 + &self.sigmal4[2].scale(oracles.beta)))) *
+        &(&(&(&(&lagrange.d8.this.l + &(l0 + &sel.sigmal4[0].scale(oracles.beta))) *
+        &(&lagrange.d8.this.r + &(l0 + &sel.sigmal4[1].scale(oracles.beta)))) *
+        &(&lagrange.d8.this.o + &(l0 + &sel.sigmal4[2].scale(oracles.beta)))) *
         &lagrange.d8.next.z)).scale(oracles.alpha))
         *
-        &self.zkpl
+        &sel.zkpl
     }
 
     pub fn perm_lnrz
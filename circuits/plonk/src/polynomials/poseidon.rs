@@ -7,8 +7,8 @@ This source file implements Posedon constraint polynomials.
 use algebra::{FftField, SquareRootField};
 use ff_fft::{Evaluations, DensePolynomial, Radix2EvaluationDomain as D};
 use oracle::{utils::{PolyUtils, EvalUtils}, poseidon::{PlonkSpongeConstants,sbox, ArithmeticSpongeParams}};
-use crate::polynomial::WitnessOverDomains;
-use crate::constraints::ConstraintSystem;
+use crate::polynomial::{WitnessOverDomains, SelectorEvals};
+use crate::constraints::{ConstraintSystem, Selector};
 use crate::scalars::ProofEvaluations;
 use rayon::prelude::*;
 
@@ -18,11 +18,12 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
     pub fn psdn_quot
     (
         &self, polys: &WitnessOverDomains<F>,
+        sel: &SelectorEvals<F>,
         params: &ArithmeticSpongeParams<F>,
         alpha: &[F]
     ) -> (Evaluations<F, D<F>>, Evaluations<F, D<F>>, DensePolynomial<F>)
     {
-        if self.psm.is_zero() {return (self.ps4.clone(), self.ps8.clone(), DensePolynomial::<F>::zero())}
+        if self.sel(Selector::Ps).is_zero() {return (sel.ps4.clone(), sel.ps8.clone(), DensePolynomial::<F>::zero())}
 
         let mut lro = [polys.d8.this.l.clone(), polys.d8.this.r.clone(), polys.d8.this.o.clone()];
         lro.iter_mut().for_each(|p| p.evals.par_iter_mut().for_each(|p| *p = sbox::<F, PlonkSpongeConstants>(*p)));
@@ -32,9 +33,9 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
             collect::<Vec<_>>();
 
         (
-            &self.ps4 * &(&(&polys.d4.next.l.scale(-alpha[0]) - &polys.d4.next.r.scale(alpha[1])) - &polys.d4.next.o.scale(alpha[2])),
-            &self.ps8 * &(&(&lro[0].scale(scalers[0]) + &lro[1].scale(scalers[1])) + &lro[2].scale(scalers[2])),
-            &(&self.rcm[0].scale(alpha[0]) + &self.rcm[1].scale(alpha[1])) + &self.rcm[2].scale(alpha[2])
+            &sel.ps4 * &(&(&polys.d4.next.l.scale(-alpha[0]) - &polys.d4.next.r.scale(alpha[1])) - &polys.d4.next.o.scale(alpha[2])),
+            &sel.ps8 * &(&(&lro[0].scale(scalers[0]) + &lro[1].scale(scalers[1])) + &lro[2].scale(scalers[2])),
+            &(&self.sel(Selector::Rc0).scale(alpha[0]) + &self.sel(Selector::Rc1).scale(alpha[1])) + &self.sel(Selector::Rc2).scale(alpha[2])
         )
     }
 
@@ -73,7 +74,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
         alpha: &[F]
     ) -> DensePolynomial<F>
     {
-        self.rcm.iter().zip(alpha[0..3].iter()).map(|(r, a)| r.scale(*a)).
-            fold(self.psm.scale(Self::psdn_scalars(evals, params, alpha)[0]), |x, y| &x + &y)
+        [Selector::Rc0, Selector::Rc1, Selector::Rc2].iter().zip(alpha[0..3].iter()).map(|(r, a)| self.sel(*r).scale(*a)).
+            fold(self.sel(Selector::Ps).scale(Self::psdn_scalars(evals, params, alpha)[0]), |x, y| &x + &y)
     }
 }
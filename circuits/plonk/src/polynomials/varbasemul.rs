@@ -6,30 +6,30 @@ This source file implements short Weierstrass curve variable base scalar multipl
 
 use algebra::{FftField, SquareRootField};
 use ff_fft::{Evaluations, DensePolynomial, Radix2EvaluationDomain as D};
-use crate::polynomial::WitnessOverDomains;
+use crate::polynomial::{WitnessOverDomains, SelectorEvals};
 use oracle::utils::{EvalUtils, PolyUtils};
-use crate::constraints::ConstraintSystem;
+use crate::constraints::{ConstraintSystem, Selector};
 use crate::scalars::ProofEvaluations;
 
 impl<F: FftField + SquareRootField> ConstraintSystem<F>
 {
     // scalar multiplication constraint quotient poly contribution computation
-    pub fn vbmul_quot(&self, polys: &WitnessOverDomains<F>, alpha: &[F]) -> (Evaluations<F, D<F>>, Evaluations<F, D<F>>)
+    pub fn vbmul_quot(&self, polys: &WitnessOverDomains<F>, sel: &SelectorEvals<F>, alpha: &[F]) -> (Evaluations<F, D<F>>, Evaluations<F, D<F>>)
     {
-        if self.mul1m.is_zero() && self.mul2m.is_zero() {return (self.mul1l.clone(), self.mul2l.clone())}
+        if self.sel(Selector::Mul1).is_zero() && self.sel(Selector::Mul2).is_zero() {return (sel.mul1l.clone(), sel.mul2l.clone())}
 
         // 2*xP - λ1^2 + xT
         let tmp = &(&polys.d8.this.l.scale((2 as u64).into()) - &polys.d8.this.r.square()) + &polys.d8.next.r;
 
         (
             // verify booleanity of the scalar bit
-            &(&(&(&polys.d4.this.r - &self.l04) * &polys.d4.this.r).scale(alpha[0])
+            &(&(&(&polys.d4.this.r - &sel.l04) * &polys.d4.this.r).scale(alpha[0])
             +
             // (xP - xT) × λ1 - yP + (yT × (2*b - 1))
             &(&(&(&(&polys.d4.next.l - &polys.d4.this.l) * &polys.d4.next.r) - &polys.d4.next.o) +
-                &(&(polys.d4.this.o) * &(&polys.d4.this.r.scale((2 as u64).into()) - &self.l04))).scale(alpha[1]))
+                &(&(polys.d4.this.o) * &(&polys.d4.this.r.scale((2 as u64).into()) - &sel.l04))).scale(alpha[1]))
             *
-            &self.mul1l
+            &sel.mul1l
             ,
             &(&(
                 // (2*yP - (2*xP - λ1^2 + xT) × λ1)^2 - (λ1^2 - xT + xS) * (2*xP - λ1^2 + xT)^2
@@ -45,7 +45,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
                 &(&(&polys.d8.next.o + &polys.d8.this.o) * &tmp)
             ).scale(alpha[3]))
             *
-            &self.mul2l
+            &sel.mul2l
         )
     }
 
@@ -81,6 +81,6 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F>
     pub fn vbmul_lnrz(&self, evals: &Vec<ProofEvaluations<F>>, alpha: &[F]) -> DensePolynomial<F>
     {
         let scalars = Self::vbmul_scalars(evals, alpha);
-        &self.mul1m.scale(scalars[0]) + &self.mul2m.scale(scalars[1])
+        &self.sel(Selector::Mul1).scale(scalars[0]) + &self.sel(Selector::Mul2).scale(scalars[1])
     }
 }
@@ -0,0 +1,142 @@
+/*****************************************************************************************************************
+
+This source file implements the compiler of the Marlin R1CS constraint matrices into the Plonk circuit
+of generic gates.
+
+The input is the matrices a, b, c with the witness in the layout of the Marlin index: the matrices
+are square of the witness size n, the witness position 0 is the constant one and the public inputs
+are at the witness positions i * n / |X| where X is the public input domain.
+
+Every witness position referenced by the matrices becomes a circuit variable, the uses of the
+variable in the different gates being bound by the copy constraints. The terms of the constant one
+column are folded into the constant selectors. The linear combination of a single term is used by
+the gates directly with its coefficient as the selector, the wider linear combinations are reduced
+to a single variable with the chain of the addition gates, one gate per term beyond the first. Every
+constraint row <a, w> * <b, w> = <c, w> then becomes the single generic gate
+
+    ql*l + qr*r + qo*o + qm*l*r + qc = 0
+
+The public inputs, the constant one included, become the public input rows of the circuit in their
+order, so that the Plonk and the Marlin proofs of the same witness share the public input vector.
+
+*****************************************************************************************************************/
+
+use algebra::{FftField, Zero, One};
+use ff_fft::{EvaluationDomain, Radix2EvaluationDomain as D};
+use sprs::{CsMat, CsVecView};
+use std::collections::HashMap;
+use crate::gate::GateType;
+use crate::gadgets::builder::{CircuitBuilder, Circuit, Var};
+
+// linear combination reduced to a constant or to a scaled variable
+#[derive(Clone, Copy)]
+enum Operand<F: FftField>
+{
+    Const(F),
+    Var(Var, F),
+}
+
+struct Compiler<'a, F: FftField>
+{
+    cb: CircuitBuilder<F>,
+    vars: HashMap<usize, Var>,  // variables of the witness positions
+    witness: &'a [F],
+}
+
+impl<'a, F: FftField> Compiler<'a, F>
+{
+    // This function returns the variable of the witness position allocating it on the first use
+    fn var(&mut self, j: usize) -> Var
+    {
+        if let Some(v) = self.vars.get(&j) {return *v}
+        let v = self.cb.witness(self.witness[j]);
+        self.vars.insert(j, v);
+        v
+    }
+
+    // This function reduces the matrix row linear combination
+    //     row: matrix row
+    //     RETURN: constant or scaled variable equal to the linear combination
+    fn reduce(&mut self, row: CsVecView<F>) -> Operand<F>
+    {
+        let mut k = F::zero();
+        let mut terms = Vec::new();
+        for (j, c) in row.iter().filter(|(_, c)| !c.is_zero())
+        {
+            if j == 0 {k += c} else {terms.push((self.var(j), *c))}
+        }
+
+        match terms.len()
+        {
+            0 => Operand::Const(k),
+            1 if k.is_zero() => Operand::Var(terms[0].0, terms[0].1),
+            1 => Operand::Var(self.cb.linear(terms[0].0, terms[0].1, terms[0].0, F::zero(), k), F::one()),
+            _ =>
+            {
+                let acc = self.cb.linear(terms[0].0, terms[0].1, terms[1].0, terms[1].1, k);
+                Operand::Var(terms[2..].iter().fold(acc, |acc, (v, c)| self.cb.linear(acc, F::one(), *v, *c, F::zero())), F::one())
+            }
+        }
+    }
+
+    // This function allocates the generic gate of the constraint a * b = c
+    fn constraint(&mut self, a: Operand<F>, b: Operand<F>, c: Operand<F>)
+    {
+        let mut q = [F::zero(); 5];
+        let (mut l, mut r, mut o) = (None, None, None);
+        match (a, b)
+        {
+            (Operand::Var(x, ca), Operand::Var(y, cb)) => {q[3] = ca * &cb; l = Some(x); r = Some(y)}
+            (Operand::Var(x, ca), Operand::Const(kb)) => {q[0] = ca * &kb; l = Some(x)}
+            (Operand::Const(ka), Operand::Var(y, cb)) => {q[1] = ka * &cb; r = Some(y)}
+            (Operand::Const(ka), Operand::Const(kb)) => q[4] = ka * &kb,
+        }
+        match c
+        {
+            Operand::Var(z, cc) => {q[2] = -cc; o = Some(z)}
+            Operand::Const(kc) => q[4] -= &kc,
+        }
+        if l.is_none() && r.is_none() && o.is_none() && q[4].is_zero() {return}
+
+        let value = |v: Option<Var>, cb: &CircuitBuilder<F>| v.map_or(F::zero(), |v| cb.value(v));
+        let w = [value(l, &self.cb), value(r, &self.cb), value(o, &self.cb)];
+        let cells = self.cb.row(GateType::Generic, q.to_vec(), w);
+        for (cell, v) in cells.iter().zip([l, r, o].iter())
+        {
+            if let Some(v) = v {self.cb.copy(*cell, *v)}
+        }
+    }
+}
+
+// This function compiles the R1CS into the Plonk circuit of generic gates
+//     a, b, c: constraint matrices in the layout of the Marlin index
+//     public_inputs: number of the public inputs including the constant one
+//     witness: R1CS witness in the layout of the Marlin index
+//     RETURN: circuit gates with the copy constraints and the Plonk witness, none if the
+//         matrices or the witness are not in the layout of the Marlin index
+pub fn compile<F: FftField>(a: &CsMat<F>, b: &CsMat<F>, c: &CsMat<F>, public_inputs: usize, witness: &[F]) -> Option<Circuit<F>>
+{
+    let n = witness.len();
+    if [a, b, c].iter().any(|m| m.shape() != (n, n)) || public_inputs == 0 || witness.first() != Some(&F::one()) {return None}
+    let x = D::<F>::compute_size_of_domain(public_inputs).filter(|x| *x <= n && n % x == 0)?;
+    let ratio = n / x;
+    let (a, b, c) = (a.to_csr(), b.to_csr(), c.to_csr());
+
+    let mut compiler = Compiler {cb: CircuitBuilder::new(), vars: HashMap::new(), witness};
+
+    // public inputs in their order, the constant one first
+    let one = compiler.cb.constant(F::one());
+    compiler.cb.public(one);
+    for i in 1..public_inputs
+    {
+        let v = compiler.var(i * ratio);
+        compiler.cb.public(v);
+    }
+
+    for ((ra, rb), rc) in a.outer_iterator().zip(b.outer_iterator()).zip(c.outer_iterator())
+    {
+        let (oa, ob, oc) = (compiler.reduce(ra), compiler.reduce(rb), compiler.reduce(rc));
+        compiler.constraint(oa, ob, oc);
+    }
+    Some(compiler.cb.finalize())
+}
@@ -0,0 +1,251 @@
+/*****************************************************************************************************************
+
+This source file defines the polynomial commitment scheme interface shared by the dlog (inner product
+argument) and the pairing (KZG) commitment backends, so that the protocols can be implemented once
+generically over the scheme.
+
+The commitment to the polynomial is the vector of commitments to its segments of the maximal segment size
+of the scheme, with the optional commitment to the last segment shifted to the degree bound. The
+evaluations of the committed polynomial are the vectors of the segment evaluations accordingly.
+
+The scalar challenges of the protocol transcripts are converted into the field elements by the scheme, see
+PolynomialCommitment::challenge, so that the protocols implemented generically run the same transcript as
+the recursive verifier of the scheme.
+
+*****************************************************************************************************************/
+
+use algebra::{
+    curves::models::short_weierstrass_jacobian::{GroupAffine as SWJAffine},
+    AffineCurve, ProjectiveCurve, Field, PrimeField, SWModelParameters, VariableBaseMSM
+};
+use ff_fft::{DensePolynomial, Evaluations, Radix2EvaluationDomain as D};
+use oracle::{FqSponge, sponge::ScalarChallenge, rndoracle::ProofError};
+use rand_core::RngCore;
+
+type Fr<G> = <G as AffineCurve>::ScalarField;
+type Fq<G> = <G as AffineCurve>::BaseField;
+
+pub trait CoordinatesCurve: AffineCurve {
+    fn to_coordinates(&self) -> Option<(Self::BaseField, Self::BaseField)>;
+    fn of_coordinates(x:Self::BaseField, y:Self::BaseField) -> Self;
+}
+
+impl<P: SWModelParameters> CoordinatesCurve for SWJAffine<P> {
+    fn to_coordinates(&self) -> Option<(Self::BaseField, Self::BaseField)>{
+        if self.infinity {
+            None
+        } else {
+            Some((self.x, self.y))
+        }
+    }
+
+    fn of_coordinates(x:Self::BaseField, y:Self::BaseField) -> Self {
+        SWJAffine::<P>::new(x, y, false)
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "ocaml_types", derive(ocaml::ToValue, ocaml::FromValue))]
+pub struct PolyComm<C>
+{
+    pub unshifted: Vec<C>,
+    pub shifted: Option<C>,
+}
+
+impl<A: Copy> PolyComm<A> {
+    pub fn map<B, F>(&self, mut f: F) -> PolyComm<B> where F: FnMut(A) -> B {
+        let unshifted = self.unshifted.iter().map(|x| f(*x)).collect();
+        let shifted = self.shifted.map(f);
+        PolyComm { unshifted, shifted }
+    }
+}
+
+impl<A:Copy, B:Copy> PolyComm<(A, B)> {
+    pub fn unzip(self) -> (PolyComm<A>, PolyComm<B>) {
+        let a = self.map(|(x, _)| x);
+        let b = self.map(|(_, y)| y);
+        (a, b)
+    }
+}
+
+impl<C: AffineCurve> PolyComm<C>
+{
+    pub fn multi_scalar_mul(com: &Vec<&PolyComm<C>>, elm: &Vec<C::ScalarField>) -> Self
+    {
+        PolyComm::<C>
+        {
+            shifted:
+            {
+                if com.len() == 0 || elm.len() == 0 || com[0].shifted == None {None}
+                else
+                {
+                    let points = com.iter().map(|c| {assert!(c.shifted.is_some()); c.shifted.unwrap()}).collect::<Vec<_>>();
+                    let scalars = elm.iter().map(|s| {s.into_repr()}).collect::<Vec<_>>();
+                    Some(VariableBaseMSM::multi_scalar_mul(&points, &scalars).into_affine())
+                }
+            },
+            unshifted:
+            {
+                if com.len() == 0 || elm.len() == 0 {Vec::new()}
+                else
+                {
+                    let n = com.iter().map(|c| c.unshifted.len()).max().unwrap();
+                    (0..n).map
+                    (
+                        |i|
+                        {
+                            let mut points = Vec::new();
+                            let mut scalars = Vec::new();
+                            com.iter().zip(elm.iter()).for_each(|(p, s)| {
+                                if i < p.unshifted.len() {
+                                    points.push(p.unshifted[i]);
+                                    scalars.push(s.into_repr())
+                                }
+                            });
+                            VariableBaseMSM::multi_scalar_mul(&points, &scalars).into_affine()
+                        }
+                    ).collect::<Vec<_>>()
+                }
+            }
+        }
+    }
+}
+
+pub fn product<F: Field>(xs: impl Iterator<Item = F>) -> F {
+    let mut res = F::one();
+    for x in xs {
+        res *= &x;
+    }
+    res
+}
+
+// This function evaluates the polynomial prod_i (1 + chals[i] X^{2^{k-1-i}}) the folded opening proofs
+// of the previous proofs are committed to
+pub fn b_poly<F: Field>(chals: &Vec<F>, x: F) -> F {
+    let k = chals.len();
+
+    let mut pow_twos = vec![x];
+
+    for i in 1..k {
+        pow_twos.push(pow_twos[i - 1].square());
+    }
+
+    product((0..k).map(|i| (F::one() + &(chals[i] * &pow_twos[k - 1 - i]))))
+}
+
+pub fn b_poly_coefficients<F: Field>(chals: &[F]) -> Vec<F> {
+    let rounds = chals.len();
+    let s_length = 1 << rounds;
+    let mut s = vec![F::one(); s_length];
+    s[0] = F::one();
+    let mut k: usize = 0;
+    let mut pow: usize = 1;
+    for i in 1..s_length {
+        k += if i == pow { 1 } else { 0 };
+        pow <<= if i == pow { 1 } else { 0 };
+        s[i] = s[i - (pow >> 1)] * &chals[rounds - 1 - (k - 1)];
+    }
+    s
+}
+
+// Batch of the batched opening proofs to verify, every element consists of
+//     the sponge state the opening proof transcript starts from
+//     vector of evaluation points
+//     scaling factor for the polynomials
+//     scaling factor for the evaluation points
+//     polynomial commitments opened in the batch with the vectors of the segment evaluations
+//         at the evaluation points and the optional degree bounds
+//     batched opening proof
+pub type OpeningBatch<'a, G, EFqSponge, P> = Vec<
+(
+    EFqSponge,
+    Vec<Fr<G>>,
+    Fr<G>,
+    Fr<G>,
+    Vec<(&'a PolyComm<G>, Vec<&'a Vec<Fr<G>>>, Option<usize>)>,
+    &'a P,
+)>;
+
+pub trait PolynomialCommitment<G: AffineCurve>
+{
+    // batched opening proof
+    type Proof: Clone;
+
+    // maximal size of the committed polynomial segment
+    fn max_poly_size(&self) -> usize;
+
+    // This function tells if the commitments are hiding. The protocols blind the committed polynomials
+    // with the multiples of the vanishing polynomial of their domain for the non-hiding schemes
+    fn hiding(&self) -> bool;
+
+    // This function converts the scalar challenge squeezed from the transcript into the field element.
+    // The dlog scheme maps it with the curve endomorphism, the way its recursive verifier circuit does
+    // with the endoscalar constraints, the pairing scheme takes it as is
+    //     chal: scalar challenge
+    //     RETURN: challenge field element
+    fn challenge(&self, chal: &ScalarChallenge<Fr<G>>) -> Fr<G>;
+
+    // This function commits the polynomial
+    //     plnm: polynomial to commit to
+    //     max: maximal degree of the polynomial, if none, no degree bound
+    //     rng: randomness source context
+    //     RETURN: commitment and the commitment randomness, zero for the non-hiding schemes
+    fn commit(&self, plnm: &DensePolynomial<Fr<G>>, max: Option<usize>, rng: &mut dyn RngCore) -> (PolyComm<G>, PolyComm<Fr<G>>);
+
+    // This function commits the polynomial with no commitment randomness
+    fn commit_non_hiding(&self, plnm: &DensePolynomial<Fr<G>>, max: Option<usize>) -> PolyComm<G>;
+
+    // This function precomputes the commitments to the Lagrange polynomials of the domain, for the
+    // schemes committing the polynomials from their evaluations, and does nothing for the others
+    //     domain: evaluation domain of the size not exceeding the maximal segment size
+    fn add_lagrange_basis(&self, _domain: D<Fr<G>>) {}
+
+    // This function commits the polynomial given by its evaluations over the domain with no commitment
+    // randomness. The commitment is equal to the one of the interpolated polynomial with no degree bound
+    //     evals: polynomial evaluations over the domain
+    //     RETURN: commitment, none if the Lagrange basis of the domain is not precomputed
+    fn commit_evaluations_non_hiding(&self, _evals: &Evaluations<Fr<G>, D<Fr<G>>>) -> Option<PolyComm<G>> {None}
+
+    // This function commits the polynomial given by its evaluations and interpolates it, the commitment
+    // being computed from the evaluations if the Lagrange basis of the domain is precomputed
+    //     evals: polynomial evaluations over the domain
+    //     rng: randomness source context
+    //     RETURN: polynomial, commitment and the commitment randomness
+    fn commit_interpolate(&self, evals: Evaluations<Fr<G>, D<Fr<G>>>, rng: &mut dyn RngCore)
+        -> (DensePolynomial<Fr<G>>, PolyComm<G>, PolyComm<Fr<G>>)
+    {
+        let p = evals.interpolate();
+        let (comm, omega) = self.commit(&p, None, rng);
+        (p, comm, omega)
+    }
+
+    // This function opens the polynomial commitments in batch at several points
+    //     plnms: polynomials with the optional degree bounds and the commitment randomness
+    //     elm: evaluation points
+    //     polyscale: scaling factor for the polynomials
+    //     evalscale: scaling factor for the evaluation points
+    //     sponge: sponge state the opening proof transcript starts from
+    //     rng: randomness source context
+    //     RETURN: batched opening proof
+    fn open<EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        &self,
+        plnms: Vec<(&DensePolynomial<Fr<G>>, Option<usize>, PolyComm<Fr<G>>)>,
+        elm: &Vec<Fr<G>>,
+        polyscale: Fr<G>,
+        evalscale: Fr<G>,
+        sponge: EFqSponge,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Proof, ProofError>;
+
+    // This function verifies the batch of the batched opening proofs
+    //     batch: batch of the batched opening proofs
+    //     rng: randomness source context
+    //     RETURN: verification status
+    fn verify<EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        &self,
+        batch: &mut OpeningBatch<G, EFqSponge, Self::Proof>,
+        rng: &mut dyn RngCore,
+    ) -> bool;
+}
@@ -0,0 +1,204 @@
+/*****************************************************************************************************************
+
+This source file implements the windowed fixed-base tables for the scalar multiplication of the points known
+in advance, such as the index polynomial commitments the verifier combines with the fresh scalars of every
+proof.
+
+The table of the point P with the window of w bits holds the multiples
+
+    d 2^(w j) P,    d = 1..2^w-1, j = 0..ceil(b/w)
+
+b the bit size of the scalar field, computed, as in combine::shamir_window_table, by the repeated additions
+and normalised in batch. The multiple k P = sum_j T[j][k_j], k_j the j-th window of the bits of k, then
+costs ceil(b/w) mixed additions and no doublings.
+
+*****************************************************************************************************************/
+
+use crate::commitment::PolyComm;
+use algebra::{AffineCurve, BigInteger, PrimeField, ProjectiveCurve, Zero};
+use rayon::prelude::*;
+
+type Fr<G> = <G as AffineCurve>::ScalarField;
+
+#[derive(Clone, Debug)]
+pub struct FixedBaseTable<G: AffineCurve>
+{
+    pub window: usize,          // window bit size
+    pub table: Vec<Vec<G>>,     // table[j][d-1] = d 2^(w j) P
+}
+
+#[derive(Clone, Debug)]
+pub struct PolyCommTable<G: AffineCurve>
+{
+    pub unshifted: Vec<FixedBaseTable<G>>,  // tables of the segment commitments
+    pub shifted: Option<FixedBaseTable<G>>, // table of the shifted commitment
+}
+
+impl<G: AffineCurve> FixedBaseTable<G>
+{
+    // This function computes the table of the point
+    //     p: point
+    //     window: window bit size
+    //     RETURN: table of the point
+    pub fn create(p: G, window: usize) -> Self
+    {
+        assert!(window > 0 && window < 16, "fixed-base table window has to be of 1 to 15 bits!");
+        let size = (1 << window) - 1;
+        let windows = (Fr::<G>::size_in_bits() + window - 1) / window;
+
+        let mut table = Vec::with_capacity(windows * size);
+        let mut base = p.into_projective();
+        for _ in 0..windows
+        {
+            let mut acc = base;
+            table.push(acc);
+            for _ in 1..size {acc += &base; table.push(acc)}
+            base = acc + &base;
+        }
+        G::Projective::batch_normalization(table.as_mut_slice());
+        let table = table.iter().map(|p| p.into_affine()).collect::<Vec<_>>();
+
+        FixedBaseTable {window, table: table.chunks(size).map(|row| row.to_vec()).collect()}
+    }
+
+    // This function returns the point of the table
+    pub fn point(&self) -> G
+    {
+        self.table[0][0]
+    }
+
+    // This function multiplies the point of the table by the scalar
+    //     k: scalar
+    //     RETURN: k P
+    pub fn mul(&self, k: &Fr<G>) -> G::Projective
+    {
+        let k = k.into_repr();
+        let bits = k.as_ref().len() * 64;
+        let mut res = G::Projective::zero();
+        for (j, row) in self.table.iter().enumerate()
+        {
+            let d = (0..self.window).rev().fold
+            (
+                0,
+                |d, i| {let b = j * self.window + i; (d << 1) | (b < bits && k.get_bit(b)) as usize}
+            );
+            if d > 0 {res.add_assign_mixed(&row[d - 1])}
+        }
+        res
+    }
+}
+
+impl<G: AffineCurve> PolyCommTable<G>
+{
+    // This function computes the tables of the polynomial commitment
+    //     comm: polynomial commitment
+    //     window: window bit size
+    //     RETURN: tables of the segment commitments and of the shifted commitment
+    pub fn create(comm: &PolyComm<G>, window: usize) -> Self
+    {
+        PolyCommTable
+        {
+            unshifted: comm.unshifted.par_iter().map(|p| FixedBaseTable::create(*p, window)).collect(),
+            shifted: comm.shifted.map(|p| FixedBaseTable::create(p, window)),
+        }
+    }
+
+    // This function checks if the tables are of the polynomial commitment
+    //     comm: polynomial commitment
+    //     RETURN: true if the tables are of the segment commitments and of the shifted commitment
+    pub fn is_of(&self, comm: &PolyComm<G>) -> bool
+    {
+        self.unshifted.len() == comm.unshifted.len() &&
+        self.unshifted.iter().zip(comm.unshifted.iter()).all(|(t, p)| t.point() == *p) &&
+        match (&self.shifted, &comm.shifted)
+        {
+            (Some(t), Some(p)) => t.point() == *p,
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    // This function computes the linear combination of the commitments followed by the tabled commitments,
+    // the same as PolyComm::multi_scalar_mul of the commitments the tables are computed of
+    //     com: commitments
+    //     elm: scalars of the commitments
+    //     tables: tables of the commitments
+    //     table_elm: scalars of the tabled commitments
+    //     RETURN: linear combination of the commitments
+    pub fn multi_scalar_mul
+    (
+        com: &Vec<&PolyComm<G>>,
+        elm: &Vec<Fr<G>>,
+        tables: &Vec<&PolyCommTable<G>>,
+        table_elm: &Vec<Fr<G>>,
+    ) -> PolyComm<G>
+    {
+        let variable = PolyComm::<G>::multi_scalar_mul(com, elm);
+        let tables = tables.iter().zip(table_elm.iter()).collect::<Vec<_>>();
+
+        let shifted = if com.len() > 0 && elm.len() > 0 {com[0].shifted.is_some()}
+            else {tables.len() > 0 && tables[0].0.shifted.is_some()};
+        let n = tables.iter().map(|(t, _)| t.unshifted.len()).chain(Some(variable.unshifted.len())).max().unwrap();
+
+        PolyComm::<G>
+        {
+            shifted: if !shifted {None} else
+            {
+                let sum = fixed_sum(&tables, |t| {assert!(t.shifted.is_some()); t.shifted.as_ref()});
+                Some(variable.shifted.map_or(sum, |p| sum + &p.into_projective()).into_affine())
+            },
+            unshifted: (0..n).map
+            (
+                |i|
+                {
+                    let sum = fixed_sum(&tables, |t| t.unshifted.get(i));
+                    variable.unshifted.get(i).map_or(sum, |p| sum + &p.into_projective()).into_affine()
+                }
+            ).collect(),
+        }
+    }
+}
+
+// This function sums the multiples of the segments of the tabled commitments
+//     tables: tables of the commitments with the scalars
+//     segment: segment of the tabled commitment, if any
+//     RETURN: sum of the multiples of the segments
+fn fixed_sum<G, F>(tables: &Vec<(&&PolyCommTable<G>, &Fr<G>)>, segment: F) -> G::Projective
+where G: AffineCurve, F: Fn(&PolyCommTable<G>) -> Option<&FixedBaseTable<G>> + Sync
+{
+    tables.par_iter().map
+    (
+        |(t, s)| match segment(t) {Some(t) => t.mul(s), None => G::Projective::zero()}
+    ).reduce(G::Projective::zero, |a, b| a + &b)
+}
+
+#[test]
+fn fixed_base_table()
+{
+    use algebra::{tweedle::{dee::Affine, fp::Fp}, UniformRand};
+    let rng = &mut rand::thread_rng();
+
+    let p = Affine::prime_subgroup_generator().mul(Fp::rand(rng)).into_affine();
+    for window in [1, 4, 7, 8].iter()
+    {
+        let table = FixedBaseTable::create(p, *window);
+        for k in [Fp::zero(), Fp::from(1u64), -Fp::from(1u64), Fp::rand(rng)].iter()
+        {
+            assert_eq!(table.mul(k), p.mul(*k));
+        }
+    }
+
+    // the combination of the commitments of the different segment numbers
+    let mut comm = |n: usize| PolyComm::<Affine>
+    {
+        unshifted: (0..n).map(|_| Affine::prime_subgroup_generator().mul(Fp::rand(rng)).into_affine()).collect(),
+        shifted: None,
+    };
+    let (com, fixed) = (vec![comm(2)], vec![comm(1), comm(3), comm(2), comm(0)]);
+    let scalars = (0..5).map(|_| Fp::rand(rng)).collect::<Vec<_>>();
+    let tables = fixed.iter().map(|c| PolyCommTable::create(c, 5)).collect::<Vec<_>>();
+
+    let res = PolyCommTable::multi_scalar_mul(&com.iter().collect(), &scalars[0..1].to_vec(), &tables.iter().collect(), &scalars[1..].to_vec());
+    let expected = PolyComm::multi_scalar_mul(&com.iter().chain(fixed.iter()).collect(), &scalars);
+    assert_eq!((res.unshifted, res.shifted), (expected.unshifted, expected.shifted));
+}
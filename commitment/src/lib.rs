@@ -0,0 +1,2 @@
+pub mod commitment;
+pub mod fixed;
@@ -63,7 +63,7 @@ fn add_pairs_in_place<P: SWModelParameters>(p: &mut Vec<SWJAffine<P>>) {
     }
 }
 
-fn batch_add_assign<P: SWModelParameters>(
+pub(crate) fn batch_add_assign<P: SWModelParameters>(
     mut denominators: &mut [P::BaseField],
     v0: &mut [SWJAffine<P>],
     v1: &[SWJAffine<P>],
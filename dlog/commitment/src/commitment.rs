@@ -11,15 +11,18 @@ The folowing functionality is implemented
 *****************************************************************************************************************/
 
 use crate::srs::SRS;
+use crate::msm::{MSMMethod, projective_multi_scalar_mul};
+use crate::ipa::{InnerProductArgument, PublicVector, Opening, BatchMSM};
 use groupmap::{GroupMap, BWParameters};
 use algebra::{
     curves::models::short_weierstrass_jacobian::{GroupAffine as SWJAffine},
     AffineCurve, Field, PrimeField, ProjectiveCurve, SquareRootField,
-    UniformRand, VariableBaseMSM, SWModelParameters, One, Zero,
+    UniformRand, VariableBaseMSM, SWModelParameters, BigInteger, One, Zero,
     FpParameters
 };
-use ff_fft::DensePolynomial;
-use oracle::{FqSponge, sponge::ScalarChallenge};
+use ff_fft::{DensePolynomial, Evaluations, EvaluationDomain, Radix2EvaluationDomain as D};
+use oracle::{FqSponge, sponge::ScalarChallenge, rndoracle::ProofError};
+pub use poly_commitment::commitment::{PolyComm, PolynomialCommitment, OpeningBatch, CoordinatesCurve, product, b_poly, b_poly_coefficients};
 use rand_core::RngCore;
 use rayon::prelude::*;
 use std::iter::Iterator;
@@ -28,82 +31,15 @@ pub use crate::CommitmentField;
 type Fr<G> = <G as AffineCurve>::ScalarField;
 type Fq<G> = <G as AffineCurve>::BaseField;
 
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "ocaml_types", derive(ocaml::ToValue, ocaml::FromValue))]
-pub struct PolyComm<C>
-{
-    pub unshifted: Vec<C>,
-    pub shifted: Option<C>,
-}
-
-impl<A: Copy> PolyComm<A> {
-    pub fn map<B, F>(&self, mut f: F) -> PolyComm<B> where F: FnMut(A) -> B {
-        let unshifted = self.unshifted.iter().map(|x| f(*x)).collect();
-        let shifted = self.shifted.map(f);
-        PolyComm { unshifted, shifted }
-    }
-}
-
-impl<A:Copy, B:Copy> PolyComm<(A, B)> {
-    fn unzip(self) -> (PolyComm<A>, PolyComm<B>) {
-        let a = self.map(|(x, _)| x);
-        let b = self.map(|(_, y)| y);
-        (a, b)
-    }
-}
-
 pub fn shift_scalar<F:PrimeField>(x : F) -> F {
     let two : F = (2 as u64).into();
     x - two.pow(&[F::Params::MODULUS_BITS as u64])
 }
 
-impl<C: AffineCurve> PolyComm<C>
-{
-    pub fn multi_scalar_mul(com: &Vec<&PolyComm<C>>, elm: &Vec<C::ScalarField>) -> Self
-    {
-        PolyComm::<C>
-        {
-            shifted:
-            {
-                if com.len() == 0 || elm.len() == 0 || com[0].shifted == None {None}
-                else
-                {
-                    let points = com.iter().map(|c| {assert!(c.shifted.is_some()); c.shifted.unwrap()}).collect::<Vec<_>>();
-                    let scalars = elm.iter().map(|s| {s.into_repr()}).collect::<Vec<_>>();
-                    Some(VariableBaseMSM::multi_scalar_mul(&points, &scalars).into_affine())
-                }
-            },
-            unshifted:
-            {
-                if com.len() == 0 || elm.len() == 0 {Vec::new()}
-                else
-                {
-                    let n = com.iter().map(|c| c.unshifted.len()).max().unwrap();
-                    (0..n).map
-                    (
-                        |i|
-                        {
-                            let mut points = Vec::new();
-                            let mut scalars = Vec::new();
-                            com.iter().zip(elm.iter()).for_each(|(p, s)| {
-                                if i < p.unshifted.len() {
-                                    points.push(p.unshifted[i]);
-                                    scalars.push(s.into_repr())
-                                }
-                            });
-                            VariableBaseMSM::multi_scalar_mul(&points, &scalars).into_affine()
-                        }
-                    ).collect::<Vec<_>>()
-                }
-            }
-        }
-    }
-}
-
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "ocaml_types", derive(ocaml::ToValue, ocaml::FromValue))]
 pub struct OpeningProof<G: AffineCurve> {
-    pub lr: Vec<(G, G)>, // vector of rounds of L & R commitments
+    pub lr: Vec<(G, G)>, // vector of rounds of L & R commitments, as many as log2 of the longest opened segment
     pub delta: G,
     pub z1: G::ScalarField,
     pub z2: G::ScalarField,
@@ -129,62 +65,32 @@ impl<G:AffineCurve> OpeningProof<G> where G::ScalarField : CommitmentField {
     }
 
     pub fn challenges<EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>(&self, endo_r: &Fr<G>, sponge : &mut EFqSponge) -> Challenges<Fr<G>> {
-        let chal: Vec<_> = self
-            .lr
-            .iter()
-            .map(|(l, r)| {
-                sponge.absorb_g(&[*l]);
-                sponge.absorb_g(&[*r]);
-                squeeze_challenge(endo_r, sponge)
-            })
-            .collect();
-
-        let chal_inv = {
-            let mut cs = chal.clone();
-            algebra::fields::batch_inversion(&mut cs);
-            cs
-        };
-
-        Challenges {
-            chal,
-            chal_inv,
-        }
+        lr_challenges(&self.lr, endo_r, sponge)
     }
 }
 
-pub fn product<F: Field>(xs: impl Iterator<Item = F>) -> F {
-    let mut res = F::one();
-    for x in xs {
-        res *= &x;
-    }
-    res
-}
-
-pub fn b_poly<F: Field>(chals: &Vec<F>, x: F) -> F {
-    let k = chals.len();
+// This function squeezes the challenges of the inner product argument rounds
+pub(crate) fn lr_challenges<G: AffineCurve, EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>(lr: &Vec<(G, G)>, endo_r: &Fr<G>, sponge : &mut EFqSponge) -> Challenges<Fr<G>>
+where G::ScalarField : CommitmentField {
+    let chal: Vec<_> = lr
+        .iter()
+        .map(|(l, r)| {
+            sponge.absorb_g(&[*l]);
+            sponge.absorb_g(&[*r]);
+            squeeze_challenge(endo_r, sponge)
+        })
+        .collect();
 
-    let mut pow_twos = vec![x];
+    let chal_inv = {
+        let mut cs = chal.clone();
+        algebra::fields::batch_inversion(&mut cs);
+        cs
+    };
 
-    for i in 1..k {
-        pow_twos.push(pow_twos[i - 1].square());
+    Challenges {
+        chal,
+        chal_inv,
     }
-
-    product((0..k).map(|i| (F::one() + &(chals[i] * &pow_twos[k - 1 - i]))))
-}
-
-pub fn b_poly_coefficients<F: Field>(chals: &[F]) -> Vec<F> {
-    let rounds = chals.len();
-    let s_length = 1 << rounds;
-    let mut s = vec![F::one(); s_length];
-    s[0] = F::one();
-    let mut k: usize = 0;
-    let mut pow: usize = 1;
-    for i in 1..s_length {
-        k += if i == pow { 1 } else { 0 };
-        pow <<= if i == pow { 1 } else { 0 };
-        s[i] = s[i - (pow >> 1)] * &chals[rounds - 1 - (k - 1)];
-    }
-    s
 }
 
 pub fn ceil_log2(d: usize) -> usize {
@@ -197,7 +103,7 @@ pub fn ceil_log2(d: usize) -> usize {
     ceil_log2
 }
 
-fn pows<F: Field>(d: usize, x: F) -> Vec<F> {
+pub(crate) fn pows<F: Field>(d: usize, x: F) -> Vec<F> {
     let mut acc = F::one();
     (0..d)
         .map(|_| {
@@ -214,19 +120,19 @@ fn squeeze_prechallenge<Fq: Field, G, Fr: SquareRootField, EFqSponge: FqSponge<F
     ScalarChallenge(sponge.challenge())
 }
 
-fn squeeze_challenge<Fq: Field, G, Fr: PrimeField+CommitmentField, EFqSponge: FqSponge<Fq, G, Fr>>(
+pub(crate) fn squeeze_challenge<Fq: Field, G, Fr: PrimeField+CommitmentField, EFqSponge: FqSponge<Fq, G, Fr>>(
     endo_r: &Fr,
     sponge: &mut EFqSponge,
 ) -> Fr {
     squeeze_prechallenge(sponge).to_field(endo_r)
 }
 
-pub trait CommitmentCurve : AffineCurve {
+pub trait CommitmentCurve : CoordinatesCurve {
     type Params : SWModelParameters;
     type Map : GroupMap<Self::BaseField>;
 
-    fn to_coordinates(&self) -> Option<(Self::BaseField, Self::BaseField)>;
-    fn of_coordinates(x : Self::BaseField, y : Self::BaseField) -> Self;
+    // Whether the point is on the curve and in the prime order subgroup
+    fn is_valid(&self) -> bool;
 
     // Combine where x1 = one
     fn combine_one(g1: &Vec<Self>, g2: &Vec<Self>, x2:Self::ScalarField) -> Vec<Self> {
@@ -236,22 +142,20 @@ pub trait CommitmentCurve : AffineCurve {
     fn combine(g1: &Vec<Self>, g2: &Vec<Self>, x1:Self::ScalarField, x2:Self::ScalarField) -> Vec<Self> {
         crate::combine::window_combine(g1, g2, x1, x2)
     }
+
+    // Multi-scalar multiplication with the batch-affine bucket accumulation, where available
+    fn affine_msm(bases: &[Self], scalars: &[<Self::ScalarField as PrimeField>::BigInt]) -> Self::Projective {
+        VariableBaseMSM::multi_scalar_mul(bases, scalars)
+    }
 }
 
 impl<P : SWModelParameters> CommitmentCurve for SWJAffine<P> where P::BaseField : PrimeField {
     type Params = P;
     type Map = BWParameters<P>;
 
-    fn to_coordinates(&self) -> Option<(Self::BaseField, Self::BaseField)>{
-        if self.infinity {
-            None
-        } else {
-            Some((self.x, self.y))
-        }
-    }
-
-    fn of_coordinates(x : P::BaseField, y : P::BaseField) -> SWJAffine<P> {
-        SWJAffine::<P>::new(x, y, false)
+    fn is_valid(&self) -> bool {
+        // the subgroup check is trivial for the prime order curves
+        self.is_on_curve() && (P::COFACTOR == &[1u64][..] || self.is_in_correct_subgroup_assuming_on_curve())
     }
 
     fn combine_one(g1: &Vec<Self>, g2: &Vec<Self>, x2:Self::ScalarField) -> Vec<Self> {
@@ -261,9 +165,13 @@ impl<P : SWModelParameters> CommitmentCurve for SWJAffine<P> where P::BaseField
     fn combine(g1: &Vec<Self>, g2: &Vec<Self>, x1:Self::ScalarField, x2:Self::ScalarField) -> Vec<Self> {
         crate::combine::affine_window_combine(g1, g2, x1, x2)
     }
+
+    fn affine_msm(bases: &[Self], scalars: &[<Self::ScalarField as PrimeField>::BigInt]) -> Self::Projective {
+        crate::msm::affine_multi_scalar_mul(bases, scalars)
+    }
 }
 
-fn to_group<G : CommitmentCurve>(
+pub(crate) fn to_group<G : CommitmentCurve>(
     m: &G::Map,
     t: <G as AffineCurve>::BaseField) -> G {
     let (x, y) = m.to_group(t);
@@ -313,6 +221,83 @@ pub fn combined_inner_product<G: CommitmentCurve>(
 }
 
 impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
+    // This function applies the curve endomorphism phi(x, y) = (endo_q * x, y) = endo_r * (x, y)
+    pub fn endo(&self, g: &G) -> G
+    {
+        match g.to_coordinates()
+        {
+            None => G::zero(),
+            Some((x, y)) => G::of_coordinates(x * &self.endo_q, y),
+        }
+    }
+
+    // This function computes the multi-scalar multiplication with the algorithm selected for the SRS,
+    // every scalar being split into the two half-size scalars over the base and its endomorphism image.
+    // Both algorithms process the windows of the half-size scalars only
+    //     bases: group elements
+    //     scalars: scalars, the extra bases or scalars are ignored
+    //     RETURN: sum of the bases scaled by the scalars
+    pub fn msm(&self, bases: &[G], scalars: &[Fr<G>]) -> G::Projective
+    {
+        self.glv_msm(bases, None, scalars)
+    }
+
+    // This function computes the multi-scalar multiplication over the SRS bases as SRS::msm does,
+    // with the endomorphism images of the bases cached in the SRS
+    //     offset: index of the first SRS base
+    //     scalars: scalars of the bases g[offset..], the extra scalars are ignored
+    //     RETURN: sum of the bases scaled by the scalars
+    pub fn msm_g(&self, offset: usize, scalars: &[Fr<G>]) -> G::Projective
+    {
+        let end = std::cmp::min(offset + scalars.len(), self.g().len());
+        self.glv_msm(&self.g()[offset..end], Some(&self.g_endo()[offset..end]), scalars)
+    }
+
+    fn glv_msm(&self, bases: &[G], endo: Option<&[G]>, scalars: &[Fr<G>]) -> G::Projective
+    {
+        let (bases, scalars): (Vec<G>, Vec<_>) = bases.par_iter().zip(scalars.par_iter()).enumerate().map
+        (
+            |(i, (g, k))|
+            {
+                let ((s1, k1), (s2, k2)) = self.glv.decompose(k);
+                let phi = endo.map_or_else(|| self.endo(g), |e| e[i]);
+                vec![(if s1 {-*g} else {*g}, k1), (if s2 {-phi} else {phi}, k2)]
+            }
+        ).flatten().unzip();
+
+        match self.msm_method
+        {
+            MSMMethod::Projective => projective_multi_scalar_mul(&bases, &scalars),
+            MSMMethod::BatchAffine => G::affine_msm(&bases, &scalars),
+        }
+    }
+
+    // This function scales the group element with the GLV decomposition of the scalar
+    //     g: group element
+    //     k: scalar
+    //     RETURN: k * g
+    pub fn scale(&self, g: G, k: Fr<G>) -> G::Projective
+    {
+        let ((s1, k1), (s2, k2)) = self.glv.decompose(&k);
+        let p1 = if s1 {-g} else {g};
+        let p2 = {let phi = self.endo(&g); if s2 {-phi} else {phi}};
+        let p12 = p1.into_projective().add_mixed(&p2);
+
+        let mut res = G::Projective::zero();
+        for i in (0..std::cmp::max(k1.num_bits(), k2.num_bits()) as usize).rev()
+        {
+            res.double_in_place();
+            match (k1.get_bit(i), k2.get_bit(i))
+            {
+                (true, true) => res += &p12,
+                (true, false) => res.add_assign_mixed(&p1),
+                (false, true) => res.add_assign_mixed(&p2),
+                (false, false) => (),
+            }
+        }
+        res
+    }
+
     pub fn commit(
         &self,
         plnm: &DensePolynomial<Fr<G>>,
@@ -335,7 +320,7 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
                 (g, Fr::<G>::zero())
             } else {
                 let w = Fr::<G>::rand(rng);
-                let mut g_masked = self.h.mul(w);
+                let mut g_masked = self.scale(self.h, w);
                 g_masked.add_assign_mixed(&g);
                 (g_masked.into_affine(), w)
             }
@@ -352,7 +337,7 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
         max: Option<usize>,
     ) -> PolyComm<G>
     {
-        let n = self.g.len();
+        let n = self.g().len();
         let p = plnm.coeffs.len();
 
         // committing all the segments without shifting
@@ -363,9 +348,9 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
             (
                 |i|
                 {
-                    VariableBaseMSM::multi_scalar_mul
+                    self.msm_g
                     (
-                        &self.g, &plnm.coeffs[i*n..p].iter().map(|s| s.into_repr()).collect::<Vec<_>>()
+                        0, &plnm.coeffs[i*n..p]
                     ).into_affine()
                 }
             ).collect()
@@ -382,10 +367,10 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
                 else if max % n == 0 {None}
                 else
                 {
-                    Some(VariableBaseMSM::multi_scalar_mul
+                    Some(self.msm_g
                     (
-                        &self.g[n - (max%n)..],
-                        &plnm.coeffs[start..p].iter().map(|s| s.into_repr()).collect::<Vec<_>>()
+                        n - (max%n),
+                        &plnm.coeffs[start..p]
                     ).into_affine())
                 }
             }
@@ -394,6 +379,64 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
         PolyComm::<G>{unshifted, shifted}
     }
 
+    // This function commits the polynomial given by its evaluations over the domain against the
+    // precomputed Lagrange basis of the domain, see SRS::add_lagrange_basis. The commitment is
+    // equal to the one of the interpolated polynomial with no degree bound.
+    //     evals: polynomial evaluations over the domain, the missing trailing ones being zero
+    //     RETURN: commitment, none if the Lagrange basis of the domain is not precomputed
+    pub fn commit_evaluations_non_hiding<E: EvaluationDomain<Fr<G>>>(
+        &self,
+        evals: &Evaluations<Fr<G>, E>,
+    ) -> Option<PolyComm<G>>
+    {
+        let basis = self.lagrange_bases.get(evals.domain().size())?;
+        let e = &evals.evals;
+        assert!(e.len() <= basis.len());
+
+        let unshifted = if e.iter().all(|x| x.is_zero()) {Vec::new()}
+        else
+        {
+            vec![self.msm
+            (
+                &basis[0..e.len()], e
+            ).into_affine()]
+        };
+        Some(PolyComm::<G>{unshifted, shifted: None})
+    }
+
+    // This function commits the polynomial given by its evaluations, see commit_evaluations_non_hiding
+    pub fn commit_evaluations<E: EvaluationDomain<Fr<G>>>(
+        &self,
+        evals: &Evaluations<Fr<G>, E>,
+        rng: &mut dyn RngCore,
+    ) -> Option<(PolyComm<G>, PolyComm<Fr<G>>)>
+    {
+        self.commit_evaluations_non_hiding(evals).map(|c| self.mask(c, rng))
+    }
+
+    // This function commits the polynomial given by its evaluations and interpolates it, for the
+    // polynomials needed in the coefficient form as well. The commitment is computed from the
+    // evaluations if the Lagrange basis of the domain is precomputed, from the polynomial otherwise
+    //     evals: polynomial evaluations over the domain
+    //     RETURN: polynomial, commitment and commitment randomness
+    pub fn commit_interpolate<E: EvaluationDomain<Fr<G>>>(
+        &self,
+        evals: Evaluations<Fr<G>, E>,
+        rng: &mut dyn RngCore,
+    ) -> (DensePolynomial<Fr<G>>, PolyComm<G>, PolyComm<Fr<G>>)
+    {
+        match self.commit_evaluations(&evals, rng)
+        {
+            Some((comm, omega)) => (evals.interpolate(), comm, omega),
+            None =>
+            {
+                let p = evals.interpolate();
+                let (comm, omega) = self.commit(&p, None, rng);
+                (p, comm, omega)
+            }
+        }
+    }
+
     // This function opens polynomial commitments in batch
     //     plnms: batch of polynomials to open commitments for with, optionally, max degrees
     //     elm: evaluation point vector to open the commitments at
@@ -408,19 +451,12 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
         elm: &Vec<Fr<G>>,                                     // vector of evaluation points
         polyscale: Fr<G>,                                     // scaling factor for polynoms
         evalscale: Fr<G>, // scaling factor for evaluation point powers
-        mut sponge: EFqSponge, // sponge
+        sponge: EFqSponge, // sponge
         rng: &mut dyn RngCore,
     ) -> OpeningProof<G> {
-        let rounds = ceil_log2(self.g.len());
-        let padded_length = 1 << rounds;
-
-        // TODO: Trim this to the degree of the largest polynomial
-
-        let padding = padded_length - self.g.len();
-        let mut g = self.g.clone();
-        g.extend(vec![G::zero(); padding]);
-
-        // scale the polynoms in accumulator shifted, if bounded, to the end of SRS
+        // scale the polynoms in accumulator shifted, if bounded, to the end of SRS,
+        // tracking the length of the longest, possibly shifted, segment
+        let mut max_length = 1;
         let (p, blinding_factor) = {
             let mut p = DensePolynomial::<Fr<G>>::zero();
 
@@ -436,16 +472,19 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
                     assert!(p_i.coeffs.len() <= m + 1);
                     while offset < p_i.coeffs.len() {
                         let segment = DensePolynomial::<Fr<G>>::from_coefficients_slice
-                            (&p_i.coeffs[offset..if offset+self.g.len() > p_i.coeffs.len() {p_i.coeffs.len()} else {offset+self.g.len()}]);
+                            (&p_i.coeffs[offset..if offset+self.g().len() > p_i.coeffs.len() {p_i.coeffs.len()} else {offset+self.g().len()}]);
+                        let length = std::cmp::min(p_i.coeffs.len() - offset, self.g().len());
+                        max_length = std::cmp::max(max_length, length);
                         // always mixing in the unshifted segments
                         p += &segment.scale(scale);
                         omega += &(omegas.unshifted[j] * scale);
                         j += 1;
                         scale *= &polyscale;
-                        offset += self.g.len();
+                        offset += self.g().len();
                         if offset > *m {
                             // mixing in the shifted segment since degree is bounded
-                            p += &(segment.shiftr(self.g.len() - m%self.g.len()).scale(scale));
+                            max_length = std::cmp::max(max_length, std::cmp::min(self.g().len() - m%self.g().len() + length, self.g().len()));
+                            p += &(segment.shiftr(self.g().len() - m%self.g().len()).scale(scale));
                             omega += &(omegas.shifted.unwrap() * scale);
                             scale *= &polyscale;
                         }
@@ -455,13 +494,14 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
                     assert!(omegas.shifted.is_none());
                     while offset < p_i.coeffs.len() {
                         let segment = DensePolynomial::<Fr<G>>::from_coefficients_slice
-                            (&p_i.coeffs[offset..if offset+self.g.len() > p_i.coeffs.len() {p_i.coeffs.len()} else {offset+self.g.len()}]);
+                            (&p_i.coeffs[offset..if offset+self.g().len() > p_i.coeffs.len() {p_i.coeffs.len()} else {offset+self.g().len()}]);
+                        max_length = std::cmp::max(max_length, std::cmp::min(p_i.coeffs.len() - offset, self.g().len()));
                         // always mixing in the unshifted segments
                         p += &segment.scale(scale);
                         omega += &(omegas.unshifted[j] * scale);
                         j += 1;
                         scale *= &polyscale;
-                        offset += self.g.len();
+                        offset += self.g().len();
                     }
                 }
                 assert_eq!(j, omegas.unshifted.len());
@@ -469,136 +509,16 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
             (p, omega)
         };
 
-        let rounds = ceil_log2(self.g.len());
-
-        // b_j = sum_i r^i elm_i^j
-        let b_init = {
-            // randomise/scale the eval powers
-            let mut scale = Fr::<G>::one();
-            let mut res: Vec<Fr<G>> = (0..padded_length).map(|_| Fr::<G>::zero()).collect();
-            for e in elm {
-                for (i, t) in pows(padded_length, *e).iter().enumerate() {
-                    res[i] += &(scale * t);
-                }
-                scale *= &evalscale;
-            }
-            res
-        };
-
-        let combined_inner_product = 
-            p.coeffs.iter().zip(b_init.iter())
-            .map(|(a, b)| *a * b)
-            .fold(Fr::<G>::zero(), |acc, x| acc + x);
-
-        sponge.absorb_fr(&[shift_scalar(combined_inner_product)]);
-
-        let t = sponge.challenge_fq();
-        let u: G = to_group(group_map, t);
-
+        // the argument runs over the SRS prefix covering the longest segment only, so that
+        // the number of the rounds, that is the length of the proof lr vector, is the one of
+        // the segment length rather than of the SRS size
         let mut a = p.coeffs;
-        assert!(padded_length >= a.len());
-        a.extend(vec![Fr::<G>::zero(); padded_length - a.len()]);
-
-        let mut b = b_init.clone();
-
-        let mut lr = vec![];
-
-        let mut blinders = vec![];
-
-        let mut chals = vec![];
-        let mut chal_invs = vec![];
-
-        for _ in 0..rounds {
-            let n = g.len() / 2;
-            let (g_lo, g_hi) = (g[0..n].to_vec(), g[n..].to_vec());
-            let (a_lo, a_hi) = (&a[0..n], &a[n..]);
-            let (b_lo, b_hi) = (&b[0..n], &b[n..]);
-
-            let rand_l = Fr::<G>::rand(rng);
-            let rand_r = Fr::<G>::rand(rng);
-
-            let l = VariableBaseMSM::multi_scalar_mul(
-                &[&g[0..n], &[self.h, u]].concat(),
-                &[&a[n..], &[rand_l, inner_prod(a_hi, b_lo)]].concat()
-                    .iter().map(|x| x.into_repr()).collect::<Vec<_>>()
-            ).into_affine();
-
-            let r = VariableBaseMSM::multi_scalar_mul(
-                &[&g[n..], &[self.h, u]].concat(),
-                &[&a[0..n], &[rand_r, inner_prod(a_lo, b_hi)]].concat()
-                    .iter().map(|x| x.into_repr()).collect::<Vec<_>>()
-            ).into_affine();
-
-            lr.push((l, r));
-            blinders.push((rand_l, rand_r));
-
-            sponge.absorb_g(&[l]);
-            sponge.absorb_g(&[r]);
-
-            let u = squeeze_challenge(&self.endo_r, &mut sponge);
-            let u_inv = u.inverse().unwrap();
-
-            chals.push(u);
-            chal_invs.push(u_inv);
-
-            a = a_hi
-                .par_iter()
-                .zip(a_lo)
-                .map(|(&hi, &lo)| {
-                    // lo + u_inv * hi
-                    let mut res = hi;
-                    res *= u_inv;
-                    res += &lo;
-                    res
-                })
-                .collect();
+        assert!(max_length >= a.len());
+        a.resize(max_length, Fr::<G>::zero());
 
-            b = b_lo
-                .par_iter()
-                .zip(b_hi)
-                .map(|(&lo, &hi)| {
-                    // lo + u * hi
-                    let mut res = hi;
-                    res *= u;
-                    res += &lo;
-                    res
-                })
-                .collect();
-
-            g = G::combine_one(&g_lo, &g_hi, u);
-        }
-
-        assert!(g.len() == 1);
-        let a0 = a[0];
-        let b0 = b[0];
-        let g0 = g[0];
-
-        let r_prime = blinders
-            .iter()
-            .zip(chals.iter().zip(chal_invs.iter()))
-            .map(|((l, r), (u, u_inv))| ((*l) * u_inv) + &(*r * u))
-            .fold(blinding_factor, |acc, x| acc + &x);
-
-        let d = Fr::<G>::rand(rng);
-        let r_delta = Fr::<G>::rand(rng);
-
-        let delta = ((g0.into_projective() + &(u.mul(b0))).into_affine().mul(d)
-            + &self.h.mul(r_delta))
-            .into_affine();
-
-        sponge.absorb_g(&[delta]);
-        let c = ScalarChallenge(sponge.challenge()).to_field(&self.endo_r);
-
-        let z1 = a0 * &c + &d;
-        let z2 = c * &r_prime + &r_delta;
-
-        OpeningProof {
-            delta,
-            lr,
-            z1,
-            z2,
-            sg: g0,
-        }
+        // opening the combined polynomial against b_j = sum_i r^i elm_i^j
+        InnerProductArgument::create(self, group_map).prove
+            (a, &PublicVector::Powers(elm.clone(), evalscale), blinding_factor, sponge, rng)
     }
 
     // This function verifies batch of batched polynomial commitment opening proofs
@@ -648,27 +568,8 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
         // We also check that the sg component of the proof is equal to the polynomial commitment
         // to the "s" array
 
-        let nonzero_length = self.g.len();
-
-        let max_rounds = ceil_log2(nonzero_length);
-
-        let padded_length = 1 << max_rounds;
-
-        // TODO: This will need adjusting
-        let padding = padded_length - nonzero_length;
-        let mut points = vec![self.h];
-        points.extend(self.g.clone());
-        points.extend(vec![G::zero(); padding]);
-
-        let mut scalars = vec![Fr::<G>::zero(); padded_length + 1];
-        assert_eq!(scalars.len(), points.len());
-
-        // sample randomiser to scale the proofs with
-        let rand_base = Fr::<G>::rand(rng);
-        let sg_rand_base = Fr::<G>::rand(rng);
-
-        let mut rand_base_i = Fr::<G>::one();
-        let mut sg_rand_base_i = Fr::<G>::one();
+        let ipa = InnerProductArgument::create(self, group_map);
+        let mut msm = BatchMSM::create(self, rng);
 
         for (sponge, evaluation_points, xi, r, polys, opening) in batch.iter_mut() {
             // TODO: This computation is repeated in ProverProof::oracles
@@ -681,129 +582,51 @@ impl<G: CommitmentCurve> SRS<G> where G::ScalarField : CommitmentField {
                     })();
                     (evals.clone(), bound)
                 }).collect();
-                combined_inner_product::<G>(evaluation_points, xi, r, &es, self.g.len())
+                combined_inner_product::<G>(evaluation_points, xi, r, &es, self.g().len())
             };
 
-            sponge.absorb_fr(&[shift_scalar(combined_inner_product0)]);
-
-            let t = sponge.challenge_fq();
-            let u: G = to_group(group_map, t);
-
-            let Challenges { chal, chal_inv } = opening.challenges::<EFqSponge>(&self.endo_r, sponge);
-
-            sponge.absorb_g(&[opening.delta]);
-            let c = ScalarChallenge(sponge.challenge()).to_field(&self.endo_r);
-
-            // < s, sum_i r^i pows(evaluation_point[i]) >
-            // ==
-            // sum_i r^i < s, pows(evaluation_point[i]) >
-            let b0 = {
-                let mut scale = Fr::<G>::one();
-                let mut res = Fr::<G>::zero();
-                for &e in evaluation_points.iter() {
-                    let term = b_poly(&chal, e);
-                    res += &(scale * &term);
-                    scale *= *r;
-                }
-                res
-            };
-
-            let s = b_poly_coefficients(&chal);
-
-            let neg_rand_base_i = -rand_base_i;
-
-            // TERM
-            // - rand_base_i z1 G
-            //
-            // we also add -sg_rand_base_i * G to check correctness of sg.
-            points.push(opening.sg);
-            scalars.push(neg_rand_base_i * &opening.z1 - &sg_rand_base_i);
-
-            // Here we add
-            // sg_rand_base_i * ( < s, self.g > )
-            // =
-            // < sg_rand_base_i s, self.g >
-            //
-            // to check correctness of the sg component.
-            {
-                let terms: Vec<_> = s.par_iter().map(|s| sg_rand_base_i * s).collect();
-
-                for (i, term) in terms.iter().enumerate() {
-                    scalars[i + 1] += term;
-                }
-            }
-
-            // TERM
-            // - rand_base_i * z2 * H
-            scalars[0] -= &(rand_base_i * &opening.z2);
-
-            // TERM
-            // -rand_base_i * (z1 * b0 * U)
-            scalars.push(neg_rand_base_i * &(opening.z1 * &b0));
-            points.push(u);
-
-            // TERM
-            // rand_base_i c_i Q_i
-            // = rand_base_i c_i
-            //   (sum_j (chal_invs[j] L_j + chals[j] R_j) + P_prime)
-            // where P_prime = combined commitment + combined_inner_product * U
-            let rand_base_i_c_i = c * &rand_base_i;
-            for ((l, r), (u_inv, u)) in opening
-                .lr
-                .iter()
-                .zip(chal_inv.iter().zip(chal.iter()))
-            {
-                points.push(*l);
-                scalars.push(rand_base_i_c_i * u_inv);
-
-                points.push(*r);
-                scalars.push(rand_base_i_c_i * u);
-            }
-
-            // TERM
             // sum_j r^j (sum_i xi^i f_i) (elm_j)
             // == sum_j sum_i r^j xi^i f_i(elm_j)
             // == sum_i xi^i sum_j r^j f_i(elm_j)
+            let mut commitment = vec![];
             {
                 let mut xi_i = Fr::<G>::one();
 
                 for (comm, _evals_tr, shifted) in polys.iter().filter(|x| x.0.unshifted.len() > 0) {
                     // iterating over the polynomial segments
                     for comm_ch in comm.unshifted.iter() {
-                        scalars.push(rand_base_i_c_i * &xi_i);
-                        points.push(*comm_ch);
+                        commitment.push((*comm_ch, xi_i));
                         xi_i *= *xi;
                     }
 
                     if let Some(_m) = shifted {
                         if let Some(comm_ch) = comm.shifted {
-							if comm_ch.is_zero() == false {
-								// xi^i sum_j r^j elm_j^{N - m} f(elm_j)
-								scalars.push(rand_base_i_c_i * &xi_i);
-								points.push(comm_ch);
+                            if comm_ch.is_zero() == false {
+                                // xi^i sum_j r^j elm_j^{N - m} f(elm_j)
+                                commitment.push((comm_ch, xi_i));
                                 xi_i *= *xi;
-							}
+                            }
                         }
                     }
                 }
             };
 
-            scalars.push(rand_base_i_c_i * &combined_inner_product0);
-            points.push(u);
-
-            scalars.push(rand_base_i);
-            points.push(opening.delta);
-
-            rand_base_i *= &rand_base;
-            sg_rand_base_i *= &sg_rand_base;
+            let mut opening = Opening
+            {
+                sponge,
+                commitment,
+                b: PublicVector::Powers(evaluation_points.clone(), *r),
+                value: combined_inner_product0,
+                proof: *opening,
+            };
+            if !ipa.accumulate(&mut msm, &mut opening) {return false}
         }
         // verify the equation
-        let scalars: Vec<_> = scalars.iter().map(|x| x.into_repr()).collect();
-        VariableBaseMSM::multi_scalar_mul(&points, &scalars) == G::Projective::zero()
+        msm.check(self)
     }
 }
 
-fn inner_prod<F: Field>(xs: &[F], ys: &[F]) -> F {
+pub(crate) fn inner_prod<F: Field>(xs: &[F], ys: &[F]) -> F {
     let mut res = F::zero();
     for (&x, y) in xs.iter().zip(ys) {
         res += &(x * y);
@@ -854,3 +677,70 @@ impl<F: Field> Utils<F> for DensePolynomial<F> {
         ).collect()
     }
 }
+
+impl<G: CommitmentCurve> PolynomialCommitment<G> for SRS<G> where G::ScalarField : CommitmentField
+{
+    type Proof = OpeningProof<G>;
+
+    fn max_poly_size(&self) -> usize
+    {
+        self.g().len()
+    }
+
+    fn hiding(&self) -> bool {true}
+
+    fn challenge(&self, chal: &ScalarChallenge<Fr<G>>) -> Fr<G>
+    {
+        chal.to_field(&self.endo_r)
+    }
+
+    fn commit(&self, plnm: &DensePolynomial<Fr<G>>, max: Option<usize>, rng: &mut dyn RngCore) -> (PolyComm<G>, PolyComm<Fr<G>>)
+    {
+        SRS::commit(self, plnm, max, rng)
+    }
+
+    fn commit_non_hiding(&self, plnm: &DensePolynomial<Fr<G>>, max: Option<usize>) -> PolyComm<G>
+    {
+        SRS::commit_non_hiding(self, plnm, max)
+    }
+
+    fn add_lagrange_basis(&self, domain: D<Fr<G>>)
+    {
+        SRS::add_lagrange_basis(self, domain)
+    }
+
+    fn commit_evaluations_non_hiding(&self, evals: &Evaluations<Fr<G>, D<Fr<G>>>) -> Option<PolyComm<G>>
+    {
+        SRS::commit_evaluations_non_hiding(self, evals)
+    }
+
+    fn commit_interpolate(&self, evals: Evaluations<Fr<G>, D<Fr<G>>>, rng: &mut dyn RngCore)
+        -> (DensePolynomial<Fr<G>>, PolyComm<G>, PolyComm<Fr<G>>)
+    {
+        SRS::commit_interpolate(self, evals, rng)
+    }
+
+    fn open<EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        &self,
+        plnms: Vec<(&DensePolynomial<Fr<G>>, Option<usize>, PolyComm<Fr<G>>)>,
+        elm: &Vec<Fr<G>>,
+        polyscale: Fr<G>,
+        evalscale: Fr<G>,
+        sponge: EFqSponge,
+        rng: &mut dyn RngCore,
+    ) -> Result<Self::Proof, ProofError>
+    {
+        Ok(SRS::open(self, &self.group_map, plnms, elm, polyscale, evalscale, sponge, rng))
+    }
+
+    fn verify<EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        &self,
+        batch: &mut OpeningBatch<G, EFqSponge, Self::Proof>,
+        rng: &mut dyn RngCore,
+    ) -> bool
+    {
+        SRS::verify(self, &self.group_map, batch, rng)
+    }
+}
@@ -0,0 +1,331 @@
+/*****************************************************************************************************************
+
+This source file implements the GLV scalar decomposition over the curve endomorphism
+
+    phi(x, y) = (endo_q * x, y) = endo_r * (x, y)
+
+The scalar k is decomposed as k = k1 + k2 * endo_r mod r with k1, k2 of about half the size of r, so
+that k * P = k1 * P + k2 * phi(P) is computed with the half-size scalars.
+
+The lattice {(a, b): a + b * endo_r = 0 mod r} is reduced with the extended Euclidean algorithm on
+(r, endo_r) into the short basis v1 = (a1, b1), v2 = (a2, b2). The scalar is decomposed by rounding
+its coordinates (b2 * k / det, -b1 * k / det) in the basis, det = a1 * b2 - a2 * b1, to the integers
+c1, c2, the rounding being done with the precomputed 2^m / det multiples so that only the integer
+multiplication is needed, and by subtracting c1 * v1 + c2 * v2 from (k, 0). The lattice determinant
+being r, the precomputation works with the BigInteger of the scalar field, the decomposition with the
+fixed-size limb arrays.
+
+*****************************************************************************************************************/
+
+use algebra::{BigInteger, PrimeField, FpParameters};
+
+// maximal number of the 64-bit limbs of the scalar field elements
+const MAX_LIMBS: usize = 8;
+
+// signed integer as the sign (true for negative) and the magnitude
+type Int<B> = (bool, B);
+
+// This function computes the quotient and the remainder of a by the non-zero b, b being smaller
+// than the half of the BigInteger range
+fn divrem<B: BigInteger>(a: &B, b: &B) -> (B, B)
+{
+    let (mut q, mut r): (B, B) = (0.into(), 0.into());
+    for i in (0..a.num_bits() as usize).rev()
+    {
+        q.mul2();
+        r.mul2();
+        if a.get_bit(i) {r.add_nocarry(&1.into());}
+        if r >= *b
+        {
+            r.sub_noborrow(b);
+            q.add_nocarry(&1.into());
+        }
+    }
+    (q, r)
+}
+
+// This function computes a * 2^m / r rounded to the nearest integer, r being smaller than the half
+// of the BigInteger range and the quotient being in the range
+fn div_round_shifted<B: BigInteger>(a: &B, m: usize, r: &B) -> B
+{
+    let (mut q, mut rem): (B, B) = (0.into(), 0.into());
+    for i in (0..a.num_bits() as usize + m).rev()
+    {
+        assert!((q.num_bits() as usize) < 64 * q.as_ref().len(), "GLV rounding multiple out of range");
+        q.mul2();
+        rem.mul2();
+        if i >= m && a.get_bit(i - m) {rem.add_nocarry(&1.into());}
+        if rem >= *r
+        {
+            rem.sub_noborrow(r);
+            q.add_nocarry(&1.into());
+        }
+    }
+    rem.mul2();
+    if rem >= *r {q.add_nocarry(&1.into());}
+    q
+}
+
+// This function multiplies the integers
+//     RETURN: product, none if it is out of the BigInteger range
+fn mul<B: BigInteger>(a: &B, b: &B) -> Option<B>
+{
+    let mut p: B = 0.into();
+    for i in (0..b.num_bits() as usize).rev()
+    {
+        if p.num_bits() as usize == 64 * p.as_ref().len() {return None}
+        p.mul2();
+        if b.get_bit(i) && p.add_nocarry(a) {return None}
+    }
+    Some(p)
+}
+
+fn sadd<B: BigInteger>(a: &Int<B>, b: &Int<B>) -> Option<Int<B>>
+{
+    let (mut x, y) = if a.0 == b.0 || a.1 >= b.1 {(*a, b.1)} else {(*b, a.1)};
+    if a.0 == b.0 {if x.1.add_nocarry(&y) {return None}} else {x.1.sub_noborrow(&y);}
+    Some(x)
+}
+
+fn smul<B: BigInteger>(a: &Int<B>, b: &Int<B>) -> Option<Int<B>>
+{
+    mul(&a.1, &b.1).map(|p| (a.0 != b.0, p))
+}
+
+fn field<F: PrimeField>(x: &Int<F::BigInt>) -> F
+{
+    let f = F::from_repr(x.1);
+    if x.0 {-f} else {f}
+}
+
+fn limbs_field<F: PrimeField>(negative: bool, x: &[u64]) -> F
+{
+    let mut repr = F::BigInt::default();
+    for (l, v) in repr.as_mut().iter_mut().zip(x.iter()) {*l = *v}
+    let f = F::from_repr(repr);
+    if negative {-f} else {f}
+}
+
+// This function computes (k * g + 2^(m-1)) >> m on the stack
+//     k, g: integers of at most MAX_LIMBS limbs
+//     m: multiple of 64 not larger than 64 * MAX_LIMBS
+//     RETURN: rounded product
+fn mul_round(k: &[u64], g: &[u64], m: usize) -> [u64; MAX_LIMBS]
+{
+    let mut r = [0u64; 2 * MAX_LIMBS + 1];
+    for (i, x) in k.iter().enumerate()
+    {
+        let mut carry = 0u128;
+        for (j, y) in g.iter().enumerate()
+        {
+            let t = *x as u128 * *y as u128 + r[i + j] as u128 + carry;
+            r[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        r[i + g.len()] = carry as u64;
+    }
+
+    let mut carry = 1u128 << ((m - 1) % 64);
+    for l in r[(m - 1) / 64..].iter_mut()
+    {
+        let t = *l as u128 + carry;
+        *l = t as u64;
+        carry = t >> 64;
+        if carry == 0 {break}
+    }
+
+    let mut c = [0u64; MAX_LIMBS];
+    c.copy_from_slice(&r[m / 64..m / 64 + MAX_LIMBS]);
+    c
+}
+
+// GLV decomposition parameters of the scalar field
+#[derive(Clone, Debug)]
+pub struct GLV<F: PrimeField>
+{
+    pub lambda: F,          // eigenvalue of the endomorphism
+    pub v1: (F, F),         // short lattice basis
+    pub v2: (F, F),
+    g1: (bool, F::BigInt),  // b2 * 2^m / det rounded
+    g2: (bool, F::BigInt),  // -b1 * 2^m / det rounded
+    m: usize,
+}
+
+impl<F: PrimeField> GLV<F>
+{
+    // This function computes the decomposition parameters
+    //     lambda: eigenvalue of the endomorphism, a non-trivial cube root of unity
+    pub fn new(lambda: F) -> Self
+    {
+        let r = F::Params::MODULUS;
+        let limbs = r.as_ref().len();
+        assert!(limbs <= MAX_LIMBS, "scalar field too large for the GLV decomposition");
+        assert!((F::Params::MODULUS_BITS as usize) < 64 * limbs, "scalar field without a spare bit");
+
+        // extended Euclidean algorithm remainders r_i = s_i * r + t_i * lambda with the
+        // alternating signs of t_i, t_i being positive for the odd i
+        let (mut rs, mut ts): (Vec<F::BigInt>, Vec<F::BigInt>) = (vec![r, lambda.into_repr()], vec![0.into(), 1.into()]);
+        while !rs[rs.len() - 1].is_zero()
+        {
+            let i = rs.len() - 1;
+            let (q, rem) = divrem(&rs[i - 1], &rs[i]);
+            let mut t = mul(&q, &ts[i]).expect("Euclidean algorithm coefficient out of range");
+            t.add_nocarry(&ts[i - 1]);
+            rs.push(rem);
+            ts.push(t);
+        }
+        // vector (r_i, -t_i) of the lattice
+        let v = |i: usize| -> (Int<F::BigInt>, Int<F::BigInt>) {((false, rs[i]), (i % 2 == 1, ts[i]))};
+
+        // the last remainder not smaller than sqrt(r)
+        let l = (0..rs.len()).rev().find(|i| mul(&rs[*i], &rs[*i]).map_or(true, |sq| sq >= r)).unwrap();
+        let norm = |x: &(Int<F::BigInt>, Int<F::BigInt>)| smul(&x.0, &x.0).and_then(|a| sadd(&a, &smul(&x.1, &x.1)?)).map(|n| n.1);
+        let v1 = v(l + 1);
+        // the norm out of the range is the larger one
+        let shorter = |a: Option<F::BigInt>, b: Option<F::BigInt>| match (a, b) {(Some(a), Some(b)) => a < b, (a, b) => a.is_some() && b.is_none()};
+        let v2 = if l + 2 < rs.len() && shorter(norm(&v(l + 2)), norm(&v(l))) {v(l + 2)} else {v(l)};
+
+        let det = smul(&v1.0, &v2.1).and_then(|a| sadd(&a, &smul(&(!(v2.0).0, (v2.0).1), &v1.1)?)).expect("GLV basis out of range");
+        assert!(det.1 == r, "GLV basis of the wrong determinant");
+        let m = 64 * limbs;
+        // the short basis coordinates being about sqrt(r), the multiples fit into the scalar limbs
+        let g1 = ((v2.1).0 != det.0, div_round_shifted(&(v2.1).1, m, &r));
+        let g2 = (!(v1.1).0 != det.0, div_round_shifted(&(v1.1).1, m, &r));
+
+        GLV
+        {
+            lambda,
+            v1: (field(&v1.0), field(&v1.1)),
+            v2: (field(&v2.0), field(&v2.1)),
+            g1, g2, m,
+        }
+    }
+
+    // This function decomposes the scalar
+    //     k: scalar
+    //     RETURN: signed half-size scalars (k1, k2), the sign being true for the negative
+    //         scalar, such that k = k1 + k2 * lambda
+    pub fn decompose(&self, k: &F) -> ((bool, F::BigInt), (bool, F::BigInt))
+    {
+        let kr = k.into_repr();
+        let round = |g: &(bool, F::BigInt)| -> F {limbs_field(g.0, &mul_round(kr.as_ref(), g.1.as_ref(), self.m))};
+        let (c1, c2) = (round(&self.g1), round(&self.g2));
+        let k1 = *k - &(c1 * &self.v1.0) - &(c2 * &self.v2.0);
+        let k2 = -(c1 * &self.v1.1) - &(c2 * &self.v2.1);
+        (signed(k1), signed(k2))
+    }
+}
+
+// This function converts the field element to the signed integer of the smallest magnitude
+fn signed<F: PrimeField>(x: F) -> (bool, F::BigInt)
+{
+    let mut half = F::Params::MODULUS;
+    half.div2();
+    let repr = x.into_repr();
+    if repr > half {(true, (-x).into_repr())} else {(false, repr)}
+}
+
+#[cfg(test)]
+fn check<G: crate::commitment::CommitmentCurve>()
+where G::BaseField : PrimeField, G::ScalarField : crate::CommitmentField {
+    use algebra::{AffineCurve, ProjectiveCurve, VariableBaseMSM, UniformRand, Field, One, Zero};
+    use crate::{srs::{SRS, endos}, msm::MSMMethod};
+    use rand_core::OsRng;
+    let rng = &mut OsRng;
+
+    let (endo_q, endo_r) = endos::<G>();
+    assert!((endo_r.square() + &endo_r + &G::ScalarField::one()).is_zero());
+    let glv = GLV::new(endo_r);
+
+    // decomposition round trip
+    let signed = |(s, x): (bool, <G::ScalarField as PrimeField>::BigInt)| {
+        let x = G::ScalarField::from_repr(x);
+        if s {-x} else {x}
+    };
+    let mut scalars = vec![G::ScalarField::zero(), G::ScalarField::one(), -G::ScalarField::one(), endo_r, -endo_r];
+    scalars.extend((0..100).map(|_| G::ScalarField::rand(rng)));
+    for k in scalars.iter() {
+        let (k1, k2) = glv.decompose(k);
+        assert!((k1.1).num_bits() as usize <= <G::ScalarField as PrimeField>::Params::MODULUS_BITS as usize / 2 + 2);
+        assert!((k2.1).num_bits() as usize <= <G::ScalarField as PrimeField>::Params::MODULUS_BITS as usize / 2 + 2);
+        assert_eq!(signed(k1) + &(signed(k2) * &endo_r), *k);
+    }
+
+    // endomorphism scaling and multi-scalar multiplication
+    let p = G::prime_subgroup_generator().mul(G::ScalarField::rand(rng)).into_affine();
+    let (x, y) = p.to_coordinates().unwrap();
+    assert_eq!(G::of_coordinates(x * &endo_q, y).into_projective(), p.mul(endo_r));
+
+    let mut srs = SRS::<G>::create(64);
+    let bases = srs.g().to_vec();
+    for k in scalars.iter() {
+        assert_eq!(srs.scale(p, *k), p.mul(*k));
+    }
+    let repr = scalars[0..64].iter().map(|s| s.into_repr()).collect::<Vec<_>>();
+    let res = VariableBaseMSM::multi_scalar_mul(&bases, &repr);
+    assert_eq!(srs.msm(&bases, &scalars[0..64]), res);
+    // the cached endomorphism images of the SRS bases
+    assert_eq!(srs.msm_g(0, &scalars[0..64]), res);
+    assert_eq!(srs.msm_g(1, &scalars[1..33]), VariableBaseMSM::multi_scalar_mul(&bases[1..33], &repr[1..33]));
+    srs.msm_method = MSMMethod::BatchAffine;
+    assert_eq!(srs.msm(&bases, &scalars[0..64]), res);
+    assert_eq!(srs.msm_g(0, &scalars[0..64]), res);
+}
+
+#[test]
+fn bench_glv_msm() {
+    use algebra::{tweedle::dee::Affine, tweedle::fp::Fp, PrimeField, UniformRand, VariableBaseMSM};
+    use crate::{srs::SRS, msm::{MSMMethod, affine_multi_scalar_mul}};
+    use rand_core::OsRng;
+    use std::time::Instant;
+    let rng = &mut OsRng;
+
+    const N: usize = 1 << 16;
+
+    let mut srs = SRS::<Affine>::create(N);
+    let scalars = (0..N).map(|_| Fp::rand(rng)).collect::<Vec<_>>();
+    let repr = scalars.iter().map(|s| s.into_repr()).collect::<Vec<_>>();
+
+    let r = {
+        let start = Instant::now();
+        let res = VariableBaseMSM::multi_scalar_mul(srs.g(), &repr);
+        println!("projective msm {:?} {:?}", N, start.elapsed());
+        res
+    };
+    {
+        let start = Instant::now();
+        let res = srs.msm_g(0, &scalars);
+        println!("GLV projective msm {:?} {:?}", N, start.elapsed());
+        assert_eq!(r, res);
+    }
+    {
+        let start = Instant::now();
+        let res = affine_multi_scalar_mul(srs.g(), &repr);
+        println!("batch affine msm {:?} {:?}", N, start.elapsed());
+        assert_eq!(r, res);
+    }
+    srs.msm_method = MSMMethod::BatchAffine;
+    {
+        let start = Instant::now();
+        let res = srs.msm_g(0, &scalars);
+        println!("GLV batch affine msm {:?} {:?}", N, start.elapsed());
+        assert_eq!(r, res);
+    }
+}
+
+#[test]
+fn glv_tweedle() {
+    check::<algebra::tweedle::dee::Affine>();
+    check::<algebra::tweedle::dum::Affine>();
+}
+
+#[test]
+fn glv_pasta() {
+    check::<algebra::pasta::vesta::Affine>();
+    check::<algebra::pasta::pallas::Affine>();
+}
+
+#[test]
+fn glv_bn_382() {
+    check::<algebra::bn_382::g::Affine>();
+}
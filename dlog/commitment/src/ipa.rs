@@ -0,0 +1,580 @@
+/*****************************************************************************************************************
+
+This source file implements the inner product argument over the dlog SRS, the argument underlying the
+polynomial commitment opening. Two forms of the argument are implemented
+
+1. Opening of the committed vector a against the public vector b
+
+        P = <a, G> + r H,    <a, b> = v
+
+    the zero-knowledge argument with the random L & R blinders and the final Schnorr-like round of the
+    polynomial commitment opening, the proof being the OpeningProof. The vector b is either explicit,
+    then folded by the verifier in linear time, or the batch of the powers of the evaluation points,
+    then folded in logarithmic time.
+
+2. Opening of the two committed vectors a and b
+
+        P = <a, G> + <b, w o H>,    <a, b> = v
+
+    with G the bases g[0..n] and H the bases g[n..2n] of the SRS weighted by the public weights w,
+    the argument of Bulletproofs revealing the folded scalars, the zero-knowledge being left to the
+    protocol committing the vectors.
+
+The verifiers do not perform the multi-scalar multiplications, they accumulate their checks into the
+BatchMSM instead, so that a batch of the proofs, of either form and of the different lengths, together
+with any other check of the calling protocol over the SRS, is verified in one MSM.
+
+The verifier expects the sponge to have absorbed the commitment P. The explicit public vector b is
+absorbed by the argument itself before the first challenge, so that the challenges bind to the statement
+proven, while the evaluation points of the powers are bound by the protocol that derived them.
+
+*****************************************************************************************************************/
+
+use crate::srs::SRS;
+use crate::commitment::{CommitmentCurve, OpeningProof, Challenges, shift_scalar, b_poly, b_poly_coefficients, ceil_log2, pows,
+    squeeze_challenge, to_group, inner_prod, lr_challenges};
+use crate::CommitmentField;
+use algebra::{AffineCurve, Field, ProjectiveCurve, UniformRand, One, Zero};
+use oracle::{FqSponge, sponge::ScalarChallenge};
+use rand_core::RngCore;
+use rayon::prelude::*;
+
+type Fr<G> = <G as AffineCurve>::ScalarField;
+type Fq<G> = <G as AffineCurve>::BaseField;
+
+// public vector b of the inner product argument
+#[derive(Clone, Debug)]
+pub enum PublicVector<F>
+{
+    Vector(Vec<F>),         // explicit vector
+    Powers(Vec<F>, F),      // sum_i r^i (1, x_i, x_i^2, ...) for the points x_i and the scaling factor r
+}
+
+impl<F: Field> PublicVector<F>
+{
+    // This function expands the vector
+    //     n: vector length
+    //     RETURN: vector of the length n, padded with zeros if explicit
+    pub fn expand(&self, n: usize) -> Vec<F>
+    {
+        match self
+        {
+            PublicVector::Vector(v) =>
+            {
+                assert!(v.len() <= n);
+                let mut v = v.clone();
+                v.resize(n, F::zero());
+                v
+            }
+            PublicVector::Powers(x, r) =>
+            {
+                let mut scale = F::one();
+                let mut res = vec![F::zero(); n];
+                for e in x.iter()
+                {
+                    for (i, t) in pows(n, *e).iter().enumerate() {res[i] += &(scale * t)}
+                    scale *= r;
+                }
+                res
+            }
+        }
+    }
+
+    // This function folds the vector with the challenges of the argument rounds
+    //     chal: round challenges
+    //     RETURN: folded vector, none if the vector is longer than the folded length
+    pub fn fold(&self, chal: &Vec<F>) -> Option<F>
+    {
+        match self
+        {
+            PublicVector::Vector(v) =>
+            {
+                if v.len() > 1 << chal.len() {return None}
+                Some(b_poly_coefficients(chal).iter().zip(v.iter()).fold(F::zero(), |acc, (s, v)| acc + &(*s * v)))
+            }
+            PublicVector::Powers(x, r) =>
+            {
+                let mut scale = F::one();
+                let mut res = F::zero();
+                for e in x.iter()
+                {
+                    res += &(scale * &b_poly(chal, *e));
+                    scale *= r;
+                }
+                Some(res)
+            }
+        }
+    }
+}
+
+// This function absorbs the explicit public vector into the argument
+fn absorb_public<G: AffineCurve, EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>(sponge: &mut EFqSponge, b: &PublicVector<Fr<G>>)
+where G::ScalarField : CommitmentField
+{
+    if let PublicVector::Vector(v) = b {sponge.absorb_fr(&v.iter().map(|x| shift_scalar(*x)).collect::<Vec<_>>())}
+}
+
+// opening proof of the two committed vectors
+#[derive(Clone, Debug)]
+pub struct VectorProof<G: AffineCurve>
+{
+    pub lr: Vec<(G, G)>,    // vector of rounds of L & R commitments
+    pub a: Fr<G>,           // folded vector a
+    pub b: Fr<G>,           // folded vector b
+}
+
+impl<G: AffineCurve> VectorProof<G> where G::ScalarField : CommitmentField
+{
+    pub fn challenges<EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>(&self, endo_r: &Fr<G>, sponge : &mut EFqSponge) -> Challenges<Fr<G>>
+    {
+        lr_challenges(&self.lr, endo_r, sponge)
+    }
+}
+
+// opening statement of the committed vector against the public vector
+pub struct Opening<'a, G: AffineCurve, EFqSponge>
+{
+    pub sponge: &'a mut EFqSponge,          // sponge having absorbed the commitment
+    pub commitment: Vec<(G, Fr<G>)>,        // commitment as the linear combination of the group elements
+    pub b: PublicVector<Fr<G>>,             // public vector
+    pub value: Fr<G>,                       // inner product
+    pub proof: &'a OpeningProof<G>,         // opening proof
+}
+
+// opening statement of the two committed vectors
+pub struct VectorOpening<'a, G: AffineCurve, EFqSponge>
+{
+    pub sponge: &'a mut EFqSponge,          // sponge having absorbed the commitment
+    pub commitment: Vec<(G, Fr<G>)>,        // commitment as the linear combination of the group elements
+    pub weights: Vec<Fr<G>>,                // weights of the bases of the vector b
+    pub value: Fr<G>,                       // inner product
+    pub proof: &'a VectorProof<G>,          // opening proof
+}
+
+// multi-scalar multiplication accumulating the batch of the checks over the SRS
+pub struct BatchMSM<G: AffineCurve>
+{
+    points: Vec<G>,
+    scalars: Vec<Fr<G>>,
+    rand_base: Fr<G>,
+    sg_rand_base: Fr<G>,
+    rand_base_i: Fr<G>,
+    sg_rand_base_i: Fr<G>,
+}
+
+impl<G: CommitmentCurve> BatchMSM<G> where G::ScalarField : CommitmentField
+{
+    // This function creates the empty batch over the SRS bases h and g, the g padded with zeros to
+    // the power of two length
+    pub fn create(srs: &SRS<G>, rng: &mut dyn RngCore) -> Self
+    {
+        let padded_length = 1 << ceil_log2(srs.g().len());
+        let mut points = vec![srs.h];
+        points.extend(srs.g().to_vec());
+        points.extend(vec![G::zero(); padded_length - srs.g().len()]);
+
+        BatchMSM
+        {
+            scalars: vec![Fr::<G>::zero(); points.len()],
+            points,
+            rand_base: Fr::<G>::rand(rng),
+            sg_rand_base: Fr::<G>::rand(rng),
+            rand_base_i: Fr::<G>::one(),
+            sg_rand_base_i: Fr::<G>::one(),
+        }
+    }
+
+    // This function returns the randomiser of the current check of the batch
+    pub fn randomiser(&self) -> Fr<G> {self.rand_base_i}
+
+    // This function moves the batch on to the next check with the fresh randomisers
+    pub fn next(&mut self)
+    {
+        self.rand_base_i *= &self.rand_base;
+        self.sg_rand_base_i *= &self.sg_rand_base;
+    }
+
+    // This function adds the term to the batch
+    pub fn push(&mut self, point: G, scalar: Fr<G>)
+    {
+        self.points.push(point);
+        self.scalars.push(scalar);
+    }
+
+    // This function adds the scalar to the one of the SRS base h
+    pub fn add_h(&mut self, scalar: Fr<G>)
+    {
+        self.scalars[0] += &scalar;
+    }
+
+    // This function adds the scalars to the ones of the SRS bases g[offset..offset+scalars.len()]
+    pub fn add_g(&mut self, offset: usize, scalars: &[Fr<G>])
+    {
+        for (s, t) in self.scalars[offset + 1..].iter_mut().zip(scalars.iter()) {*s += t}
+    }
+
+    // This function verifies the batch
+    //     RETURN: verification status
+    pub fn check(&self, srs: &SRS<G>) -> bool
+    {
+        srs.msm(&self.points, &self.scalars) == G::Projective::zero()
+    }
+}
+
+pub struct InnerProductArgument<'a, G: CommitmentCurve>
+{
+    pub srs: &'a SRS<G>,
+    pub group_map: &'a G::Map,
+}
+
+impl<'a, G: CommitmentCurve> InnerProductArgument<'a, G> where G::ScalarField : CommitmentField
+{
+    pub fn create(srs: &'a SRS<G>, group_map: &'a G::Map) -> Self
+    {
+        InnerProductArgument {srs, group_map}
+    }
+
+    // This function proves the opening of the committed vector against the public vector
+    //     a: committed vector, the argument spanning its length rounded up to the power of two
+    //     b: public vector
+    //     blinding: blinding factor of the commitment
+    //     sponge: sponge having absorbed the commitment
+    //     rng: randomness source
+    //     RETURN: opening proof
+    pub fn prove<EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        &self,
+        mut a: Vec<Fr<G>>,
+        b: &PublicVector<Fr<G>>,
+        blinding: Fr<G>,
+        mut sponge: EFqSponge,
+        rng: &mut dyn RngCore,
+    ) -> OpeningProof<G>
+    {
+        let srs = self.srs;
+        let rounds = ceil_log2(std::cmp::max(a.len(), if let PublicVector::Vector(v) = b {v.len()} else {1}));
+        let padded_length = 1 << rounds;
+        assert!(rounds <= ceil_log2(srs.g().len()));
+
+        let mut g = srs.g()[0..std::cmp::min(padded_length, srs.g().len())].to_vec();
+        g.extend(vec![G::zero(); padded_length - g.len()]);
+        a.resize(padded_length, Fr::<G>::zero());
+        absorb_public::<G, _>(&mut sponge, b);
+        let mut b = b.expand(padded_length);
+
+        sponge.absorb_fr(&[shift_scalar(inner_prod(&a, &b))]);
+
+        let t = sponge.challenge_fq();
+        let u: G = to_group(self.group_map, t);
+
+        let mut lr = vec![];
+        let mut blinders = vec![];
+        let mut chals = vec![];
+        let mut chal_invs = vec![];
+
+        for _ in 0..rounds {
+            let n = g.len() / 2;
+            let (g_lo, g_hi) = (g[0..n].to_vec(), g[n..].to_vec());
+            let (a_lo, a_hi) = (&a[0..n], &a[n..]);
+            let (b_lo, b_hi) = (&b[0..n], &b[n..]);
+
+            let rand_l = Fr::<G>::rand(rng);
+            let rand_r = Fr::<G>::rand(rng);
+
+            let l = srs.msm(
+                &[&g[0..n], &[srs.h, u]].concat(),
+                &[&a[n..], &[rand_l, inner_prod(a_hi, b_lo)]].concat()
+            ).into_affine();
+
+            let r = srs.msm(
+                &[&g[n..], &[srs.h, u]].concat(),
+                &[&a[0..n], &[rand_r, inner_prod(a_lo, b_hi)]].concat()
+            ).into_affine();
+
+            lr.push((l, r));
+            blinders.push((rand_l, rand_r));
+
+            sponge.absorb_g(&[l]);
+            sponge.absorb_g(&[r]);
+
+            let u = squeeze_challenge(&srs.endo_r, &mut sponge);
+            let u_inv = u.inverse().unwrap();
+
+            chals.push(u);
+            chal_invs.push(u_inv);
+
+            a = fold(a_lo, a_hi, u_inv);
+            b = fold(b_lo, b_hi, u);
+            g = G::combine_one(&g_lo, &g_hi, u);
+        }
+
+        assert!(g.len() == 1);
+        let a0 = a[0];
+        let b0 = b[0];
+        let g0 = g[0];
+
+        let r_prime = blinders
+            .iter()
+            .zip(chals.iter().zip(chal_invs.iter()))
+            .map(|((l, r), (u, u_inv))| ((*l) * u_inv) + &(*r * u))
+            .fold(blinding, |acc, x| acc + &x);
+
+        let d = Fr::<G>::rand(rng);
+        let r_delta = Fr::<G>::rand(rng);
+
+        let delta = (srs.scale((g0.into_projective() + &srs.scale(u, b0)).into_affine(), d)
+            + &srs.scale(srs.h, r_delta))
+            .into_affine();
+
+        sponge.absorb_g(&[delta]);
+        let c = ScalarChallenge(sponge.challenge()).to_field(&srs.endo_r);
+
+        let z1 = a0 * &c + &d;
+        let z2 = c * &r_prime + &r_delta;
+
+        OpeningProof {
+            delta,
+            lr,
+            z1,
+            z2,
+            sg: g0,
+        }
+    }
+
+    // This function proves the opening of the two committed vectors
+    //     a: committed vector in the bases g[0..n]
+    //     b: committed vector in the weighted bases g[n..2n]
+    //     weights: weights of the bases of the vector b
+    //     sponge: sponge having absorbed the commitment
+    //     RETURN: opening proof
+    pub fn prove_vectors<EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        &self,
+        mut a: Vec<Fr<G>>,
+        mut b: Vec<Fr<G>>,
+        weights: &[Fr<G>],
+        mut sponge: EFqSponge,
+    ) -> VectorProof<G>
+    {
+        let srs = self.srs;
+        let n = a.len();
+        assert!(n.is_power_of_two() && b.len() == n && weights.len() == n && 2 * n <= srs.g().len());
+
+        let mut g = srs.g()[0..n].to_vec();
+        let mut h = srs.g()[n..2*n].par_iter().zip(weights.par_iter()).map(|(h, w)| srs.scale(*h, *w)).collect::<Vec<_>>();
+        G::Projective::batch_normalization(&mut h);
+        let mut h = h.iter().map(|h| h.into_affine()).collect::<Vec<_>>();
+
+        sponge.absorb_fr(&[shift_scalar(inner_prod(&a, &b))]);
+
+        let t = sponge.challenge_fq();
+        let u: G = to_group(self.group_map, t);
+
+        let mut lr = vec![];
+
+        for _ in 0..ceil_log2(n) {
+            let n = g.len() / 2;
+            let (g_lo, g_hi) = (g[0..n].to_vec(), g[n..].to_vec());
+            let (h_lo, h_hi) = (h[0..n].to_vec(), h[n..].to_vec());
+            let (a_lo, a_hi) = (&a[0..n], &a[n..]);
+            let (b_lo, b_hi) = (&b[0..n], &b[n..]);
+
+            let l = srs.msm(
+                &[&g_lo[..], &h_hi[..], &[u]].concat(),
+                &[a_hi, b_lo, &[inner_prod(a_hi, b_lo)]].concat()
+            ).into_affine();
+
+            let r = srs.msm(
+                &[&g_hi[..], &h_lo[..], &[u]].concat(),
+                &[a_lo, b_hi, &[inner_prod(a_lo, b_hi)]].concat()
+            ).into_affine();
+
+            lr.push((l, r));
+
+            sponge.absorb_g(&[l]);
+            sponge.absorb_g(&[r]);
+
+            let u = squeeze_challenge(&srs.endo_r, &mut sponge);
+            let u_inv = u.inverse().unwrap();
+
+            a = fold(a_lo, a_hi, u_inv);
+            b = fold(b_lo, b_hi, u);
+            g = G::combine_one(&g_lo, &g_hi, u);
+            h = G::combine_one(&h_lo, &h_hi, u_inv);
+        }
+
+        VectorProof {lr, a: a[0], b: b[0]}
+    }
+
+    // This function accumulates the opening of the committed vector against the public vector
+    //     batch: multi-scalar multiplication of the batch
+    //     opening: opening statement and proof
+    //     RETURN: false if the proof is malformed, the verification status being the one of the batch
+    pub fn accumulate<EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        &self,
+        batch: &mut BatchMSM<G>,
+        opening: &mut Opening<G, EFqSponge>,
+    ) -> bool
+    {
+        // Verifier checks
+        // c Q + delta = z1 (G + b U) + z2 H
+        // where Q = sum_j (chal_invs[j] L_j + chals[j] R_j) + P + v U
+        //
+        // and for the correctness of sg
+        // sg = < s, self.g >
+
+        let srs = self.srs;
+        let proof = opening.proof;
+
+        // the proofs of the trimmed openings fold the prefix of the SRS only, their s vectors
+        // are added to the prefix of the SRS scalars, and none may exceed the SRS
+        if proof.lr.len() > ceil_log2(srs.g().len()) {return false}
+
+        let sponge = &mut *opening.sponge;
+        absorb_public::<G, _>(sponge, &opening.b);
+        sponge.absorb_fr(&[shift_scalar(opening.value)]);
+
+        let t = sponge.challenge_fq();
+        let u: G = to_group(self.group_map, t);
+
+        let Challenges { chal, chal_inv } = proof.challenges::<EFqSponge>(&srs.endo_r, sponge);
+
+        sponge.absorb_g(&[proof.delta]);
+        let c = ScalarChallenge(sponge.challenge()).to_field(&srs.endo_r);
+
+        let b0 = match opening.b.fold(&chal) {Some(b0) => b0, None => return false};
+        let s = b_poly_coefficients(&chal);
+
+        let rand_base_i = batch.rand_base_i;
+        let sg_rand_base_i = batch.sg_rand_base_i;
+        let neg_rand_base_i = -rand_base_i;
+
+        // TERM
+        // - rand_base_i z1 G
+        //
+        // we also add -sg_rand_base_i * G to check correctness of sg.
+        batch.push(proof.sg, neg_rand_base_i * &proof.z1 - &sg_rand_base_i);
+
+        // Here we add
+        // sg_rand_base_i * ( < s, self.g > )
+        // =
+        // < sg_rand_base_i s, self.g >
+        //
+        // to check correctness of the sg component.
+        batch.add_g(0, &s.par_iter().map(|s| sg_rand_base_i * s).collect::<Vec<_>>());
+
+        // TERM
+        // - rand_base_i * z2 * H
+        batch.add_h(neg_rand_base_i * &proof.z2);
+
+        // TERM
+        // rand_base_i c Q - rand_base_i * (z1 * b0 * U)
+        let rand_base_i_c_i = c * &rand_base_i;
+        for ((l, r), (u_inv, u)) in proof.lr.iter().zip(chal_inv.iter().zip(chal.iter()))
+        {
+            batch.push(*l, rand_base_i_c_i * u_inv);
+            batch.push(*r, rand_base_i_c_i * u);
+        }
+        for (p, x) in opening.commitment.iter() {batch.push(*p, rand_base_i_c_i * x)}
+        batch.push(u, rand_base_i_c_i * &opening.value - &(rand_base_i * &(proof.z1 * &b0)));
+
+        // TERM
+        // rand_base_i delta
+        batch.push(proof.delta, rand_base_i);
+
+        batch.next();
+        true
+    }
+
+    // This function accumulates the opening of the two committed vectors
+    //     batch: multi-scalar multiplication of the batch
+    //     opening: opening statement and proof
+    //     RETURN: false if the proof is malformed, the verification status being the one of the batch
+    pub fn accumulate_vectors<EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        &self,
+        batch: &mut BatchMSM<G>,
+        opening: &mut VectorOpening<G, EFqSponge>,
+    ) -> bool
+    {
+        // Verifier checks
+        // Q = a G + b H + a b U
+        // where Q = sum_j (chal_invs[j] L_j + chals[j] R_j) + P + v U
+        // and G = < s, g[0..n] >, H = < s_inv, w o g[n..2n] >
+
+        let srs = self.srs;
+        let proof = opening.proof;
+        let n = 1 << proof.lr.len();
+        if 2 * n > srs.g().len() || opening.weights.len() != n {return false}
+
+        let sponge = &mut *opening.sponge;
+        sponge.absorb_fr(&[shift_scalar(opening.value)]);
+
+        let t = sponge.challenge_fq();
+        let u: G = to_group(self.group_map, t);
+
+        let Challenges { chal, chal_inv } = proof.challenges::<EFqSponge>(&srs.endo_r, sponge);
+        let s = b_poly_coefficients(&chal);
+        let s_inv = b_poly_coefficients(&chal_inv);
+
+        let rand_base_i = batch.rand_base_i;
+        let neg_rand_base_i = -rand_base_i;
+
+        // TERM
+        // rand_base_i Q
+        for ((l, r), (u_inv, u)) in proof.lr.iter().zip(chal_inv.iter().zip(chal.iter()))
+        {
+            batch.push(*l, rand_base_i * u_inv);
+            batch.push(*r, rand_base_i * u);
+        }
+        for (p, x) in opening.commitment.iter() {batch.push(*p, rand_base_i * x)}
+        batch.push(u, rand_base_i * &(opening.value - &(proof.a * &proof.b)));
+
+        // TERM
+        // - rand_base_i (a G + b H)
+        let a = neg_rand_base_i * &proof.a;
+        let b = neg_rand_base_i * &proof.b;
+        batch.add_g(0, &s.par_iter().map(|s| a * s).collect::<Vec<_>>());
+        batch.add_g(n, &s_inv.par_iter().zip(opening.weights.par_iter()).map(|(s, w)| b * s * w).collect::<Vec<_>>());
+
+        batch.next();
+        true
+    }
+
+    // This function verifies the batch of the openings of the committed vectors against the public vectors
+    //     batch: batch of the opening statements and proofs
+    //     rng: randomness source
+    //     RETURN: verification status
+    pub fn verify<EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        &self,
+        batch: &mut Vec<Opening<G, EFqSponge>>,
+        rng: &mut dyn RngCore,
+    ) -> bool
+    {
+        let mut msm = BatchMSM::create(self.srs, rng);
+        batch.iter_mut().all(|opening| self.accumulate(&mut msm, opening)) && msm.check(self.srs)
+    }
+
+    // This function verifies the batch of the openings of the two committed vectors
+    //     batch: batch of the opening statements and proofs
+    //     rng: randomness source
+    //     RETURN: verification status
+    pub fn verify_vectors<EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        &self,
+        batch: &mut Vec<VectorOpening<G, EFqSponge>>,
+        rng: &mut dyn RngCore,
+    ) -> bool
+    {
+        let mut msm = BatchMSM::create(self.srs, rng);
+        batch.iter_mut().all(|opening| self.accumulate_vectors(&mut msm, opening)) && msm.check(self.srs)
+    }
+}
+
+// This function folds the vector halves as lo + x * hi
+fn fold<F: Field>(lo: &[F], hi: &[F], x: F) -> Vec<F>
+{
+    lo.par_iter().zip(hi).map(|(&lo, &hi)| hi * &x + &lo).collect()
+}
@@ -3,6 +3,11 @@ mod qnr_field;
 pub use qnr_field::*;
 pub mod srs;
 pub mod commitment;
+pub mod msm;
+pub mod glv;
+pub mod ipa;
+pub mod range;
+pub use poly_commitment::fixed;
 use algebra::{tweedle, bn_382, pasta};
 
 pub trait CommitmentField : QnrField {
@@ -0,0 +1,228 @@
+/*****************************************************************************************************************
+
+This source file implements the Pippenger multi-scalar multiplication with the batch-affine and the
+projective bucket accumulation.
+
+The scalars are split into the windows of c bits, the windows are processed in parallel. Within a
+window every base is put into the bucket of its window digit, the buckets are then reduced to single
+points in rounds, every round adding the pairs of the points of all the buckets in affine coordinates
+with a single batched inversion, see combine::batch_add_assign. The reduced buckets are summed with
+the running sum, and the window sums are combined by doubling.
+
+Unlike VariableBaseMSM::multi_scalar_mul, that processes the windows of all the MODULUS_BITS bits,
+the windows span the longest scalar only, so that the half-size scalars of the GLV decomposition are
+processed in half of the windows.
+
+*****************************************************************************************************************/
+
+use algebra::{
+    curves::models::short_weierstrass_jacobian::{GroupAffine as SWJAffine, GroupProjective as SWJProjective},
+    AffineCurve, BigInteger, PrimeField, ProjectiveCurve, SWModelParameters, Zero,
+};
+use rayon::prelude::*;
+use crate::combine::batch_add_assign;
+
+// multi-scalar multiplication algorithm
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MSMMethod
+{
+    Projective,     // Pippenger with projective bucket accumulation
+    BatchAffine,    // Pippenger with batch-affine bucket accumulation
+}
+
+impl Default for MSMMethod
+{
+    fn default() -> Self {MSMMethod::Projective}
+}
+
+// window size for the number of the bases
+fn window_size(n: usize) -> usize
+{
+    if n < 32 {3} else {((usize::MAX.count_ones() - n.leading_zeros()) as usize * 69 / 100) + 2}
+}
+
+// This function reduces the buckets to at most one point each
+fn reduce_buckets<P: SWModelParameters>(buckets: &mut Vec<Vec<SWJAffine<P>>>)
+{
+    let mut denominators = Vec::new();
+    loop
+    {
+        let (mut v0, mut v1, mut owner) = (Vec::new(), Vec::new(), Vec::new());
+        for (i, b) in buckets.iter_mut().enumerate()
+        {
+            while b.len() >= 2
+            {
+                v1.push(b.pop().unwrap());
+                v0.push(b.pop().unwrap());
+                owner.push(i);
+            }
+        }
+        if v0.is_empty() {break}
+
+        denominators.resize(v0.len(), P::BaseField::zero());
+        batch_add_assign(&mut denominators[0..v0.len()], &mut v0, &v1);
+        for (p, i) in v0.into_iter().zip(owner.into_iter()) {buckets[i].push(p)}
+    }
+}
+
+// This function computes the multi-scalar multiplication
+//     bases: group elements
+//     scalars: scalars in their integer representation, the extra bases or scalars are ignored
+//     RETURN: sum of the bases scaled by the scalars
+pub fn affine_multi_scalar_mul<P: SWModelParameters>
+(
+    bases: &[SWJAffine<P>],
+    scalars: &[<P::ScalarField as PrimeField>::BigInt],
+) -> SWJProjective<P>
+{
+    let c = window_size(std::cmp::min(bases.len(), scalars.len()));
+    let windows = windows(scalars, c);
+
+    let sums = windows.par_iter().map
+    (
+        |w_start|
+        {
+            let mut buckets = vec![Vec::new(); (1 << c) - 1];
+            for (s, g) in scalars.iter().zip(bases.iter()).filter(|(s, g)| !s.is_zero() && !g.is_zero())
+            {
+                let mut s = *s;
+                s.divn(*w_start as u32);
+                let d = (s.as_ref()[0] % (1 << c)) as usize;
+                if d != 0 {buckets[d - 1].push(*g)}
+            }
+            reduce_buckets(&mut buckets);
+
+            let mut res = SWJProjective::<P>::zero();
+            let mut running = SWJProjective::<P>::zero();
+            for b in buckets.iter().rev()
+            {
+                if let Some(p) = b.first() {running.add_assign_mixed(p)}
+                res += &running;
+            }
+            res
+        }
+    ).collect::<Vec<_>>();
+
+    combine_windows(&sums, c)
+}
+
+// This function computes the multi-scalar multiplication with the projective bucket accumulation
+//     bases: group elements
+//     scalars: scalars in their integer representation, the extra bases or scalars are ignored
+//     RETURN: sum of the bases scaled by the scalars
+pub fn projective_multi_scalar_mul<G: AffineCurve>
+(
+    bases: &[G],
+    scalars: &[<G::ScalarField as PrimeField>::BigInt],
+) -> G::Projective
+{
+    let c = window_size(std::cmp::min(bases.len(), scalars.len()));
+    let windows = windows(scalars, c);
+
+    let sums = windows.par_iter().map
+    (
+        |w_start|
+        {
+            let mut buckets = vec![G::Projective::zero(); (1 << c) - 1];
+            for (s, g) in scalars.iter().zip(bases.iter()).filter(|(s, g)| !s.is_zero() && !g.is_zero())
+            {
+                let mut s = *s;
+                s.divn(*w_start as u32);
+                let d = (s.as_ref()[0] % (1 << c)) as usize;
+                if d != 0 {buckets[d - 1].add_assign_mixed(g)}
+            }
+
+            let mut res = G::Projective::zero();
+            let mut running = G::Projective::zero();
+            for b in buckets.iter().rev()
+            {
+                running += b;
+                res += &running;
+            }
+            res
+        }
+    ).collect::<Vec<_>>();
+
+    combine_windows(&sums, c)
+}
+
+// This function returns the starting bits of the windows of c bits spanning the longest scalar
+fn windows<B: BigInteger>(scalars: &[B], c: usize) -> Vec<usize>
+{
+    let num_bits = scalars.iter().map(|s| s.num_bits() as usize).max().unwrap_or(0);
+    (0..num_bits).step_by(c).collect()
+}
+
+// This function combines the window sums by doubling
+fn combine_windows<G: ProjectiveCurve>(sums: &[G], c: usize) -> G
+{
+    if sums.is_empty() {return G::zero()}
+    sums[1..].iter().rev().fold
+    (
+        G::zero(),
+        |mut total, sum|
+        {
+            total += sum;
+            for _ in 0..c {total.double_in_place();}
+            total
+        }
+    ) + &sums[0]
+}
+
+#[test]
+fn msm_equivalence() {
+    use algebra::{bn_382::g::Affine, bn_382::Fq, UniformRand, VariableBaseMSM, One};
+    use rand_core::OsRng;
+    let rng = &mut OsRng;
+
+    for n in [1, 2, 31, 100, 1000].iter() {
+        let mut bases = (0..*n).map(|_| Affine::prime_subgroup_generator().mul(Fq::rand(rng)).into_affine()).collect::<Vec<_>>();
+        let mut scalars = (0..*n).map(|_| Fq::rand(rng)).collect::<Vec<_>>();
+
+        // the repeated and the opposite bases, the zero base and scalar
+        if *n >= 31 {
+            bases[1] = bases[0];
+            bases[2] = -bases[0];
+            bases[3] = Affine::zero();
+            scalars[1] = scalars[0];
+            scalars[2] = scalars[0];
+            scalars[4] = Fq::zero();
+            scalars[5] = Fq::one();
+        }
+
+        let scalars = scalars.iter().map(|s| s.into_repr()).collect::<Vec<_>>();
+        let res = VariableBaseMSM::multi_scalar_mul(&bases, &scalars);
+        assert_eq!(affine_multi_scalar_mul(&bases, &scalars), res);
+        assert_eq!(projective_multi_scalar_mul(&bases, &scalars), res);
+    }
+}
+
+#[test]
+fn bench_msm() {
+    use algebra::{bn_382::g::Affine, bn_382::Fq, UniformRand, VariableBaseMSM};
+    use rand_core::OsRng;
+    use std::time::Instant;
+    let rng = &mut OsRng;
+
+    const N: usize = 1 << 16;
+
+    let g = Affine::prime_subgroup_generator().mul(Fq::rand(rng));
+    let mut bases = (0..N).scan(g, |p, _| {p.double_in_place(); Some(*p)}).collect::<Vec<_>>();
+    ProjectiveCurve::batch_normalization(bases.as_mut_slice());
+    let bases = bases.iter().map(|p| p.into_affine()).collect::<Vec<_>>();
+    let scalars = (0..N).map(|_| Fq::rand(rng).into_repr()).collect::<Vec<_>>();
+
+    let r = {
+        let start = Instant::now();
+        let res = VariableBaseMSM::multi_scalar_mul(&bases, &scalars);
+        println!("projective msm {:?} {:?}", N, start.elapsed());
+        res
+    };
+
+    {
+        let start = Instant::now();
+        let res = affine_multi_scalar_mul(&bases, &scalars);
+        println!("batch affine msm {:?} {:?}", N, start.elapsed());
+        assert_eq!(r, res);
+    }
+}
@@ -0,0 +1,239 @@
+/*****************************************************************************************************************
+
+This source file implements the aggregated Bulletproofs range proofs over the dlog SRS.
+
+The values v_j, j = 0..m, are committed as V_j = v_j B + gamma_j H with B the last base of the SRS and H
+the SRS blinding base. The proof of v_j lying in [0, 2^n) for all j commits the bits a_L of the values,
+i = j n + k indexing the bit k of the value j, and a_R = a_L - 1 in the bases G = g[0..N] and H' = g[N..2N]
+of the SRS, N = n m, and proves
+
+    t(x) = < l(x), r(x) >
+
+    l(X) = a_L - z 1 + s_L X
+    r(X) = y^N o (a_R + z 1 + s_R X) + sum_j z^(2+j) (0^(jn) || 2^n || 0^((m-1-j)n))
+
+with the inner product argument over the two committed vectors, the bases H' weighted by y^-N.
+The verifier checks
+
+    t B + tau_x H = sum_j z^(2+j) V_j + delta(y, z) B + x T1 + x^2 T2
+    delta(y, z) = (z - z^2) < 1, y^N > - sum_j z^(3+j) (2^n - 1)
+
+together with the inner product argument, all the checks of the batch of the proofs in one MSM.
+
+*****************************************************************************************************************/
+
+use crate::srs::SRS;
+use crate::commitment::{CommitmentCurve, shift_scalar, inner_prod};
+use crate::ipa::{InnerProductArgument, VectorProof, VectorOpening, BatchMSM};
+use crate::CommitmentField;
+use algebra::{AffineCurve, BigInteger, Field, PrimeField, ProjectiveCurve, UniformRand, One, Zero};
+use oracle::{FqSponge, sponge::ScalarChallenge, rndoracle::ProofError};
+use rand_core::RngCore;
+
+type Fr<G> = <G as AffineCurve>::ScalarField;
+type Fq<G> = <G as AffineCurve>::BaseField;
+
+#[derive(Clone, Debug)]
+pub struct RangeProof<G: AffineCurve>
+{
+    pub a: G,               // commitment to the bits and the bits less one
+    pub s: G,               // commitment to the blinding vectors
+    pub t1: G,              // commitment to the linear coefficient of t
+    pub t2: G,              // commitment to the quadratic coefficient of t
+    pub tau_x: Fr<G>,       // blinding factor of t(x)
+    pub mu: Fr<G>,          // blinding factor of A + x S
+    pub t: Fr<G>,           // t(x)
+    pub ipa: VectorProof<G>,// inner product argument of < l(x), r(x) > = t(x)
+}
+
+// This function returns the base of the committed values
+pub fn value_base<G: CommitmentCurve>(srs: &SRS<G>) -> G
+{
+    srs.g()[srs.g().len() - 1]
+}
+
+// This function commits the value
+//     v: value
+//     gamma: blinding factor
+//     RETURN: commitment v B + gamma H
+pub fn commit<G: CommitmentCurve>(srs: &SRS<G>, v: Fr<G>, gamma: Fr<G>) -> G where G::ScalarField : CommitmentField
+{
+    srs.msm(&[value_base(srs), srs.h], &[v, gamma]).into_affine()
+}
+
+// This function squeezes the challenge of the range proof
+fn challenge<G: CommitmentCurve, EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>(endo_r: &Fr<G>, sponge: &mut EFqSponge) -> Fr<G>
+where G::ScalarField : CommitmentField
+{
+    ScalarChallenge(sponge.challenge()).to_field(endo_r)
+}
+
+// This function absorbs the statement of the range proof
+fn absorb<G: CommitmentCurve, EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>(sponge: &mut EFqSponge, n: usize, commitments: &[G])
+{
+    sponge.absorb_fr(&[Fr::<G>::from(n as u64), Fr::<G>::from(commitments.len() as u64)]);
+    sponge.absorb_g(commitments);
+}
+
+// This function computes the powers 1, x, x^2, ... of the field element
+fn powers<F: Field>(x: F, n: usize) -> Vec<F>
+{
+    (0..n).scan(F::one(), |acc, _| {let r = *acc; *acc *= &x; Some(r)}).collect()
+}
+
+// This function checks whether the proof of the m values of n bits fits into the SRS
+fn fits<G: CommitmentCurve>(srs: &SRS<G>, n: usize, m: usize) -> bool
+{
+    n > 0 && m > 0 && (n * m).is_power_of_two() && 2 * n * m < srs.g().len()
+}
+
+impl<G: CommitmentCurve> RangeProof<G> where G::ScalarField : CommitmentField
+{
+    // This function proves the committed values lie in [0, 2^n)
+    //     srs: structured reference string
+    //     group_map: group map of the inner product argument
+    //     n: bit size of the range
+    //     values: values, the number of them and n having their product a power of two
+    //     blindings: blinding factors of the commitments to the values
+    //     sponge: sponge
+    //     rng: randomness source
+    //     RETURN: commitments to the values and the range proof
+    pub fn create<EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        srs: &SRS<G>,
+        group_map: &G::Map,
+        n: usize,
+        values: &[Fr<G>],
+        blindings: &[Fr<G>],
+        mut sponge: EFqSponge,
+        rng: &mut dyn RngCore,
+    ) -> Result<(Vec<G>, Self), ProofError>
+    {
+        let m = values.len();
+        if !fits(srs, n, m) || blindings.len() != m {return Err(ProofError::ProofCreation)}
+        if values.iter().any(|v| v.into_repr().num_bits() as usize > n) {return Err(ProofError::WitnessCsInconsistent)}
+        let size = n * m;
+        let (g, h) = (&srs.g()[0..size], &srs.g()[size..2*size]);
+
+        let commitments = values.iter().zip(blindings.iter()).map(|(v, gamma)| commit(srs, *v, *gamma)).collect::<Vec<_>>();
+        absorb(&mut sponge, n, &commitments);
+
+        // commit the bits
+        let a_l = values.iter().map
+        (
+            |v|
+            {
+                let v = v.into_repr();
+                (0..n).map(move |k| if v.get_bit(k) {Fr::<G>::one()} else {Fr::<G>::zero()})
+            }
+        ).flatten().collect::<Vec<_>>();
+        let a_r = a_l.iter().map(|a| *a - &Fr::<G>::one()).collect::<Vec<_>>();
+        let (s_l, s_r) = ((0..size).map(|_| Fr::<G>::rand(rng)).collect::<Vec<_>>(), (0..size).map(|_| Fr::<G>::rand(rng)).collect::<Vec<_>>());
+        let (alpha, rho) = (Fr::<G>::rand(rng), Fr::<G>::rand(rng));
+
+        let bases = [g, h, &[srs.h]].concat();
+        let a = srs.msm(&bases, &[&a_l[..], &a_r, &[alpha]].concat()).into_affine();
+        let s = srs.msm(&bases, &[&s_l[..], &s_r, &[rho]].concat()).into_affine();
+        sponge.absorb_g(&[a, s]);
+        let y = challenge::<G, _>(&srs.endo_r, &mut sponge);
+        let z = challenge::<G, _>(&srs.endo_r, &mut sponge);
+
+        // the coefficients of l(X) = l0 + l1 X, r(X) = r0 + r1 X
+        let yn = powers(y, size);
+        let zm = powers(z, m + 2);
+        let two = powers(Fr::<G>::from(2u64), n);
+        let l0 = a_l.iter().map(|a| *a - &z).collect::<Vec<_>>();
+        let r0 = (0..size).map(|i| yn[i] * &(a_r[i] + &z) + &(zm[2 + i / n] * &two[i % n])).collect::<Vec<_>>();
+        let r1 = (0..size).map(|i| yn[i] * &s_r[i]).collect::<Vec<_>>();
+
+        let t1 = inner_prod(&l0, &r1) + &inner_prod(&s_l, &r0);
+        let t2 = inner_prod(&s_l, &r1);
+        let (tau1, tau2) = (Fr::<G>::rand(rng), Fr::<G>::rand(rng));
+        let base = value_base(srs);
+        let (t1, t2) = (srs.msm(&[base, srs.h], &[t1, tau1]).into_affine(), srs.msm(&[base, srs.h], &[t2, tau2]).into_affine());
+        sponge.absorb_g(&[t1, t2]);
+        let x = challenge::<G, _>(&srs.endo_r, &mut sponge);
+
+        let l = l0.iter().zip(s_l.iter()).map(|(l0, l1)| *l0 + &(x * l1)).collect::<Vec<_>>();
+        let r = r0.iter().zip(r1.iter()).map(|(r0, r1)| *r0 + &(x * r1)).collect::<Vec<_>>();
+        let t = inner_prod(&l, &r);
+        let tau_x = blindings.iter().zip(zm[2..].iter()).fold(tau2 * &x.square() + &(tau1 * &x), |acc, (gamma, z)| acc + &(*gamma * z));
+        let mu = alpha + &(rho * &x);
+        sponge.absorb_fr(&[shift_scalar(tau_x), shift_scalar(mu)]);
+
+        let weights = powers(y.inverse().unwrap(), size);
+        let ipa = InnerProductArgument::create(srs, group_map).prove_vectors(l, r, &weights, sponge);
+
+        Ok((commitments, RangeProof {a, s, t1, t2, tau_x, mu, t, ipa}))
+    }
+
+    // This function verifies the batch of the range proofs
+    //     srs: structured reference string
+    //     group_map: group map of the inner product argument
+    //     batch: batch of the sponges, the bit sizes of the ranges, the commitments to the values and the range proofs
+    //     rng: randomness source
+    //     RETURN: verification status
+    pub fn verify<EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>
+    (
+        srs: &SRS<G>,
+        group_map: &G::Map,
+        batch: &mut Vec<(EFqSponge, usize, &Vec<G>, &RangeProof<G>)>,
+        rng: &mut dyn RngCore,
+    ) -> bool
+    {
+        let ipa = InnerProductArgument::create(srs, group_map);
+        let mut msm = BatchMSM::create(srs, rng);
+        let base = value_base(srs);
+
+        for (sponge, n, commitments, proof) in batch.iter_mut()
+        {
+            let (n, m) = (*n, commitments.len());
+            if !fits(srs, n, m) {return false}
+            let size = n * m;
+
+            absorb(sponge, n, &commitments[..]);
+            sponge.absorb_g(&[proof.a, proof.s]);
+            let y = challenge::<G, _>(&srs.endo_r, sponge);
+            let z = challenge::<G, _>(&srs.endo_r, sponge);
+            sponge.absorb_g(&[proof.t1, proof.t2]);
+            let x = challenge::<G, _>(&srs.endo_r, sponge);
+            sponge.absorb_fr(&[shift_scalar(proof.tau_x), shift_scalar(proof.mu)]);
+
+            let yn = powers(y, size);
+            let zm = powers(z, m + 3);
+            let two = powers(Fr::<G>::from(2u64), n);
+            let delta = (z - &zm[2]) * &yn.iter().fold(Fr::<G>::zero(), |acc, y| acc + y)
+                - &(zm[3..].iter().fold(Fr::<G>::zero(), |acc, z| acc + z) * &(two[n - 1].double() - &Fr::<G>::one()));
+
+            // TERM
+            // rand (t B + tau_x H - sum_j z^(2+j) V_j - delta B - x T1 - x^2 T2)
+            let rand = msm.randomiser();
+            msm.push(base, rand * &(proof.t - &delta));
+            msm.add_h(rand * &proof.tau_x);
+            for (v, z) in commitments.iter().zip(zm[2..].iter()) {msm.push(*v, -(rand * z))}
+            msm.push(proof.t1, -(rand * &x));
+            msm.push(proof.t2, -(rand * &x.square()));
+            msm.next();
+
+            // TERM
+            // rand (A + x S - mu H - z < 1, G > + < z y^N + z^(2+j) 2^n, H' >)
+            // the inner product argument adding the rest of the check
+            let rand = msm.randomiser();
+            let y_inv = powers(y.inverse().unwrap(), size);
+            msm.add_h(-(rand * &proof.mu));
+            msm.add_g(0, &vec![-(rand * &z); size]);
+            msm.add_g(size, &(0..size).map(|i| rand * &(z + &(zm[2 + i / n] * &two[i % n] * &y_inv[i]))).collect::<Vec<_>>());
+
+            let mut opening = VectorOpening
+            {
+                sponge,
+                commitment: vec![(proof.a, Fr::<G>::one()), (proof.s, x)],
+                weights: y_inv,
+                value: proof.t,
+                proof: &proof.ipa,
+            };
+            if !ipa.accumulate_vectors(&mut msm, &mut opening) {return false}
+        }
+        msm.check(srs)
+    }
+}
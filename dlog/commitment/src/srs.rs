@@ -6,21 +6,104 @@ This source file implements the Marlin structured reference string primitive
 
 pub use crate::{QnrField, CommitmentField};
 use blake2::{Blake2b, Digest};
-use std::io::{Read, Result as IoResult, Write};
-use algebra::{FromBytes, PrimeField, ToBytes, BigInteger};
+use std::io::{Read, Result as IoResult, Write, Seek, SeekFrom, Error as IoError, ErrorKind};
+use algebra::{FromBytes, PrimeField, ToBytes, BigInteger, AffineCurve, ProjectiveCurve, Field, One, Zero};
+use ff_fft::{EvaluationDomain, Radix2EvaluationDomain as D};
+use rayon::prelude::*;
 use crate::commitment::CommitmentCurve;
+use crate::msm::MSMMethod;
+use crate::glv::GLV;
 use groupmap::GroupMap;
 use array_init::array_init;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
 
 #[derive(Debug, Clone)]
 pub struct SRS<G: CommitmentCurve>
 {
-    pub g: Vec<G>,    // for committing polynomials
+    g: Vec<G>,        // for committing polynomials, see SRS::g
     pub h: G,         // blinding
 
+    // endomorphism images phi(g) of the bases, for the GLV multi-scalar multiplication, see SRS::msm_g.
+    // The bases are private so that the images are always the ones of the bases
+    g_endo: Vec<G>,
+
     // Coefficients for the curve endomorphism
     pub endo_r: G::ScalarField,
     pub endo_q: G::BaseField,
+
+    // commitments to the Lagrange polynomials of the domains, keyed by the domain size
+    pub lagrange_bases: LagrangeBases<G>,
+
+    // multi-scalar multiplication algorithm used by commit, open and verify
+    pub msm_method: MSMMethod,
+
+    // GLV decomposition of the scalars over the endomorphism
+    pub glv: GLV<G::ScalarField>,
+
+    // group map of the opening proofs through the PolynomialCommitment interface
+    pub group_map: SRSGroupMap<G::Map>,
+}
+
+// Group map set up once per SRS and shared by its clones and prefixes, the setup not being free
+pub struct SRSGroupMap<M>(Arc<M>);
+
+impl<M> SRSGroupMap<M>
+{
+    pub fn setup<F>() -> Self where M: GroupMap<F> {SRSGroupMap(Arc::new(M::setup()))}
+}
+
+impl<M> Clone for SRSGroupMap<M>
+{
+    fn clone(&self) -> Self {SRSGroupMap(self.0.clone())}
+}
+
+impl<M> std::fmt::Debug for SRSGroupMap<M>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {f.write_str("SRSGroupMap")}
+}
+
+impl<M> std::ops::Deref for SRSGroupMap<M>
+{
+    type Target = M;
+    fn deref(&self) -> &M {&self.0}
+}
+
+// Cache of the Lagrange bases of the SRS keyed by the domain size. The cache is behind the lock so that
+// the bases can be added to the SRS shared between the indexes through Arc, see SRSRegistry
+#[derive(Debug)]
+pub struct LagrangeBases<G>(RwLock<HashMap<usize, Arc<Vec<G>>>>);
+
+impl<G> Clone for LagrangeBases<G>
+{
+    fn clone(&self) -> Self {LagrangeBases(RwLock::new(self.0.read().unwrap().clone()))}
+}
+
+impl<G> LagrangeBases<G>
+{
+    pub fn new() -> Self {LagrangeBases(RwLock::new(HashMap::new()))}
+
+    // Lagrange basis of the domain of the size, if precomputed
+    pub fn get(&self, size: usize) -> Option<Arc<Vec<G>>> {self.0.read().unwrap().get(&size).cloned()}
+
+    pub fn contains_key(&self, size: &usize) -> bool {self.0.read().unwrap().contains_key(size)}
+
+    pub fn len(&self) -> usize {self.0.read().unwrap().len()}
+
+    pub fn is_empty(&self) -> bool {self.len() == 0}
+
+    fn insert(&self, size: usize, basis: Vec<G>) {self.0.write().unwrap().entry(size).or_insert_with(|| Arc::new(basis));}
+
+    // Lagrange bases of the domains not larger than k
+    fn prefix(&self, k: usize) -> Self
+    {
+        LagrangeBases(RwLock::new(self.0.read().unwrap().iter().filter(|(n, _)| **n <= k).map(|(n, b)| (*n, b.clone())).collect()))
+    }
+}
+
+impl<G> Default for LagrangeBases<G>
+{
+    fn default() -> Self {Self::new()}
 }
 
 pub fn endos<G: CommitmentCurve>() -> (G::BaseField, G::ScalarField)
@@ -40,8 +123,153 @@ where G::BaseField : PrimeField {
     (endo_q, endo_r)
 }
 
-fn point_of_random_bytes<G: CommitmentCurve>(m : &G::Map, random_bytes : &[u8])  -> G
-where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
+// This function computes in place the unscaled inverse DFT of the group elements
+//     a: group elements, the length is a power of two
+//     omega_inv: inverse of the primitive root of unity of the order equal to the length
+fn ifft<G: CommitmentCurve>(a: &mut [G::Projective], omega_inv: G::ScalarField) {
+    let n = a.len();
+    let log_n = n.trailing_zeros();
+    assert_eq!(n, 1 << log_n);
+
+    // bit reversal permutation
+    for k in 0..n {
+        let rk = if log_n == 0 {k} else {k.reverse_bits() >> (usize::MAX.count_ones() - log_n)};
+        if k < rk {a.swap(k, rk)}
+    }
+
+    let mut m = 1;
+    while m < n {
+        let w_m = omega_inv.pow(&[(n / (2 * m)) as u64]);
+        let mut twiddles = Vec::with_capacity(m);
+        let mut w = G::ScalarField::one();
+        for _ in 0..m {
+            twiddles.push(w);
+            w *= &w_m;
+        }
+
+        a.par_chunks_mut(2 * m).for_each(|chunk| {
+            let (lo, hi) = chunk.split_at_mut(m);
+            for j in 0..m {
+                let t = hi[j].mul(twiddles[j]);
+                hi[j] = lo[j] - &t;
+                lo[j] += &t;
+            }
+        });
+        m *= 2;
+    }
+}
+
+// SRS file format version. The file consists of the header
+//
+//     magic "dlog-srs", version u32, curve id [u8; 32], length u64, Blake2b hash [u8; 64] of the body
+//
+// and of the body
+//
+//     endo_q, endo_r, h, g[0..length]
+//
+// all the numbers being little-endian
+pub const VERSION: u32 = 1;
+const MAGIC: [u8; 8] = *b"dlog-srs";
+
+// bound of the SRS length read, the bases being derived from the u32 indexes
+const MAX_LENGTH: u64 = 1 << 32;
+
+fn invalid(kind: ErrorKind, msg: &str) -> IoError {
+    IoError::new(kind, msg)
+}
+
+// This function computes the id of the curve as the Blake2b hash of the base and the scalar field
+// moduli and of the prime subgroup generator, truncated to 32 bytes
+pub fn curve_id<G: CommitmentCurve>() -> Vec<u8> {
+    let mut h = Blake2b::new();
+    for l in G::BaseField::characteristic().iter().chain(G::ScalarField::characteristic().iter()) {
+        h.input(&l.to_le_bytes());
+    }
+    let mut generator = vec![];
+    G::write(&G::prime_subgroup_generator(), &mut generator).unwrap();
+    h.input(&generator);
+    h.result()[0..32].to_vec()
+}
+
+// size of the serialized point
+fn point_size<G: CommitmentCurve>() -> IoResult<usize> {
+    let mut point = vec![];
+    G::write(&G::zero(), &mut point)?;
+    Ok(point.len())
+}
+
+fn write_header<G: CommitmentCurve, W: Write>(mut writer: W, length: usize, hash: &[u8]) -> IoResult<()> {
+    writer.write_all(&MAGIC)?;
+    u32::write(&VERSION, &mut writer)?;
+    writer.write_all(&curve_id::<G>())?;
+    u64::write(&(length as u64), &mut writer)?;
+    writer.write_all(hash)
+}
+
+// This function reads and validates the header
+//     RETURN: SRS length and hash of the body
+fn read_header<G: CommitmentCurve, R: Read>(mut reader: R) -> IoResult<(usize, Vec<u8>)> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {return Err(invalid(ErrorKind::InvalidData, "not an SRS file"))}
+    if u32::read(&mut reader)? != VERSION {return Err(invalid(ErrorKind::InvalidData, "unsupported SRS file version"))}
+    let mut id = [0u8; 32];
+    reader.read_exact(&mut id)?;
+    if id[..] != curve_id::<G>()[..] {return Err(invalid(ErrorKind::InvalidData, "SRS of another curve"))}
+    let length = u64::read(&mut reader)?;
+    if length > MAX_LENGTH {return Err(invalid(ErrorKind::InvalidData, "SRS length out of bound"))}
+    let mut hash = vec![0u8; 64];
+    reader.read_exact(&mut hash)?;
+    Ok((length as usize, hash))
+}
+
+// reader hashing the bytes read
+struct HashReader<R: Read> {
+    reader: R,
+    hash: Blake2b,
+}
+
+impl<R: Read> HashReader<R> {
+    fn new(reader: R) -> Self {HashReader {reader, hash: Blake2b::new()}}
+}
+
+impl<R: Read> Read for HashReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let n = self.reader.read(buf)?;
+        self.hash.input(&buf[0..n]);
+        Ok(n)
+    }
+}
+
+// writer hashing the bytes written
+struct HashWriter<W: Write> {
+    writer: W,
+    hash: Blake2b,
+}
+
+impl<W: Write> HashWriter<W> {
+    fn new(writer: W) -> Self {HashWriter {writer, hash: Blake2b::new()}}
+}
+
+impl<W: Write> Write for HashWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let n = self.writer.write(buf)?;
+        self.hash.input(&buf[0..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.writer.flush()
+    }
+}
+
+// number of the SRS bases derived in parallel at once when streamed
+const CHUNK: usize = 1 << 14;
+
+// This function maps the random bytes to the base field element: the bits of the first 31 bytes, taken
+// in the byte order and within every byte from the least significant one, are read by BigInteger::from_bits
+// as the big-endian 248-bit integer, so that the bit 0 of the byte 0 is its most significant bit
+pub fn field_of_random_bytes<F: PrimeField>(random_bytes : &[u8]) -> F {
     const N : usize = 31;
     let mut bits = [false;8*N];
     for i in 0..N {
@@ -50,65 +278,314 @@ where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
         }
     }
 
-    let n = <G::BaseField as PrimeField>::BigInt::from_bits(&bits);
-    let t = G::BaseField::from_repr(n);
+    let n = <F as PrimeField>::BigInt::from_bits(&bits);
+    F::from_repr(n)
+}
+
+pub fn point_of_random_bytes<G: CommitmentCurve>(m : &G::Map, random_bytes : &[u8])  -> G
+where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
+    let t = field_of_random_bytes::<G::BaseField>(random_bytes);
     let (x, y) = m.to_group(t);
     G::of_coordinates(x, y)
 }
 
+// This function derives the SRS base g[i] from the Blake2b hash of the big-endian u32 encoding of i
+pub fn base<G: CommitmentCurve>(m : &G::Map, i: usize) -> G
+where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
+    let mut h = Blake2b::new();
+    h.input(&(i as u32).to_be_bytes());
+    point_of_random_bytes(m, &h.result())
+}
+
+// This function derives the SRS miscellaneous base from the Blake2b hash of "srs_misc" followed by
+// the big-endian u32 encoding of i, the base 0 being the blinding base h
+pub fn misc_base<G: CommitmentCurve>(m : &G::Map, i: usize) -> G
+where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
+    let mut h = Blake2b::new();
+    h.input("srs_misc".as_bytes());
+    h.input(&(i as u32).to_be_bytes());
+    point_of_random_bytes(m, &h.result())
+}
+
+// This function applies the curve endomorphism phi(x, y) = (endo_q * x, y) to the bases
+fn endo_images<G: CommitmentCurve>(g: &[G], endo_q: G::BaseField) -> Vec<G>
+{
+    g.par_iter().map(|g| match g.to_coordinates() {None => G::zero(), Some((x, y)) => G::of_coordinates(x * &endo_q, y)}).collect()
+}
+
+// This function derives the SRS bases g[start..end] in parallel
+fn bases<G: CommitmentCurve>(start: usize, end: usize) -> Vec<G>
+where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
+    (start..end).into_par_iter().map_init(|| G::Map::setup(), |m, i| base(m, i)).collect()
+}
+
+impl<G: CommitmentCurve> SRS<G> {
+    // SRS bases for committing polynomials
+    pub fn g(&self) -> &[G] {&self.g}
+
+    // endomorphism images of the SRS bases
+    pub fn g_endo(&self) -> &[G] {&self.g_endo}
+}
+
 impl<G: CommitmentCurve> SRS<G> where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
     pub fn max_degree(&self) -> usize {
         self.g.len()
     }
 
+    // This function creates the SRS of the bases, for instance of the ones of another derivation
+    //     g: bases for committing polynomials
+    //     h: blinding base
+    pub fn of_bases(g: Vec<G>, h: G) -> Self {
+        let (endo_q, endo_r) = endos::<G>();
+        let g_endo = endo_images(&g, endo_q);
+        SRS
+        {
+            g, g_endo, h, endo_r, endo_q,
+            lagrange_bases: LagrangeBases::new(),
+            msm_method: MSMMethod::default(),
+            glv: GLV::new(endo_r),
+            group_map: SRSGroupMap::setup::<G::BaseField>(),
+        }
+    }
+
     // This function creates SRS instance for circuits up to depth d
     //      depth: maximal depth of SRS string
     //      size: circuit size
     pub fn create(depth: usize) -> Self {
         let m = G::Map::setup();
 
-        let g : Vec<_> = (0..depth).map(|i| {
-            let mut h = Blake2b::new();
-            h.input(&(i as u32).to_be_bytes());
-            point_of_random_bytes(&m, &h.result())
-        }).collect();
+        let g = bases::<G>(0, depth);
 
         let (endo_q, endo_r) = endos::<G>();
 
         const MISC : usize = 1;
-        let [h] : [G;MISC] = array_init(|i| {
-            let mut h = Blake2b::new();
-            h.input("srs_misc".as_bytes());
-            h.input(&(i as u32).to_be_bytes());
-            point_of_random_bytes(&m, &h.result())
-        });
+        let [h] : [G;MISC] = array_init(|i| misc_base(&m, i));
 
+        let g_endo = endo_images(&g, endo_q);
         SRS
         {
             g,
+            g_endo,
             h,
-            endo_r, endo_q
+            endo_r, endo_q,
+            lagrange_bases: LagrangeBases::new(),
+            msm_method: MSMMethod::default(),
+            glv: GLV::new(endo_r),
+            group_map: SRSGroupMap(Arc::new(m)),
         }
     }
 
-    pub fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
-        u64::write(&(self.g.len() as u64), &mut writer)?;
+    // This function precomputes the commitments to the Lagrange polynomials of the domain
+    //
+    //     L_i(X) = 1/n * sum_j (w^-i * X)^j
+    //
+    // that is the inverse DFT of the first n SRS points, so that the polynomials can be committed
+    // from their evaluations over the domain, see SRS::commit_evaluations. The basis is cached in
+    // place, so it can be added to the shared SRS as well
+    //     domain: evaluation domain of the size not exceeding the SRS size
+    pub fn add_lagrange_basis(&self, domain: D<G::ScalarField>) {
+        let n = domain.size();
+        assert!(n <= self.g.len(), "domain size has to be not larger than that of the SRS");
+        if self.lagrange_bases.contains_key(&n) {return}
+
+        let mut basis = self.g[0..n].iter().map(|g| g.into_projective()).collect::<Vec<_>>();
+        ifft::<G>(&mut basis, domain.group_gen_inv);
+        basis.par_iter_mut().for_each(|b| *b = b.mul(domain.size_inv));
+        G::Projective::batch_normalization(basis.as_mut_slice());
+        self.lagrange_bases.insert(n, basis.par_iter().map(|g| g.into_affine()).collect());
+    }
+
+    // This function truncates the SRS to its first k bases, dropping the Lagrange bases of the larger domains
+    pub fn truncate(&mut self, k: usize) {
+        assert!(k <= self.g.len(), "truncated size has to be not larger than that of the SRS");
+        self.g.truncate(k);
+        self.g_endo.truncate(k);
+        self.lagrange_bases = self.lagrange_bases.prefix(k);
+    }
+
+    // This function returns the SRS of the first k bases, keeping the Lagrange bases of the domains it covers
+    pub fn prefix(&self, k: usize) -> Self {
+        assert!(k <= self.g.len(), "prefix size has to be not larger than that of the SRS");
+        SRS
+        {
+            g: self.g[0..k].to_vec(),
+            g_endo: self.g_endo[0..k].to_vec(),
+            h: self.h,
+            endo_r: self.endo_r,
+            endo_q: self.endo_q,
+            lagrange_bases: self.lagrange_bases.prefix(k),
+            msm_method: self.msm_method,
+            glv: self.glv.clone(),
+            group_map: self.group_map.clone(),
+        }
+    }
+
+    fn write_body<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.endo_q.write(&mut writer)?;
+        self.endo_r.write(&mut writer)?;
+        G::write(&self.h, &mut writer)?;
         for x in &self.g {
             G::write(x, &mut writer)?;
         }
-        G::write(&self.h, &mut writer)?;
         Ok(())
     }
 
+    // This function writes the SRS in the versioned format, see VERSION
+    pub fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        let mut hashing = HashWriter::new(std::io::sink());
+        self.write_body(&mut hashing)?;
+        write_header::<G, _>(&mut writer, self.g.len(), &hashing.hash.result())?;
+        self.write_body(&mut writer)
+    }
+
+    // This function reads the SRS written by SRS::write, validating the header, the hash and the points
     pub fn read<R: Read>(mut reader: R) -> IoResult<Self> {
-        let n = u64::read(&mut reader)? as usize;
-        let mut g = vec![];
-        for _ in 0..n {
-            g.push(G::read(&mut reader)?);
+        let (length, hash) = read_header::<G, _>(&mut reader)?;
+        Self::read_body(reader, length, length, &hash)
+    }
+
+    // This function reads the first k bases of the SRS written by SRS::write, for the circuits smaller
+    // than the SRS. The rest of the bases are hashed, but neither deserialized nor kept.
+    //     reader: source
+    //     k: number of the bases to load
+    pub fn read_prefix<R: Read>(mut reader: R, k: usize) -> IoResult<Self> {
+        let (length, hash) = read_header::<G, _>(&mut reader)?;
+        if k > length {return Err(invalid(ErrorKind::InvalidInput, "SRS prefix longer than the SRS"))}
+        Self::read_body(reader, length, k, &hash)
+    }
+
+    fn read_body<R: Read>(reader: R, length: usize, k: usize, hash: &[u8]) -> IoResult<Self> {
+        let mut reader = HashReader::new(reader);
+
+        // the endomorphism coefficients are read and checked to be the ones of the verifier, the conjugate
+        // endomorphism producing the incompatible scalar challenges
+        let endo_q = G::BaseField::read(&mut reader)?;
+        let endo_r = G::ScalarField::read(&mut reader)?;
+        if (endo_q, endo_r) != endos::<G>() {
+            return Err(invalid(ErrorKind::InvalidData, "SRS endomorphism coefficients mismatch"))
         }
 
         let h = G::read(&mut reader)?;
+        let mut g = vec![];
+        for start in (0..k).step_by(CHUNK) {
+            let end = std::cmp::min(start + CHUNK, k);
+            let chunk = (start..end).map(|_| G::read(&mut reader)).collect::<IoResult<Vec<_>>>()?;
+            g.extend(chunk);
+        }
+        if h.is_zero() || !h.is_valid() || !g.par_iter().all(|p| !p.is_zero() && p.is_valid()) {
+            return Err(invalid(ErrorKind::InvalidData, "SRS point not in the curve group"))
+        }
+
+        let rest = ((length - k) * point_size::<G>()?) as u64;
+        if std::io::copy(&mut (&mut reader).take(rest), &mut std::io::sink())? != rest {
+            return Err(invalid(ErrorKind::UnexpectedEof, "SRS file truncated"))
+        }
+        if reader.hash.result()[..] != hash[..] {
+            return Err(invalid(ErrorKind::InvalidData, "SRS hash mismatch"))
+        }
+
+        let g_endo = endo_images(&g, endo_q);
+        Ok(SRS
+        {
+            g, g_endo, h, endo_r, endo_q,
+            lagrange_bases: LagrangeBases::new(),
+            msm_method: MSMMethod::default(),
+            glv: GLV::new(endo_r),
+            group_map: SRSGroupMap::setup::<G::BaseField>(),
+        })
+    }
+
+    // This function writes the SRS for circuits up to depth d, in the format of SRS::write, deriving
+    // the bases in parallel chunks streamed to the writer, so that the SRS is never held in memory.
+    // The hash of the header is written once the bases are, hence the writer has to be seekable.
+    //     depth: maximal depth of SRS string
+    //     writer: destination
+    pub fn write_derivation<W: Write + Seek>(depth: usize, mut writer: W) -> IoResult<()> {
+        let start = writer.seek(SeekFrom::Current(0))?;
+        write_header::<G, _>(&mut writer, depth, &[0u8; 64])?;
+
+        let mut hashing = HashWriter::new(&mut writer);
         let (endo_q, endo_r) = endos::<G>();
-        Ok(SRS { g, h, endo_r, endo_q })
+        endo_q.write(&mut hashing)?;
+        endo_r.write(&mut hashing)?;
+        G::write(&misc_base::<G>(&G::Map::setup(), 0), &mut hashing)?;
+        for start in (0..depth).step_by(CHUNK) {
+            for x in bases::<G>(start, std::cmp::min(start + CHUNK, depth)).iter() {
+                G::write(x, &mut hashing)?;
+            }
+        }
+        let hash = hashing.hash.result();
+
+        let end = writer.seek(SeekFrom::Current(0))?;
+        writer.seek(SeekFrom::Start(start))?;
+        write_header::<G, _>(&mut writer, depth, &hash)?;
+        writer.seek(SeekFrom::Start(end))?;
+        Ok(())
+    }
+
+    // This function checks that the SRS written by SRS::write was derived as by SRS::create,
+    // re-deriving and comparing the bases chunk by chunk
+    //     reader: source
+    //     RETURN: derivation status, the error being the one of reading the file
+    pub fn verify_derivation<R: Read>(mut reader: R) -> IoResult<bool> {
+        let (n, hash) = read_header::<G, _>(&mut reader)?;
+        let mut reader = HashReader::new(reader);
+
+        let endo_q = G::BaseField::read(&mut reader)?;
+        let endo_r = G::ScalarField::read(&mut reader)?;
+        if (endo_q, endo_r) != endos::<G>() {return Ok(false)}
+        if G::read(&mut reader)? != misc_base::<G>(&G::Map::setup(), 0) {return Ok(false)}
+
+        for start in (0..n).step_by(CHUNK) {
+            let end = std::cmp::min(start + CHUNK, n);
+            let g = (start..end).map(|_| G::read(&mut reader)).collect::<IoResult<Vec<_>>>()?;
+            if g != bases::<G>(start, end) {return Ok(false)}
+        }
+        Ok(reader.hash.result()[..] == hash[..])
+    }
+}
+
+// Registry of the SRS instances keyed by the size. Every size is created (or inserted) once
+// and shared by all the prover and verifier indexes which use it.
+pub struct SRSRegistry<G: CommitmentCurve>
+{
+    srs: Mutex<HashMap<usize, Arc<SRS<G>>>>,
+}
+
+impl<G: CommitmentCurve> Default for SRSRegistry<G> where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
+    fn default() -> Self {Self::new()}
+}
+
+impl<G: CommitmentCurve> SRSRegistry<G> where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
+    pub fn new() -> Self {
+        SRSRegistry {srs: Mutex::new(HashMap::new())}
+    }
+
+    // This function returns the shared SRS of the size, creating it on the first request. The SRS is
+    // derived with the registry unlocked, not to block the requests of the other sizes; of the SRS
+    // instances derived concurrently for the same size the first registered one is kept
+    pub fn get(&self, size: usize) -> Arc<SRS<G>> {
+        if let Some(srs) = self.srs.lock().unwrap().get(&size) {return srs.clone()}
+        let srs = Arc::new(SRS::create(size));
+        self.srs.lock().unwrap().entry(size).or_insert(srs).clone()
+    }
+
+    // This function registers the SRS, for instance the one read from a file. The SRS already
+    // registered for the size is kept and returned.
+    pub fn insert(&self, srs: SRS<G>) -> Arc<SRS<G>> {
+        self.srs.lock().unwrap().entry(srs.g().len()).or_insert_with(|| Arc::new(srs)).clone()
+    }
+
+    // This function drops the registry reference to the SRS of the size, the indexes
+    // using it keep their references
+    pub fn remove(&self, size: usize) -> Option<Arc<SRS<G>>> {
+        self.srs.lock().unwrap().remove(&size)
+    }
+
+    // sizes of the registered SRS instances
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = self.srs.lock().unwrap().keys().cloned().collect::<Vec<_>>();
+        sizes.sort();
+        sizes
     }
 }
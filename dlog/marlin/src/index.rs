@@ -12,43 +12,27 @@ use marlin_circuits::{gate::CircuitGate, domains::EvaluationDomains};
 pub use super::compiled::Compiled;
 use algebra::PrimeField;
 use ff_fft::EvaluationDomain;
+use std::sync::Arc;
 
 type Fr<G> = <G as AffineCurve>::ScalarField;
 type Fq<G> = <G as AffineCurve>::BaseField;
 
-pub enum SRSValue<'a, G : CommitmentCurve> {
-    Value(SRS<G>),
-    Ref(&'a SRS<G>)
-}
-
-impl<'a, G : CommitmentCurve> SRSValue<'a, G> {
-    pub fn get_ref(&self) -> & SRS<G> {
-        match self {
-            SRSValue::Value(x) => &x,
-            SRSValue::Ref(x) => x
-        }
-    }
-}
-
-pub enum SRSSpec <'a, G: CommitmentCurve>{
-    Use(&'a SRS<G>),
+// SRS of the index, either shared or generated for the index
+pub enum SRSSpec<G: CommitmentCurve>{
+    Use(Arc<SRS<G>>),
     Generate
 }
 
-impl<'a, G: CommitmentCurve> SRSValue<'a, G> where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
-    pub fn generate(size: usize) -> SRS<G> {
-        SRS::<G>::create(size)
-    }
-
-    pub fn create<'b>(size: usize, spec : SRSSpec<'a, G>) -> SRSValue<'a, G>{
-        match spec {
-            SRSSpec::Use(x) => SRSValue::Ref(x),
-            SRSSpec::Generate => SRSValue::Value(Self::generate(size,))
+impl<G: CommitmentCurve> SRSSpec<G> where G::BaseField : PrimeField, G::ScalarField : CommitmentField {
+    pub fn create(self, size: usize) -> Arc<SRS<G>> {
+        match self {
+            SRSSpec::Use(x) => x,
+            SRSSpec::Generate => Arc::new(SRS::<G>::create(size))
         }
     }
 }
 
-pub struct Index<'a, G: CommitmentCurve> where G::ScalarField : CommitmentField
+pub struct Index<G: CommitmentCurve> where G::ScalarField : CommitmentField
 {
     // constraint system compilation
     pub compiled: [Compiled<G>; 3],
@@ -63,7 +47,7 @@ pub struct Index<'a, G: CommitmentCurve> where G::ScalarField : CommitmentField
     pub max_poly_size: usize,
 
     // polynomial commitment keys
-    pub srs: SRSValue<'a, G>,
+    pub srs: Arc<SRS<G>>,
 
     // random oracle argument parameters
     pub fr_sponge_params: ArithmeticSpongeParams<Fr<G>>,
@@ -77,7 +61,7 @@ pub struct MatrixValues<C: AffineCurve> {
     pub rc : PolyComm<C>,
 }
 
-pub struct VerifierIndex<'a, G: CommitmentCurve>
+pub struct VerifierIndex<G: CommitmentCurve>
 {
     // constraint system compilation
     pub matrix_commitments: [MatrixValues<G>; 3],
@@ -92,14 +76,14 @@ pub struct VerifierIndex<'a, G: CommitmentCurve>
     pub max_poly_size: usize,
 
     // polynomial commitment keys
-    pub srs: SRSValue<'a, G>,
+    pub srs: Arc<SRS<G>>,
 
     // random oracle argument parameters
     pub fr_sponge_params: ArithmeticSpongeParams<Fr<G>>,
     pub fq_sponge_params: ArithmeticSpongeParams<Fq<G>>,
 }
 
-impl<'a, G: CommitmentCurve> Index<'a, G> where G::BaseField: PrimeField, G::ScalarField : CommitmentField
+impl<G: CommitmentCurve> Index<G> where G::BaseField: PrimeField, G::ScalarField : CommitmentField
 {
     fn matrix_values(c : &Compiled<G>) -> MatrixValues<G> {
         MatrixValues {
@@ -110,13 +94,10 @@ impl<'a, G: CommitmentCurve> Index<'a, G> where G::BaseField: PrimeField, G::Sca
         }
     }
 
-    pub fn verifier_index(&self) -> VerifierIndex<'a, G> {
+    pub fn verifier_index(&self) -> VerifierIndex<G> {
         let [ a, b, c ] = & self.compiled;
 
-        let srs = match &self.srs {
-            SRSValue::Value(s) => SRSValue::Value(s.clone()),
-            SRSValue::Ref(x) => SRSValue::Ref(x)
-        };
+        let srs = self.srs.clone();
 
         VerifierIndex {
             matrix_commitments : [ Self::matrix_values(a), Self::matrix_values(b), Self::matrix_values(c) ],
@@ -139,7 +120,7 @@ impl<'a, G: CommitmentCurve> Index<'a, G> where G::BaseField: PrimeField, G::Sca
         max_poly_size: usize,
         fr_sponge_params: ArithmeticSpongeParams<Fr<G>>,
         fq_sponge_params: ArithmeticSpongeParams<Fq<G>>,
-        srs : SRSSpec<'a, G>
+        srs : SRSSpec<G>
     ) -> Result<Self, ProofError>
     {
         if a.shape() != b.shape() ||
@@ -162,16 +143,22 @@ impl<'a, G: CommitmentCurve> Index<'a, G> where G::BaseField: PrimeField, G::Sca
             nonzero_entries)
             .map_or(Err(ProofError::EvaluationGroup), |s| Ok(s))?;
 
-        let srs = SRSValue::create(max_poly_size, srs);
+        let srs = srs.create(max_poly_size);
+        // the public input and the ZA, ZB polynomials are committed from their evaluations over the domains
+        // fitting into a single segment
+        for domain in [domains.x, domains.h].iter()
+        {
+            if domain.size() <= srs.g().len() {srs.add_lagrange_basis(*domain)}
+        }
 
         // compile the constraints
         Ok(Index::<G>
         {
             compiled:
             [
-                Compiled::<G>::compile(srs.get_ref(), domains.h, domains.k, domains.b, a)?,
-                Compiled::<G>::compile(srs.get_ref(), domains.h, domains.k, domains.b, b)?,
-                Compiled::<G>::compile(srs.get_ref(), domains.h, domains.k, domains.b, c)?,
+                Compiled::<G>::compile(&srs, domains.h, domains.k, domains.b, a)?,
+                Compiled::<G>::compile(&srs, domains.h, domains.k, domains.b, b)?,
+                Compiled::<G>::compile(&srs, domains.h, domains.k, domains.b, c)?,
             ],
             fr_sponge_params,
             fq_sponge_params,
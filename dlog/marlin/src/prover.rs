@@ -101,12 +101,15 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         let ratio = index.domains.h.size() / index.domains.x.size();
         let public: Vec<Fr<G>> = (0..index.public_inputs).map(|i| {witness[i * ratio]}).collect();
 
+        // compute public input polynomial and commit to it, from the evaluations if the Lagrange basis is precomputed
+        let x_hat_evals = EvaluationDomains::evals_from_coeffs(public.clone(), index.domains.x);
+        let x_hat_comm = index.srs.commit_evaluations_non_hiding(&x_hat_evals);
+        let x_hat = x_hat_evals.interpolate();
+         // TODO: Should have no degree bound when we add the correct degree bound method
+        let x_hat_comm = x_hat_comm.unwrap_or_else(|| index.srs.commit_non_hiding(&x_hat, None));
+
         // evaluate public input polynomial over domains.h
-        let public_evals = index.domains.h.fft
-        (
-            &EvaluationDomains::evals_from_coeffs(public.clone(),
-            index.domains.x
-        ).interpolate());
+        let public_evals = index.domains.h.fft(&x_hat);
 
         // prover computes w polynomial from the witness by subtracting the public polynomial evaluations
         let (w, r) = EvaluationDomains::evals_from_coeffs
@@ -130,23 +133,15 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
             }
         }
 
-        let x_hat =
-            EvaluationDomains::evals_from_coeffs(public.clone(), index.domains.x).interpolate();
-         // TODO: Should have no degree bound when we add the correct degree bound method
-        let x_hat_comm = index.srs.get_ref().commit_non_hiding(&x_hat, None);
-
-        // prover interpolates the vectors and computes the evaluation polynomial
-        let za = EvaluationDomains::evals_from_coeffs(zv[0].to_vec(), index.domains.h).interpolate();
-        let zb = EvaluationDomains::evals_from_coeffs(zv[1].to_vec(), index.domains.h).interpolate();
+        // commit to W, ZA, ZB polynomials, ZA and ZB from the evaluations if the Lagrange basis is precomputed,
+        // the sumchecks needing them interpolated all the same
+        let (w_comm, omega_w) = index.srs.commit(&w, None, rng);
+        let (za, za_comm, omega_za) = index.srs.commit_interpolate(EvaluationDomains::evals_from_coeffs(zv[0].to_vec(), index.domains.h), rng);
+        let (zb, zb_comm, omega_zb) = index.srs.commit_interpolate(EvaluationDomains::evals_from_coeffs(zv[1].to_vec(), index.domains.h), rng);
 
         // substitute ZC with ZA*ZB
         let zv = [za.clone(), zb.clone(), &za * &zb];
 
-        // commit to W, ZA, ZB polynomials
-        let (w_comm, omega_w) = index.srs.get_ref().commit(&w.clone(), None, rng);
-        let (za_comm, omega_za) = index.srs.get_ref().commit(&za.clone(), None, rng);
-        let (zb_comm, omega_zb) = index.srs.get_ref().commit(&zb.clone(), None, rng);
-
         // the transcript of the random oracle non-interactive argument
         let mut fq_sponge = EFqSponge::new(index.fq_sponge_params.clone());
 
@@ -183,8 +178,8 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         g1.coeffs.remove(0);
 
         // commit to H1 & G1 polynomials and
-        let (h1_comm, omega_h1) = index.srs.get_ref().commit(&h1, None, rng);
-        let (g1_comm, omega_g1) = index.srs.get_ref().commit(&g1, Some(index.domains.h.size()-1), rng);
+        let (h1_comm, omega_h1) = index.srs.commit(&h1, None, rng);
+        let (g1_comm, omega_g1) = index.srs.commit(&g1, Some(index.domains.h.size()-1), rng);
 
         // absorb H1, G1 polycommitments
         fq_sponge.absorb_g(&g1_comm.unshifted);
@@ -199,8 +194,8 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         let (h2, mut g2) = Self::sumcheck_2_compute (index, &ra, &oracles)?;
         let sigma2 = g2.coeffs[0];
         g2.coeffs.remove(0);
-        let (h2_comm, omega_h2) = index.srs.get_ref().commit(&h2, None, rng);
-        let (g2_comm, omega_g2) = index.srs.get_ref().commit(&g2, Some(index.domains.h.size()-1), rng);
+        let (h2_comm, omega_h2) = index.srs.commit(&h2, None, rng);
+        let (g2_comm, omega_g2) = index.srs.commit(&g2, Some(index.domains.h.size()-1), rng);
 
         // absorb sigma2, g2, h2
         fq_sponge.absorb_fr(&[sigma2]);
@@ -216,8 +211,8 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         let (h3, mut g3) = Self::sumcheck_3_compute (index, &oracles)?;
         let sigma3 = g3.coeffs[0];
         g3.coeffs.remove(0);
-        let (h3_comm, omega_h3) = index.srs.get_ref().commit(&h3, None, rng);
-        let (g3_comm, omega_g3) = index.srs.get_ref().commit(&g3, Some(index.domains.k.size()-1), rng);
+        let (h3_comm, omega_h3) = index.srs.commit(&h3, None, rng);
+        let (g3_comm, omega_g3) = index.srs.commit(&g3, Some(index.domains.k.size()-1), rng);
 
         // absorb sigma3 scalar
         fq_sponge.absorb_fr(&[sigma3]);
@@ -238,7 +233,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
             s
         };
 
-        let endo = &index.srs.get_ref().endo_r;
+        let endo = &index.srs.endo_r;
         let beta : Vec<_> = oracles.beta.iter().map(|x| x.to_field(endo)).collect();
 
         let evals =
@@ -310,7 +305,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
             let k = (p.coeffs.len() + n - 1) / n;
             (p, None, non_hiding(k))
         };
-        let n = index.srs.get_ref().g.len();
+        let n = index.srs.g().len();
 
         polynoms.extend(
             vec!
@@ -353,7 +348,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
             g3_comm,
 
             // polynomial commitment batched opening proofs
-            proof: index.srs.get_ref().open::<EFqSponge>
+            proof: index.srs.open::<EFqSponge>
             (
                 group_map,
                 polynoms,
@@ -425,7 +420,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
     ) -> Result<(DensePolynomial<Fr<G>>, DensePolynomial<Fr<G>>), ProofError>
     {
         // precompute Lagrange polynomial evaluations
-        let lagrng = index.domains.h.evaluate_all_lagrange_coefficients(oracles.beta[0].to_field(&index.srs.get_ref().endo_r));
+        let lagrng = index.domains.h.evaluate_all_lagrange_coefficients(oracles.beta[0].to_field(&index.srs.endo_r));
 
         // compute and return H2 & G2 polynomials
         // use the precomputed normalized Lagrange evaluations for interpolation evaluations
@@ -458,7 +453,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         oracles: &RandomOracles<Fr<G>>
     ) -> Result<(DensePolynomial<Fr<G>>, DensePolynomial<Fr<G>>), ProofError>
     {
-        let endo = &index.srs.get_ref().endo_r;
+        let endo = &index.srs.endo_r;
         let beta0 = oracles.beta[0].to_field(endo);
         let beta1 = oracles.beta[1].to_field(endo);
 
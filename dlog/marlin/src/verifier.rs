@@ -48,7 +48,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         x_hat_value: Fr<G>
     ) -> bool
     {
-        let endo = &index.srs.get_ref().endo_r;
+        let endo = &index.srs.endo_r;
         let beta0 = oracles.beta[0].to_field(endo);
         // compute ra*zm - ram*z ?= h*v + b*g to verify the first sumcheck argument
         (oracles.alpha.pow([index.domains.h.size]) - &beta0.pow([index.domains.h.size])) *
@@ -88,7 +88,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         evals: &ProofEvals<Fr<G>>,
     ) -> bool
     {
-        let endo = &index.srs.get_ref().endo_r;
+        let endo = &index.srs.endo_r;
         let beta1 = oracles.beta[1].to_field(endo);
         self.sigma3 * &index.domains.k.size_as_field_element *
             &((oracles.alpha.pow([index.domains.h.size]) - &beta1.pow([index.domains.h.size])))
@@ -110,7 +110,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         evals: &ProofEvals<Fr<G>>,
     ) -> bool
     {
-        let endo = &index.srs.get_ref().endo_r;
+        let endo = &index.srs.endo_r;
         let beta0 = oracles.beta[0].to_field(endo);
         let beta1 = oracles.beta[1].to_field(endo);
         let beta2 = oracles.beta[2].to_field(endo);
@@ -166,21 +166,21 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
             return true;
         }
 
-        let n = proofs[0].0.srs.get_ref().g.len();
+        let n = proofs[0].0.srs.g().len();
         for (index, _) in proofs.iter() {
-            assert_eq!(index.srs.get_ref().g.len(), n);
+            assert_eq!(index.srs.g().len(), n);
         }
 
         let params = proofs.iter().map
         (
             |(index, proof)|
             {
-                let endo = &index.srs.get_ref().endo_r;
-                let x_hat =
+                let endo = &index.srs.endo_r;
+                let x_hat_evals = Evaluations::<Fr<G>>::from_vec_and_domain(proof.public.clone(), GeneralEvaluationDomain::Radix2(index.domains.x));
+                let x_hat_comm = index.srs.commit_evaluations_non_hiding(&x_hat_evals);
                 // TODO: Cache this interpolated polynomial.
-                Evaluations::<Fr<G>>::from_vec_and_domain(proof.public.clone(), GeneralEvaluationDomain::Radix2(index.domains.x)).interpolate();
-                // TODO: No degree bound needed
-                let x_hat_comm = index.srs.get_ref().commit_non_hiding(&x_hat, None);
+                let x_hat = x_hat_evals.interpolate();
+                let x_hat_comm = x_hat_comm.unwrap_or_else(|| index.srs.commit_non_hiding(&x_hat, None));
 
                 let (fq_sponge, oracles) = proof.oracles::<EFqSponge, EFrSponge>(index, x_hat_comm.clone(), &x_hat);
 
@@ -202,7 +202,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
                         |i|
                         {
                             let full = b_poly(&chals, oracles.beta[i].to_field(endo));
-                            if index.max_poly_size == b_len {
+                            if b_len <= index.max_poly_size {
                                 return vec![full]
                             }
                             let mut betaacc = Fr::<G>::one();
@@ -326,7 +326,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
                     ]
                 );
 
-                let endo = &index.srs.get_ref().endo_r;
+                let endo = &index.srs.endo_r;
 
                 Ok((
                     fq_sponge.clone(),
@@ -340,7 +340,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         ).collect::<Result<Vec<_>, _>>()
         // second, verify the commitment opening proofs
         {
-            Ok(mut batch) => proofs[0].0.srs.get_ref().verify::<EFqSponge>(group_map, &mut batch, rng),
+            Ok(mut batch) => proofs[0].0.srs.verify::<EFqSponge>(group_map, &mut batch, rng),
             Err(_) => false
         }
     }
@@ -401,7 +401,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
             s
         };
 
-        let endo = &index.srs.get_ref().endo_r;
+        let endo = &index.srs.endo_r;
 
         let x_hat_evals =
             [ x_hat.eval(oracles.beta[0].to_field(endo), index.max_poly_size)
@@ -1,5 +1,4 @@
 pub mod prover;
-pub mod verifier;
-pub mod plonk_sponge;
 pub mod index;
-pub mod range;
+pub mod recursion;
+pub use plonk_protocol::{verifier, plonk_sponge, range};
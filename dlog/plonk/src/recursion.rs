@@ -0,0 +1,183 @@
+/********************************************************************************************
+
+This source file implements the native counterpart of the in-circuit verifier gadget
+plonk_circuits::gadgets::verifier. It prepares the proof as the input of the verifier
+circuit over the base field of the proof curve and computes the values the circuit
+defers to the other curve of the cycle, together with the accumulator of the proof.
+
+*********************************************************************************************/
+
+pub use super::prover::ProverProof;
+pub use super::index::VerifierIndex as Index;
+use oracle::FqSponge;
+use commitment_dlog::commitment::{CommitmentField, CommitmentCurve, PolyComm, shift_scalar, combined_inner_product};
+use plonk_circuits::gadgets::verifier::{ProofInput, Deferred};
+use algebra::{AffineCurve, ProjectiveCurve, PrimeField, BigInteger, FpParameters, Field, Zero, One};
+use crate::plonk_sponge::FrSponge;
+
+type Fr<G> = <G as AffineCurve>::ScalarField;
+type Fq<G> = <G as AffineCurve>::BaseField;
+
+// This function converts the 128 bit scalar challenge into the base field element
+fn challenge_to_fq<G: AffineCurve>(x: Fr<G>) -> Fq<G>
+{
+    let x = x.into_repr();
+    let limbs = x.as_ref();
+    assert!(limbs[2..].iter().all(|l| *l == 0));
+    let two: Fq<G> = (2 as u64).into();
+    Fq::<G>::from(limbs[1]) * &two.pow(&[64]) + &Fq::<G>::from(limbs[0])
+}
+
+// This function computes the base field elements FqSponge::absorb_fr absorbs for the scalar
+fn absorbed_fr<G: AffineCurve>(x: Fr<G>) -> Vec<Fq<G>>
+where G::BaseField: PrimeField, <Fq<G> as PrimeField>::BigInt: Into<<Fr<G> as PrimeField>::BigInt>
+{
+    let total_length = Fr::<G>::size_in_bits();
+    let mut bits: Vec<bool> = x.into_repr().to_bits();
+    bits.reverse();
+    let mut bits : Vec<_> = (0..total_length).map(|i| i < bits.len() && bits[i]).collect();
+    bits.reverse();
+
+    if <Fr<G> as PrimeField>::Params::MODULUS < <Fq<G> as PrimeField>::Params::MODULUS.into()
+    {
+        vec![Fq::<G>::from_repr(<Fq<G> as PrimeField>::BigInt::from_bits(&bits))]
+    }
+    else
+    {
+        vec!
+        [
+            Fq::<G>::from_repr(<Fq<G> as PrimeField>::BigInt::from_bits(&bits[1..])),
+            if bits[0] {Fq::<G>::one()} else {Fq::<G>::zero()}
+        ]
+    }
+}
+
+// Preparation of the dlog Plonk proof for the in-circuit verification
+pub trait RecursionInput<G: CommitmentCurve>
+{
+    // This function prepares the proof for the in-circuit verification
+    //     index: verifier index
+    //     RETURN: verifier circuit input, values deferred by the verifier circuit and
+    //             the accumulator (challenges, sg) of the proof
+    fn recursion_input
+        <EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
+         EFrSponge: FrSponge<Fr<G>>,
+        >
+    (
+        &self,
+        index: &Index<G>,
+    ) -> (ProofInput<Fq<G>>, Deferred<Fq<G>>, (Vec<Fr<G>>, PolyComm<G>));
+}
+
+impl<G: CommitmentCurve> RecursionInput<G> for ProverProof<G>
+where
+    G::ScalarField: CommitmentField,
+    G::BaseField: PrimeField,
+    <Fq<G> as PrimeField>::BigInt: Into<<Fr<G> as PrimeField>::BigInt>,
+{
+    fn recursion_input
+        <EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
+         EFrSponge: FrSponge<Fr<G>>,
+        >
+    (
+        &self,
+        index: &Index<G>,
+    ) -> (ProofInput<Fq<G>>, Deferred<Fq<G>>, (Vec<Fr<G>>, PolyComm<G>))
+    {
+        let p_comm = index.public_comm(&self.public);
+        let (fq_sponge, _, oracles, _, p_eval, _, polys, _) = self.oracles::<EFqSponge, EFrSponge>(index, &p_comm);
+
+        // the combined evaluation the opening proof is run for, with the polynomials in the order of the verifier
+        let combined_inner_product =
+        {
+            let mut es : Vec<(Vec<&Vec<Fr<G>>>, Option<usize>)> = polys.iter().map(|(_, e)| (e.iter().map(|x| x).collect(), None)).collect();
+            es.extend(
+                vec!
+                [
+                    (p_eval.iter().map(|e| e).collect::<Vec<_>>(), None),
+
+                    (self.evals.iter().map(|e| &e.l).collect::<Vec<_>>(), None),
+                    (self.evals.iter().map(|e| &e.r).collect::<Vec<_>>(), None),
+                    (self.evals.iter().map(|e| &e.o).collect::<Vec<_>>(), None),
+                    (self.evals.iter().map(|e| &e.z).collect::<Vec<_>>(), None),
+
+                    (self.evals.iter().map(|e| &e.f).collect::<Vec<_>>(), None),
+
+                    (self.evals.iter().map(|e| &e.sigma1).collect::<Vec<_>>(), None),
+                    (self.evals.iter().map(|e| &e.sigma2).collect::<Vec<_>>(), None),
+
+                    (self.evals.iter().map(|e| &e.t).collect::<Vec<_>>(), Some(index.max_quot_size)),
+                ]
+            );
+
+            combined_inner_product::<G>(&[oracles.zeta, oracles.zeta * &index.domain.group_gen], &oracles.v, &oracles.u, &es, index.max_poly_size)
+        };
+
+        let dummy = (Fq::<G>::zero(), Fq::<G>::zero());
+        let coords = |g: &G| g.to_coordinates().unwrap();
+        let comm = |c: &PolyComm<G>| c.unshifted.iter().map(coords).collect::<Vec<_>>();
+
+        // the quotient commitment is absorbed padded to the maximal size, followed by the shifted part
+        let t_comm =
+        {
+            let max_t_size = (index.max_quot_size + index.max_poly_size - 1) / index.max_poly_size;
+            let mut t = comm(&self.commitments.t_comm);
+            t.resize(max_t_size, dummy);
+            let s = self.commitments.t_comm.shifted.unwrap();
+            t.push(if s.is_zero() {dummy} else {coords(&s)});
+            t
+        };
+
+        // replay the opening proof transcript
+        let digest = fq_sponge.clone().challenge_fq();
+        let combined_inner_product = shift_scalar(combined_inner_product);
+        let mut sponge = fq_sponge.clone();
+        sponge.absorb_fr(&[combined_inner_product]);
+        let combined_inner_product = absorbed_fr::<G>(combined_inner_product);
+        let u = sponge.clone().challenge_fq();
+        let prechallenges = self.proof.prechallenges(&mut sponge);
+        sponge.absorb_g(&[self.proof.delta]);
+        let c = sponge.challenge();
+
+        let chal = prechallenges.iter().map(|p| p.to_field(&index.pcs.endo_r)).collect::<Vec<_>>();
+        let lr_inv = self.proof.lr.iter().zip(chal.iter()).map(|((l, _), c)| l.mul(c.inverse().unwrap()).into_affine()).collect::<Vec<_>>();
+        let folded = self.proof.lr.iter().zip(lr_inv.iter().zip(chal.iter())).fold
+        (
+            G::Projective::zero(),
+            |acc, ((_, r), (l_inv, c))| acc + &l_inv.into_projective() + &r.mul(*c)
+        ).into_affine();
+
+        (
+            ProofInput
+            {
+                p_comm: comm(&p_comm),
+                l_comm: comm(&self.commitments.l_comm),
+                r_comm: comm(&self.commitments.r_comm),
+                o_comm: comm(&self.commitments.o_comm),
+                z_comm: comm(&self.commitments.z_comm),
+                t_comm,
+                combined_inner_product: combined_inner_product.clone(),
+                lr: self.proof.lr.iter().map(|(l, r)| (coords(l), coords(r))).collect(),
+                lr_inv: lr_inv.iter().map(coords).collect(),
+                delta: coords(&self.proof.delta),
+                sg: coords(&self.proof.sg),
+            },
+            Deferred
+            {
+                beta: challenge_to_fq::<G>(oracles.beta),
+                gamma: challenge_to_fq::<G>(oracles.gamma),
+                alpha_chal: challenge_to_fq::<G>(oracles.alpha_chal.0),
+                zeta_chal: challenge_to_fq::<G>(oracles.zeta_chal.0),
+                digest,
+                combined_inner_product,
+                u,
+                prechallenges: prechallenges.iter().map(|p| challenge_to_fq::<G>(p.0)).collect(),
+                c: challenge_to_fq::<G>(c),
+                folded: coords(&folded),
+                sg: coords(&self.proof.sg),
+                p_comm: comm(&p_comm),
+            },
+            (chal, PolyComm {unshifted: vec![self.proof.sg], shifted: None})
+        )
+    }
+}
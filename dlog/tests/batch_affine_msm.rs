@@ -0,0 +1,87 @@
+/*****************************************************************************************************************
+
+This source file tests the batch-affine multi-scalar multiplication selected for the SRS against the
+projective one: the commitments and the opening proofs computed with the same randomness are identical,
+and the proofs are verified with either of the algorithms. The timings of both are printed.
+
+*****************************************************************************************************************/
+
+use algebra::{tweedle::{dee::{Affine, TweedledeeParameters}, Fp}, UniformRand};
+use commitment_dlog::{srs::SRS, commitment::CommitmentCurve, msm::MSMMethod};
+use oracle::FqSponge;
+use oracle::sponge::DefaultFqSponge;
+use oracle::poseidon::PlonkSpongeConstants as SC;
+use oracle::utils::PolyUtils;
+use ff_fft::DensePolynomial;
+use colored::Colorize;
+use rand::{SeedableRng, rngs::StdRng};
+use std::time::Instant;
+use groupmap::GroupMap;
+
+#[test]
+fn batch_affine_msm()
+{
+    let rng = &mut rand::thread_rng();
+    let size = 1 << 10;
+    let projective = SRS::<Affine>::create(size);
+    let mut affine = projective.clone();
+    affine.msm_method = MSMMethod::BatchAffine;
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let sponge = DefaultFqSponge::<TweedledeeParameters, SC>::new(oracle::tweedle::fq::params());
+
+    let polys = [size / 3, size, 2 * size + 5].iter().map(|n| DensePolynomial::<Fp>::rand(n - 1, rng)).collect::<Vec<_>>();
+    let bounds = vec![Some(size / 3), None, Some(2 * size + 5)];
+    let x = (0..3).map(|_| Fp::rand(rng)).collect::<Vec<Fp>>();
+    let (polymask, evalmask) = (Fp::rand(rng), Fp::rand(rng));
+
+    let mut results = [&projective, &affine].iter().map
+    (
+        |srs|
+        {
+            let start = Instant::now();
+            let comm = polys.iter().zip(bounds.iter()).map(|(p, b)| srs.commit(p, *b, &mut StdRng::seed_from_u64(0))).collect::<Vec<_>>();
+            println!("{}{:?} {:?}", "commitment time: ".yellow(), srs.msm_method, start.elapsed());
+
+            let start = Instant::now();
+            let proof = srs.open::<DefaultFqSponge<TweedledeeParameters, SC>>
+            (
+                &group_map,
+                polys.iter().zip(bounds.iter()).zip(comm.iter()).map(|((p, b), c)| (p, *b, c.1.clone())).collect(),
+                &x,
+                polymask,
+                evalmask,
+                sponge.clone(),
+                &mut StdRng::seed_from_u64(1)
+            );
+            println!("{}{:?} {:?}", "open time: ".magenta(), srs.msm_method, start.elapsed());
+            (comm, proof)
+        }
+    ).collect::<Vec<_>>();
+
+    let (affine_result, projective_result) = (results.pop().unwrap(), results.pop().unwrap());
+    for (c0, c1) in projective_result.0.iter().zip(affine_result.0.iter())
+    {
+        assert_eq!(c0.0.unshifted, c1.0.unshifted);
+        assert_eq!(c0.0.shifted, c1.0.shifted);
+    }
+    let (p0, p1) = (&projective_result.1, &affine_result.1);
+    assert_eq!((&p0.lr, p0.delta, p0.z1, p0.z2, p0.sg), (&p1.lr, p1.delta, p1.z1, p1.z2, p1.sg));
+
+    let evals = polys.iter().map(|p| x.iter().map(|xx| p.eval(*xx, size)).collect::<Vec<_>>()).collect::<Vec<_>>();
+    for srs in [&projective, &affine].iter()
+    {
+        let mut batch = vec!
+        [(
+            sponge.clone(),
+            x.clone(),
+            polymask,
+            evalmask,
+            projective_result.0.iter().zip(evals.iter()).zip(bounds.iter()).map(|((c, e), b)| (&c.0, e.iter().collect::<Vec<_>>(), *b)).collect::<Vec<_>>(),
+            p0
+        )];
+        let start = Instant::now();
+        assert!(srs.verify::<DefaultFqSponge<TweedledeeParameters, SC>>(&group_map, &mut batch, rng));
+        println!("{}{:?} {:?}", "verification time: ".green(), srs.msm_method, start.elapsed());
+    }
+}
@@ -0,0 +1,199 @@
+/*********************************************************************************************************
+
+This source file tests the custom gate registry with two custom constraints defined outside of
+the plonk_circuits crate:
+
+1. scaled square gate, two selectors (gate indicator and scaling coefficient), domain d4
+
+    c * l * l - o = 0
+
+2. fifth power gate, references the next row, domain d8
+
+    l^5 + r - l_next = 0
+
+The test verifies both positive and negative outcomes for satisfying and not satisfying witnesses
+
+**********************************************************************************************************/
+
+use plonk_circuits::{wires::GateWires, gate::CircuitGate, constraints::ConstraintSystem};
+use plonk_circuits::{custom::{CustomGate, GateRegistry}, polynomial::WitnessShifts, scalars::ProofEvaluations};
+use oracle::{poseidon::{ArithmeticSpongeParams, PlonkSpongeConstants as SC}, sponge::{DefaultFqSponge, DefaultFrSponge}};
+use commitment_dlog::srs::SRS;
+use algebra::{Field, tweedle::{dee::{Affine, TweedledeeParameters}, fp::Fp}, One, Zero, UniformRand};
+use plonk_protocol_dlog::{prover::ProverProof, index::Index};
+use ff_fft::{Evaluations, Radix2EvaluationDomain as D};
+use oracle::utils::EvalUtils;
+use std::sync::Arc;
+use std::time::Instant;
+use colored::Colorize;
+use rand_core::OsRng;
+
+const MAX_SIZE: usize = 8; // max size of poly chunks
+const N: usize = 8; // Plonk domain size
+const PUBLIC: usize = 1;
+
+struct ScaledSquare;
+
+impl CustomGate<Fp> for ScaledSquare
+{
+    fn selectors(&self) -> usize {2}
+    fn selector(&self, gate: &CircuitGate<Fp>) -> Vec<Fp> {vec![Fp::one(), gate.c[0]]}
+    fn alphas(&self) -> usize {1}
+    fn degree(&self) -> usize {3}
+
+    fn verify(&self, this: &CircuitGate<Fp>, _next: &CircuitGate<Fp>, witness: &Vec<Fp>) -> bool
+    {
+        this.c[0] * &witness[this.wires.l.0].square() == witness[this.wires.o.0]
+    }
+
+    fn quot(&self, polys: &WitnessShifts<Fp>, selectors: &[Evaluations<Fp, D<Fp>>], alpha: &[Fp]) -> Evaluations<Fp, D<Fp>>
+    {
+        (&(&polys.this.l.square() * &selectors[1]) - &(&polys.this.o * &selectors[0])).scale(alpha[0])
+    }
+
+    fn scalars(&self, evals: &Vec<ProofEvaluations<Fp>>, alpha: &[Fp]) -> Vec<Fp>
+    {
+        vec![-evals[0].o * &alpha[0], evals[0].l.square() * &alpha[0]]
+    }
+}
+
+struct FifthPower;
+
+impl CustomGate<Fp> for FifthPower
+{
+    fn alphas(&self) -> usize {1}
+    fn degree(&self) -> usize {6}
+
+    fn verify(&self, this: &CircuitGate<Fp>, next: &CircuitGate<Fp>, witness: &Vec<Fp>) -> bool
+    {
+        witness[this.wires.l.0].pow(&[5]) + &witness[this.wires.r.0] == witness[next.wires.l.0]
+    }
+
+    fn quot(&self, polys: &WitnessShifts<Fp>, selectors: &[Evaluations<Fp, D<Fp>>], alpha: &[Fp]) -> Evaluations<Fp, D<Fp>>
+    {
+        &(&(&polys.this.l.pow(5) + &polys.this.r) - &polys.next.l).scale(alpha[0]) * &selectors[0]
+    }
+
+    fn scalars(&self, evals: &Vec<ProofEvaluations<Fp>>, alpha: &[Fp]) -> Vec<Fp>
+    {
+        vec![(evals[0].l.pow(&[5]) + &evals[0].r - &evals[1].l) * &alpha[0]]
+    }
+}
+
+#[test]
+fn custom_gate()
+{
+    let z = Fp::zero();
+    let p = Fp::one();
+
+    let mut registry = GateRegistry::<Fp>::new();
+    let sqr = registry.register("scaled_square", Arc::new(ScaledSquare)).unwrap();
+    let pow5 = registry.register("fifth_power", Arc::new(FifthPower)).unwrap();
+    assert!(registry.register("fifth_power", Arc::new(FifthPower)).is_none());
+
+    /* permutation sets:
+
+        L0, L1, R2
+        O1, L2
+    */
+
+    let gates = vec!
+    [
+        CircuitGate::<Fp>::create_generic(GateWires::wires((0, 1), (N, N), (2*N, 2*N)), p, z, z, z, z),
+        CircuitGate::<Fp>::create_custom(sqr, GateWires::wires((1, N+2), (N+1, N+1), (2*N+1, 2)), vec![Fp::from(3u64)]),
+        CircuitGate::<Fp>::create_custom(pow5, GateWires::wires((2, 2*N+1), (N+2, 0), (2*N+2, 2*N+2)), vec![]),
+        CircuitGate::<Fp>::create_custom(sqr, GateWires::wires((3, 3), (N+3, N+3), (2*N+3, 2*N+3)), vec![Fp::from(2u64)]),
+        CircuitGate::<Fp>::zero(GateWires::wires((4, 4), (N+4, N+4), (2*N+4, 2*N+4))),
+    ];
+
+    // custom gates have to be consistent with the registry
+    assert!(ConstraintSystem::<Fp>::create(gates.clone(), oracle::tweedle::fp::params(), PUBLIC).is_none());
+
+    let (endo_q, _endo_r) = commitment_dlog::srs::endos::<algebra::tweedle::dum::Affine>();
+    let srs = SRS::create(MAX_SIZE);
+
+    let index = Index::<Affine>::create
+    (
+        ConstraintSystem::<Fp>::create_with_registry(gates, registry, oracle::tweedle::fp::params() as ArithmeticSpongeParams<Fp>, PUBLIC).unwrap(),
+        oracle::tweedle::fq::params(),
+        endo_q,
+        Arc::new(srs)
+    );
+
+    positive(&index);
+    negative(&index);
+}
+
+fn witness(x: Fp) -> Vec<Fp>
+{
+    let y = Fp::from(3u64) * &x.square();
+    let w = y.pow(&[5]) + &x;
+
+    let mut l = vec![x, x, y, w];
+    let mut r = vec![Fp::zero(), Fp::zero(), x, Fp::zero()];
+    let mut o = vec![Fp::zero(), y, Fp::zero(), Fp::from(2u64) * &w.square()];
+
+    l.resize(N, Fp::zero());
+    r.resize(N, Fp::zero());
+    o.resize(N, Fp::zero());
+
+    let mut witness = l;
+    witness.append(&mut r);
+    witness.append(&mut o);
+    witness
+}
+
+fn positive(index: &Index<Affine>)
+{
+    let rng = &mut OsRng;
+
+    println!("{}", "Prover 10 zk-proofs computation".green());
+    let mut start = Instant::now();
+
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.custom_comm.values().flatten().count(), 3);
+
+    let mut batch = Vec::new();
+    for _ in 0..10
+    {
+        let witness = witness(Fp::rand(rng));
+
+        // verify the circuit satisfiability by the computed witness
+        assert_eq!(index.cs.verify(&witness), true);
+
+        batch.push(ProverProof::create::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+            &witness, &index, vec![]).unwrap());
+    }
+    println!("{}{:?}", "Execution time: ".yellow(), start.elapsed());
+
+    println!("{}", "Verifier zk-proofs verification".green());
+    start = Instant::now();
+    let batch : Vec<_> = batch.iter().map(|p| (&verifier_index, p)).collect();
+    match ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &batch)
+    {
+        Err(error) => {panic!("Failure verifying the prover's proofs in batch: {}", error)},
+        Ok(_) => {println!("{}{:?}", "Execution time: ".yellow(), start.elapsed());}
+    }
+}
+
+fn negative(index: &Index<Affine>)
+{
+    let rng = &mut OsRng;
+
+    // break the scaled square constraint
+    let mut witness = witness(Fp::rand(rng));
+    witness[2*N+1] += &Fp::one();
+    witness[2] = witness[2*N+1];
+
+    // verify the circuit satisfiability by the computed witness
+    assert_eq!(index.cs.verify(&witness), false);
+
+    // attempt creating the proof
+    match ProverProof::create::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &witness, &index, vec![])
+    {
+        Err(_) => {println!("{}", "Proof creation failed as expected".green())},
+        Ok(_) => {panic!("Proof created for the not satisfying witness")}
+    }
+}
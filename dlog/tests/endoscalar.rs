@@ -0,0 +1,96 @@
+/*********************************************************************************************************
+
+This source file tests the in-circuit ScalarChallenge::to_field gadget against the native conversion
+for random scalar challenges over the tweedle and pasta scalar fields, and checks that the tampered
+witness is rejected. The endoscalar custom gate is also proven and verified over tweedledee.
+
+**********************************************************************************************************/
+
+use plonk_circuits::gadgets::{builder::{CircuitBuilder, Circuit}, endoscalar::to_field};
+use oracle::{poseidon::{ArithmeticSpongeParams, PlonkSpongeConstants as SC}, sponge::{DefaultFqSponge, DefaultFrSponge, ScalarChallenge}};
+use commitment_dlog::srs::{SRS, endos};
+use algebra::{tweedle, pasta, FftField, PrimeField, SquareRootField, Field, One};
+use plonk_protocol_dlog::{prover::ProverProof, index::Index};
+use rand::Rng;
+use std::sync::Arc;
+use rand_core::OsRng;
+
+const CHALLENGES: usize = 4;
+
+// This function builds the circuit converting random scalar challenges
+fn circuit<F: FftField + PrimeField>(endo: F) -> Circuit<F>
+{
+    let rng = &mut OsRng;
+    let mut cb = CircuitBuilder::<F>::new();
+    for _ in 0..CHALLENGES
+    {
+        let two: F = (2 as u64).into();
+        let c = F::from(rng.gen::<u64>()) * &two.pow(&[64]) + &F::from(rng.gen::<u64>());
+        let x = cb.witness(c);
+        let y = to_field(&mut cb, endo, x);
+        assert_eq!(cb.value(y), ScalarChallenge(c).to_field(&endo));
+        cb.public(y);
+    }
+    cb.finalize()
+}
+
+// This function checks the circuit satisfiability and the rejection of the tampered witness
+fn check<F: FftField + PrimeField + SquareRootField>(endo: F, params: ArithmeticSpongeParams<F>)
+{
+    let circuit = circuit(endo);
+    let cs = circuit.constraint_system(params).unwrap();
+    assert_eq!(cs.verify(&circuit.witness), true);
+
+    // wrong output
+    let mut witness = circuit.witness.clone();
+    witness[0] += &F::one();
+    assert_eq!(cs.verify(&witness), false);
+
+    // wrong crumbs, the first step row follows the public and the advice rows
+    let n = circuit.gates.len();
+    let row = circuit.public + 2;
+    let mut witness = circuit.witness.clone();
+    witness[2*n + row] += &F::one();
+    assert_eq!(cs.verify(&witness), false);
+}
+
+#[test]
+fn endoscalar_tweedle()
+{
+    check(endos::<tweedle::dee::Affine>().1, oracle::tweedle::fp::params());
+    check(endos::<tweedle::dum::Affine>().1, oracle::tweedle::fq::params());
+}
+
+#[test]
+fn endoscalar_pasta()
+{
+    check(endos::<pasta::vesta::Affine>().1, oracle::pasta::fp::params());
+    check(endos::<pasta::pallas::Affine>().1, oracle::pasta::fq::params());
+}
+
+#[test]
+fn endoscalar_proof()
+{
+    use tweedle::{dee::{Affine, TweedledeeParameters}, fp::Fp};
+
+    let circuit = circuit(endos::<Affine>().1);
+    let srs = SRS::<Affine>::create(circuit.gates.len().next_power_of_two());
+    let index = Index::<Affine>::create
+    (
+        circuit.constraint_system(oracle::tweedle::fp::params()).unwrap(),
+        oracle::tweedle::fq::params(),
+        endos::<tweedle::dum::Affine>().0,
+        Arc::new(srs)
+    );
+    assert_eq!(index.cs.verify(&circuit.witness), true);
+
+    let proof = ProverProof::create::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &circuit.witness, &index, vec![]).unwrap();
+    let verifier_index = index.verifier_index();
+    match ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &vec![(&verifier_index, &proof)])
+    {
+        Err(error) => {panic!("Failure verifying the prover's proof: {}", error)},
+        Ok(_) => {}
+    }
+}
@@ -0,0 +1,96 @@
+/*********************************************************************************************************
+
+This source file tests the Plonk protocol generic over the polynomial commitment scheme with the dlog
+(inner product argument) commitment backend over Tweedledee. The circuit computes:
+
+1. Poseidon hash function permutation of the public input via custom Plonk constraints
+
+2. Tweedledum group addition and point doubling
+
+3. Tweedledum group endomorphism optimised variable base scalar multiplication
+
+**********************************************************************************************************/
+
+use plonk_circuits::gadgets::{builder::{CircuitBuilder, Circuit}, poseidon::SpongeGadget, endo};
+use oracle::{poseidon::{ArithmeticSponge, ArithmeticSpongeParams, Sponge, PlonkSpongeConstants as SC}, sponge::{DefaultFqSponge, DefaultFrSponge}};
+use commitment_dlog::srs::{SRS, endos};
+use plonk_protocol::{prover::ProverProof, index::Index};
+use algebra::{tweedle::{dee::{Affine, TweedledeeParameters}, dum::Affine as DumAffine, fp::Fp, fq::Fq}, AffineCurve, ProjectiveCurve, One, Zero, UniformRand};
+use std::sync::Arc;
+use rand_core::OsRng;
+use rand::Rng;
+
+fn circuit(endo: Fp) -> Circuit<Fp>
+{
+    let rng = &mut OsRng;
+    let params: ArithmeticSpongeParams<Fp> = oracle::tweedle::fp::params();
+    let mut cb = CircuitBuilder::<Fp>::new();
+
+    // Poseidon hash of the public input
+    let x = Fp::rand(rng);
+    let xv = cb.witness(x);
+    cb.public(xv);
+    let mut sponge = SpongeGadget::new(&mut cb, params.clone());
+    sponge.absorb(&mut cb, &[xv]);
+    let h = sponge.squeeze(&mut cb);
+    let mut native = ArithmeticSponge::<Fp, SC>::new();
+    native.absorb(&params, &[x]);
+    assert_eq!(cb.value(h), native.squeeze(&params));
+    cb.public(h);
+
+    // EC addition and doubling
+    let (t, q) = (DumAffine::prime_subgroup_generator().mul(Fq::rand(rng)).into_affine(),
+        DumAffine::prime_subgroup_generator().mul(Fq::rand(rng)).into_affine());
+    let tv = (cb.witness(t.x), cb.witness(t.y));
+    let qv = (cb.witness(q.x), cb.witness(q.y));
+    let sum = endo::add(&mut cb, tv, qv);
+    let s = (t.into_projective() + &q.into_projective()).into_affine();
+    assert_eq!((cb.value(sum.0), cb.value(sum.1)), (s.x, s.y));
+    let dbl = endo::double(&mut cb, qv);
+    cb.public(dbl.0);
+
+    // group endomorphism optimised scalar multiplication
+    let bits = (0..8).map
+    (
+        |_|
+        {
+            let b = cb.witness(if rng.gen::<bool>() {Fp::one()} else {Fp::zero()});
+            cb.boolean(b);
+            b
+        }
+    ).collect::<Vec<_>>();
+    let r = endo::endo_scale(&mut cb, endo, tv, &bits);
+    cb.public(r.0);
+
+    cb.finalize()
+}
+
+#[test]
+fn generic_plonk_dlog()
+{
+    let (endo, _) = endos::<DumAffine>();
+    let circuit = circuit(endo);
+    let cs = circuit.constraint_system(oracle::tweedle::fp::params()).unwrap();
+    let srs = Arc::new(SRS::<Affine>::create(cs.domain.d1.size()));
+
+    let index = Index::<Affine, SRS<Affine>>::create(cs, oracle::tweedle::fq::params(), endo, srs);
+    assert_eq!(index.cs.verify(&circuit.witness), true);
+    let verifier_index = index.verifier_index();
+
+    let batch = (0..2).map
+    (
+        |_| ProverProof::create::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(&circuit.witness, &index, vec![]).unwrap()
+    ).collect::<Vec<_>>();
+    let proofs = batch.iter().map(|p| (&verifier_index, p)).collect::<Vec<_>>();
+    assert!(ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(&proofs).is_ok());
+
+    // the tampered public input is rejected
+    let mut proof = batch[0].clone();
+    proof.public[0] += &Fp::one();
+    assert!(ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(&vec![(&verifier_index, &proof)]).is_err());
+
+    // the tampered quotient evaluation is rejected
+    let mut proof = batch[0].clone();
+    proof.evals[0].t[0] += &Fp::one();
+    assert!(ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(&vec![(&verifier_index, &proof)]).is_err());
+}
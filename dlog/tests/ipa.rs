@@ -0,0 +1,109 @@
+/*****************************************************************************************************************
+
+This source file tests the standalone inner product argument: the openings of the committed vectors
+against the explicit public vectors, among them the unit vectors opening the vector commitments at
+arbitrary positions, and the openings of the pairs of the committed vectors, all the proofs being
+verified in one batch
+
+*****************************************************************************************************************/
+
+use algebra::{tweedle::{dee::{Affine, TweedledeeParameters}, Fp}, UniformRand, ProjectiveCurve, One, Zero};
+use commitment_dlog::{srs::SRS, commitment::CommitmentCurve, ipa::{InnerProductArgument, PublicVector, Opening, VectorOpening, BatchMSM}};
+use oracle::FqSponge;
+use oracle::sponge::DefaultFqSponge;
+use oracle::poseidon::PlonkSpongeConstants as SC;
+use groupmap::GroupMap;
+
+type Sponge = DefaultFqSponge<TweedledeeParameters, SC>;
+
+#[test]
+fn ipa()
+{
+    let rng = &mut rand::thread_rng();
+    let srs = SRS::<Affine>::create(1 << 8);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let ipa = InnerProductArgument::create(&srs, &group_map);
+    let sponge = Sponge::new(oracle::tweedle::fq::params());
+
+    // the committed vectors against the public ones: the random vector, the unit vector and the powers
+    let openings = [(100, 100), (256, 256), (37, 1)].iter().map
+    (
+        |(n, position)|
+        {
+            let a = (0..*n).map(|_| Fp::rand(rng)).collect::<Vec<_>>();
+            let blinding = Fp::rand(rng);
+            let commitment = (srs.msm_g(0, &a) + &srs.scale(srs.h, blinding)).into_affine();
+
+            let b = if *position == *n {(0..*n).map(|_| Fp::rand(rng)).collect::<Vec<_>>()}
+                else {let mut b = vec![Fp::zero(); *n]; b[*position] = Fp::one(); b};
+            let b = if *n == 100 {PublicVector::Powers(vec![Fp::rand(rng), Fp::rand(rng)], Fp::rand(rng))} else {PublicVector::Vector(b)};
+            let value = a.iter().zip(b.expand(*n).iter()).fold(Fp::zero(), |acc, (a, b)| acc + &(*a * b));
+            if *n == 37 {assert_eq!(value, a[1])}
+
+            let mut sponge = sponge.clone();
+            sponge.absorb_g(&[commitment]);
+            let proof = ipa.prove(a, &b, blinding, sponge.clone(), rng);
+            (sponge, commitment, b, value, proof)
+        }
+    ).collect::<Vec<_>>();
+
+    // the pairs of the committed vectors
+    let vector_openings = [1, 4, 128].iter().map
+    (
+        |n|
+        {
+            let a = (0..*n).map(|_| Fp::rand(rng)).collect::<Vec<_>>();
+            let b = (0..*n).map(|_| Fp::rand(rng)).collect::<Vec<_>>();
+            let weights = (0..*n).map(|_| Fp::rand(rng)).collect::<Vec<_>>();
+            let bw = b.iter().zip(weights.iter()).map(|(b, w)| *b * w).collect::<Vec<_>>();
+            let commitment = (srs.msm_g(0, &a) + &srs.msm_g(*n, &bw)).into_affine();
+            let value = a.iter().zip(b.iter()).fold(Fp::zero(), |acc, (a, b)| acc + &(*a * b));
+
+            let mut sponge = sponge.clone();
+            sponge.absorb_g(&[commitment]);
+            let proof = ipa.prove_vectors(a, b, &weights, sponge.clone());
+            assert_eq!(proof.lr.len(), commitment_dlog::commitment::ceil_log2(*n));
+            (sponge, commitment, weights, value, proof)
+        }
+    ).collect::<Vec<_>>();
+
+    // all the proofs verify in one batch, and separately
+    let check = |value_shift: Fp, vector_value_shift: Fp, rng: &mut rand::rngs::ThreadRng| -> bool
+    {
+        let mut msm = BatchMSM::create(&srs, rng);
+        let mut valid = true;
+        for (s, c, b, v, p) in openings.iter()
+        {
+            let mut sponge = s.clone();
+            valid &= ipa.accumulate(&mut msm, &mut Opening
+                {sponge: &mut sponge, commitment: vec![(*c, Fp::one())], b: b.clone(), value: *v + &value_shift, proof: p});
+        }
+        for (s, c, w, v, p) in vector_openings.iter()
+        {
+            let mut sponge = s.clone();
+            valid &= ipa.accumulate_vectors(&mut msm, &mut VectorOpening
+                {sponge: &mut sponge, commitment: vec![(*c, Fp::one())], weights: w.clone(), value: *v + &vector_value_shift, proof: p});
+        }
+        valid && msm.check(&srs)
+    };
+    assert!(check(Fp::zero(), Fp::zero(), rng));
+    assert!(!check(Fp::one(), Fp::zero(), rng));
+    assert!(!check(Fp::zero(), Fp::one(), rng));
+
+    let (s, c, b, v, p) = &openings[1];
+    let mut sponge = s.clone();
+    assert!(ipa.verify(&mut vec![Opening {sponge: &mut sponge, commitment: vec![(*c, Fp::one())], b: b.clone(), value: *v, proof: p}], rng));
+
+    let (s, c, w, v, p) = &vector_openings[2];
+    let mut sponge = s.clone();
+    assert!(ipa.verify_vectors(&mut vec![VectorOpening {sponge: &mut sponge, commitment: vec![(*c, Fp::one())], weights: w.clone(), value: *v, proof: p}], rng));
+
+    // the wrong commitment, the mismatched weights and the public vector longer than the proof are rejected
+    let mut sponge = s.clone();
+    assert!(!ipa.verify_vectors(&mut vec![VectorOpening {sponge: &mut sponge, commitment: vec![(*c, Fp::from(2u64))], weights: w.clone(), value: *v, proof: p}], rng));
+    let mut sponge = s.clone();
+    assert!(!ipa.verify_vectors(&mut vec![VectorOpening {sponge: &mut sponge, commitment: vec![(*c, Fp::one())], weights: w[1..].to_vec(), value: *v, proof: p}], rng));
+    let (s, c, _, v, p) = &openings[0];
+    let mut sponge = s.clone();
+    assert!(!ipa.verify(&mut vec![Opening {sponge: &mut sponge, commitment: vec![(*c, Fp::one())], b: PublicVector::Vector(vec![Fp::one(); 256]), value: *v, proof: p}], rng));
+}
@@ -0,0 +1,119 @@
+/*****************************************************************************************************************
+
+This source file tests the opening proofs trimmed to the longest opened segment: the polynomials shorter
+than the SRS are opened in fewer rounds, the degree bounded ones, shifted to the end of the SRS, in the
+full number of the rounds, and the proofs of the different lengths are verified in one batch against
+the same SRS
+
+*****************************************************************************************************************/
+
+use algebra::{tweedle::{dee::{Affine, TweedledeeParameters}, Fp}, UniformRand};
+use commitment_dlog::{srs::SRS, commitment::{CommitmentCurve, OpeningProof, PolyComm, ceil_log2}};
+use oracle::FqSponge;
+use oracle::sponge::DefaultFqSponge;
+use oracle::poseidon::PlonkSpongeConstants as SC;
+use oracle::utils::PolyUtils;
+use ff_fft::DensePolynomial;
+use groupmap::GroupMap;
+
+type Sponge = DefaultFqSponge<TweedledeeParameters, SC>;
+
+struct Opening
+{
+    x: Vec<Fp>,
+    polymask: Fp,
+    evalmask: Fp,
+    comm: Vec<(PolyComm<Affine>, Vec<Vec<Fp>>, Option<usize>)>,
+    proof: OpeningProof<Affine>,
+}
+
+#[test]
+fn ipa_trim()
+{
+    let rng = &mut rand::thread_rng();
+    let size = 1 << 10;
+    let srs = SRS::<Affine>::create(size);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let sponge = Sponge::new(oracle::tweedle::fq::params());
+
+    // the polynomial lengths and the degree bounds of the batches with the expected numbers of the rounds
+    let batches = vec!
+    [
+        (vec![(100, None), (20, None)], 7),
+        (vec![(300, None), (512, None)], 9),
+        (vec![(size + 100, None)], 10),
+        (vec![(100, Some(100)), (20, None)], 10),
+        (vec![(1, None)], 0),
+    ];
+
+    let openings = batches.iter().map
+    (
+        |(polys, rounds)|
+        {
+            let plnms = polys.iter().map(|(n, _)| DensePolynomial::<Fp>::rand(n - 1, rng)).collect::<Vec<_>>();
+            let x = (0..3).map(|_| Fp::rand(rng)).collect::<Vec<Fp>>();
+            let (polymask, evalmask) = (Fp::rand(rng), Fp::rand(rng));
+
+            let comm = plnms.iter().zip(polys.iter()).map(|(p, (_, b))| srs.commit(p, *b, rng)).collect::<Vec<_>>();
+            let proof = srs.open::<Sponge>
+            (
+                &group_map,
+                plnms.iter().zip(polys.iter()).zip(comm.iter()).map(|((p, (_, b)), c)| (p, *b, c.1.clone())).collect(),
+                &x,
+                polymask,
+                evalmask,
+                sponge.clone(),
+                rng
+            );
+            assert_eq!(proof.lr.len(), *rounds);
+
+            let comm = plnms.iter().zip(polys.iter()).zip(comm.into_iter()).map
+                (|((p, (_, b)), c)| (c.0, x.iter().map(|xx| p.eval(*xx, size)).collect::<Vec<_>>(), *b)).collect::<Vec<_>>();
+            Opening {x, polymask, evalmask, comm, proof}
+        }
+    ).collect::<Vec<_>>();
+
+    let verify = |openings: &Vec<&Opening>, rng: &mut rand::rngs::ThreadRng| -> bool
+    {
+        let mut batch = openings.iter().map
+        (
+            |o|
+            (
+                sponge.clone(),
+                o.x.clone(),
+                o.polymask,
+                o.evalmask,
+                o.comm.iter().map(|(c, e, b)| (c, e.iter().collect::<Vec<_>>(), *b)).collect::<Vec<_>>(),
+                &o.proof
+            )
+        ).collect::<Vec<_>>();
+        srs.verify::<Sponge>(&group_map, &mut batch, rng)
+    };
+
+    // the proofs of all the lengths verify separately and in one batch
+    for o in openings.iter() {assert!(verify(&vec![o], rng))}
+    assert!(verify(&openings.iter().collect(), rng));
+    assert!(openings.iter().all(|o| o.proof.lr.len() <= ceil_log2(size)));
+
+    // the proof with a round dropped or added does not verify
+    let mut o = Opening
+    {
+        x: openings[1].x.clone(),
+        polymask: openings[1].polymask,
+        evalmask: openings[1].evalmask,
+        comm: openings[1].comm.clone(),
+        proof: OpeningProof
+        {
+            lr: openings[1].proof.lr[1..].to_vec(),
+            delta: openings[1].proof.delta,
+            z1: openings[1].proof.z1,
+            z2: openings[1].proof.z2,
+            sg: openings[1].proof.sg,
+        },
+    };
+    assert!(!verify(&vec![&o], rng));
+    o.proof.lr = openings[2].proof.lr.clone();
+    o.proof.lr.push(o.proof.lr[0]);
+    assert!(!verify(&vec![&o], rng));
+    assert!(!verify(&vec![&openings[0], &o], rng));
+}
@@ -0,0 +1,88 @@
+/*********************************************************************************************************
+
+This source file tests the commitments against the precomputed Lagrange basis of the SRS:
+
+1. the commitments of the evaluations are equal to the commitments of the interpolated polynomials
+   for the domains up to the SRS size, including the partial and the zero evaluations
+2. the index adds the Lagrange basis of the circuit domain to the SRS it shares, and the dlog Plonk proof
+   of the witness columns committed from their evaluations is verified
+
+**********************************************************************************************************/
+
+use plonk_circuits::gadgets::builder::CircuitBuilder;
+use oracle::{poseidon::PlonkSpongeConstants as SC, sponge::{DefaultFqSponge, DefaultFrSponge}};
+use commitment_dlog::srs::{SRS, endos};
+use algebra::{tweedle::{dee::{Affine, TweedledeeParameters}, dum, fp::Fp}, Zero, UniformRand};
+use plonk_protocol_dlog::{prover::ProverProof, index::Index};
+use ff_fft::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
+use std::sync::Arc;
+use rand_core::OsRng;
+
+const SRS_SIZE: usize = 32;
+
+#[test]
+fn lagrange_commitments()
+{
+    let rng = &mut OsRng;
+    let srs = SRS::<Affine>::create(SRS_SIZE);
+
+    let mut size = 1;
+    while size <= SRS_SIZE
+    {
+        let domain = D::<Fp>::new(size).unwrap();
+        let evals = Evaluations::<Fp, D<Fp>>::from_vec_and_domain((0..size).map(|_| Fp::rand(rng)).collect(), domain);
+        assert!(srs.commit_evaluations_non_hiding(&evals).is_none());
+
+        srs.add_lagrange_basis(domain);
+        assert_eq!(srs.lagrange_bases.get(size).unwrap().len(), size);
+
+        let partial = Evaluations::<Fp, D<Fp>>::from_vec_and_domain(evals.evals[0..(size + 1) / 2].to_vec(), domain);
+        let zero = Evaluations::<Fp, D<Fp>>::from_vec_and_domain(vec![Fp::zero(); size], domain);
+        for e in [evals, partial, zero].iter()
+        {
+            let c = srs.commit_evaluations_non_hiding(e).unwrap();
+            let p = srs.commit_non_hiding(&e.clone().interpolate(), None);
+            assert_eq!(c.unshifted, p.unshifted);
+            assert!(c.shifted.is_none());
+        }
+        size *= 2;
+    }
+}
+
+#[test]
+fn lagrange_proof()
+{
+    let rng = &mut OsRng;
+    let circuit =
+    {
+        let mut cb = CircuitBuilder::<Fp>::new();
+        let x = cb.witness(Fp::rand(rng));
+        cb.public(x);
+        let xx = cb.mul(x, x);
+        let y = cb.mul(xx, x);
+        cb.public(y);
+        cb.finalize()
+    };
+    let cs = circuit.constraint_system(oracle::tweedle::fp::params()).unwrap();
+
+    // the basis is added by the index to the SRS it shares
+    let srs = Arc::new(SRS::<Affine>::create(cs.domain.d1.size()));
+    assert!(!srs.lagrange_bases.contains_key(&cs.domain.d1.size()));
+    let index = Index::<Affine>::create(cs, oracle::tweedle::fq::params(), endos::<dum::Affine>().0, srs.clone());
+    assert!(srs.lagrange_bases.contains_key(&index.cs.domain.d1.size()));
+
+    // the public input commitment from the evaluations is the one of the interpolated polynomial
+    let public = circuit.witness[0..index.cs.public].iter().map(|x| -*x).collect::<Vec<_>>();
+    let p = Evaluations::<Fp, D<Fp>>::from_vec_and_domain(public, index.cs.domain.d1);
+    assert_eq!(srs.commit_evaluations_non_hiding(&p).unwrap().unshifted, srs.commit_non_hiding(&p.interpolate(), None).unshifted);
+
+    let proof = ProverProof::create::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &circuit.witness, &index, vec![]).unwrap();
+    let verifier_index = index.verifier_index();
+    match ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &vec![(&verifier_index, &proof)])
+    {
+        Err(error) => {panic!("Failure verifying the prover's proof: {}", error)},
+        Ok(_) => {}
+    }
+}
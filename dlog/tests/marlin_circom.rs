@@ -0,0 +1,128 @@
+/*********************************************************************************************************
+
+This source file tests the reader of the .r1cs/.wtns circuit files with the hand-built files of the
+Weierstrass curve group addition constraints of non-special pairs of points
+
+    (x2 - x1) * s = y2 - y1
+    s * s = x1 + x2 + x3
+    (x1 - x3) * s = y3 + y1
+
+with the public output x3, the public inputs x1, x2 and the private inputs y1, y2, y3, s, the wires
+being laid out as 1, x3, x1, x2, y1, y2, y3, s. The files are read back, compiled into the layout of
+the dlog Marlin index and proved. The files written for the other fields are rejected.
+
+**********************************************************************************************************/
+
+use marlin_circuits::circom::{R1CSFile, FormatError, read_wtns, write_wtns};
+use marlin_protocol_dlog::{index::{SRSSpec, Index}, prover::ProverProof};
+use commitment_dlog::{srs::SRS, commitment::CommitmentCurve};
+use oracle::{sponge::{DefaultFrSponge, DefaultFqSponge}, poseidon::MarlinSpongeConstants as SC};
+use algebra::{UniformRand, bn_382::g::{Affine, Bn_382GParameters}, tweedle, pasta, AffineCurve, Field, PrimeField, One};
+use rand_core::OsRng;
+use std::sync::Arc;
+use groupmap::GroupMap;
+
+type Fr = <Affine as AffineCurve>::ScalarField;
+
+fn circuit<F: PrimeField>() -> R1CSFile<F>
+{
+    let (x3, x1, x2, y1, y2, y3, s) = (1, 2, 3, 4, 5, 6, 7);
+    let m = -F::one();
+    R1CSFile
+    {
+        wires: 8,
+        public_outputs: 1,
+        public_inputs: 2,
+        private_inputs: 4,
+        labels: 8,
+        constraints: vec!
+        [
+            [vec![(x2, F::one()), (x1, m)], vec![(s, F::one())], vec![(y2, F::one()), (y1, m)]],
+            [vec![(s, F::one())], vec![(s, F::one())], vec![(x1, F::one()), (x2, F::one()), (x3, F::one())]],
+            [vec![(x1, F::one()), (x3, m)], vec![(s, F::one())], vec![(y3, F::one()), (y1, F::one())]],
+        ],
+    }
+}
+
+fn witness<F: Field>(x1: F, y1: F, x2: F, y2: F) -> Vec<F>
+{
+    let s = (y2 - &y1) / &(x2 - &x1);
+    let x3 = s.square() - &x1 - &x2;
+    let y3 = (x1 - &x3) * &s - &y1;
+    vec![F::one(), x3, x1, x2, y1, y2, y3, s]
+}
+
+#[test]
+fn marlin_circom()
+{
+    let rng = &mut OsRng;
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    let mut r1cs_file = Vec::new();
+    circuit::<Fr>().write(&mut r1cs_file).unwrap();
+    let w = witness(Fr::rand(rng), Fr::rand(rng), Fr::rand(rng), Fr::rand(rng));
+    let mut wtns_file = Vec::new();
+    write_wtns(&mut wtns_file, &w).unwrap();
+
+    // hand-checked layout of the file headers
+    assert_eq!(&r1cs_file[0..12], &[b'r', b'1', b'c', b's', 1, 0, 0, 0, 3, 0, 0, 0]);
+    assert_eq!(&r1cs_file[12..16], &[1, 0, 0, 0]);
+    assert_eq!(&r1cs_file[24..28], &[48, 0, 0, 0]);
+    assert_eq!(&wtns_file[0..12], &[b'w', b't', b'n', b's', 2, 0, 0, 0, 2, 0, 0, 0]);
+    assert_eq!(wtns_file.len(), 12 + 12 + 4 + 48 + 4 + 12 + 8 * 48);
+
+    let circuit = R1CSFile::<Fr>::read(&r1cs_file[..]).unwrap();
+    assert_eq!(circuit.public(), 3);
+    assert_eq!(circuit.constraints, self::circuit::<Fr>().constraints);
+    let values = read_wtns::<Fr, _>(&wtns_file[..]).unwrap();
+    assert_eq!(values, w);
+
+    let r1cs = circuit.compile(&values).unwrap();
+    assert!(r1cs.verify());
+    assert_eq!(r1cs.public(), vec![Fr::one(), w[1], w[2], w[3]]);
+
+    let index = Index::<Affine>::create
+    (
+        r1cs.a.clone(),
+        r1cs.b.clone(),
+        r1cs.c.clone(),
+        r1cs.public_inputs,
+        r1cs.witness.len(),
+        oracle::bn_382::fq::params(),
+        oracle::bn_382::fp::params(),
+        SRSSpec::Use(Arc::new(SRS::create(r1cs.witness.len())))
+    ).unwrap();
+    assert!(index.verify(&r1cs.witness));
+
+    let verifier_index = index.verifier_index();
+    let proof = ProverProof::create::<DefaultFqSponge<Bn_382GParameters, SC>, DefaultFrSponge<Fr, SC>>
+        (&group_map, &r1cs.witness, &index, vec![], rng).unwrap();
+    assert!(ProverProof::verify::<DefaultFqSponge<Bn_382GParameters, SC>, DefaultFrSponge<Fr, SC>>
+        (&group_map, &vec![(&verifier_index, proof)], rng));
+
+    // the witness breaking the constraints or missing the wires is rejected
+    let mut bad = values.clone();
+    bad[7] += &Fr::one();
+    assert!(!circuit.compile(&bad).unwrap().verify());
+    assert!(matches!(circuit.builder(&bad[1..]), Err(FormatError::Witness)));
+
+    // the files of the other fields are rejected
+    let mut file = Vec::new();
+    self::circuit::<tweedle::fp::Fp>().write(&mut file).unwrap();
+    assert!(R1CSFile::<tweedle::fp::Fp>::read(&file[..]).is_ok());
+    assert!(matches!(R1CSFile::<Fr>::read(&file[..]), Err(FormatError::Field)));
+    assert!(matches!(R1CSFile::<pasta::fp::Fp>::read(&file[..]), Err(FormatError::Field)));
+    let mut file = Vec::new();
+    write_wtns(&mut file, &witness::<pasta::fp::Fp>(2u64.into(), 3u64.into(), 5u64.into(), 7u64.into())).unwrap();
+    assert!(read_wtns::<pasta::fp::Fp, _>(&file[..]).is_ok());
+    assert!(matches!(read_wtns::<tweedle::fq::Fq, _>(&file[..]), Err(FormatError::Field)));
+
+    // the corrupted files are rejected
+    let mut file = r1cs_file.clone();
+    file[0] = b'x';
+    assert!(matches!(R1CSFile::<Fr>::read(&file[..]), Err(FormatError::Magic)));
+    assert!(R1CSFile::<Fr>::read(&r1cs_file[..r1cs_file.len() - 1]).is_err());
+    let mut file = wtns_file.clone();
+    for b in file[wtns_file.len() - 48..].iter_mut() {*b = 0xff}
+    assert!(matches!(read_wtns::<Fr, _>(&file[..]), Err(FormatError::Element)));
+}
@@ -30,6 +30,7 @@ use oracle::{sponge::{DefaultFrSponge, DefaultFqSponge}, poseidon::{ArithmeticSp
 use rand_core::{RngCore, OsRng};
 use std::{io, io::Write};
 use std::time::Instant;
+use std::sync::Arc;
 use colored::Colorize;
 use ff_fft::{DensePolynomial};
 use groupmap::GroupMap;
@@ -79,7 +80,7 @@ fn dlog_marlin_group_addition()
         MAX_SIZE,
         oracle::bn_382::fq::params() as ArithmeticSpongeParams<Fr>,
         oracle::bn_382::fp::params(),
-        SRSSpec::Use(&srs)
+        SRSSpec::Use(Arc::new(srs))
     ).unwrap();
 
     positive(&index, rng);
@@ -208,11 +209,11 @@ where <Fr as std::str::FromStr>::Err : std::fmt::Debug
         assert_eq!(index.verify(&witness), true);
 
         let prev = {
-            let k = ceil_log2(index.srs.get_ref().g.len());
+            let k = ceil_log2(index.srs.g().len());
             let chals : Vec<_> = (0..k).map(|_| Fr::rand(rng)).collect();
             let comm = {
                 let b = DensePolynomial::from_coefficients_vec(b_poly_coefficients(&chals));
-                index.srs.get_ref().commit_non_hiding(&b, None)
+                index.srs.commit_non_hiding(&b, None)
             };
             ( chals, comm )
         };
@@ -0,0 +1,97 @@
+/*********************************************************************************************************
+
+This source file tests the Marlin R1CS builder with the Weierstrass curve group addition constraints
+of non-special pairs of points
+
+    (x2 - x1) * s = y2 - y1
+    s * s = x1 + x2 + x3
+    (x1 - x3) * s = y3 + y1
+
+with the public x1, x2, x3 and the private y1, y2, y3, s. The matrices and the witness emitted by the
+builder are fed as they are into the dlog Marlin index and prover.
+
+**********************************************************************************************************/
+
+use marlin_circuits::r1cs::{R1CSBuilder, LinearCombination, ONE};
+use marlin_protocol_dlog::{index::{SRSSpec, Index}, prover::ProverProof};
+use commitment_dlog::{srs::SRS, commitment::CommitmentCurve};
+use oracle::{sponge::{DefaultFrSponge, DefaultFqSponge}, poseidon::MarlinSpongeConstants as SC};
+use algebra::{UniformRand, bn_382::g::{Affine, Bn_382GParameters}, AffineCurve, Field, One, Zero};
+use rand_core::OsRng;
+use std::sync::Arc;
+use groupmap::GroupMap;
+
+type Fr = <Affine as AffineCurve>::ScalarField;
+type LC = LinearCombination<Fr>;
+
+fn circuit(x1: Fr, y1: Fr, x2: Fr, y2: Fr) -> R1CSBuilder<Fr>
+{
+    let s = (y2 - &y1) / &(x2 - &x1);
+    let x3 = s.square() - &x1 - &x2;
+    let y3 = (x1 - &x3) * &s - &y1;
+
+    let mut cb = R1CSBuilder::<Fr>::new();
+    let (x1, x2, x3) = (cb.public("x1", x1), cb.public("x2", x2), cb.public("x3", x3));
+    let (y1, y2, y3) = (cb.private("y1", y1), cb.private("y2", y2), cb.private("y3", y3));
+    let s = cb.private("s", s);
+
+    cb.enforce(LC::from(x2) - x1, s, LC::from(y2) - y1);
+    cb.enforce(s, s, LC::from(x1) + x2 + x3);
+    cb.enforce(LC::from(x1) - x3, s, LC::from(y3) + y1);
+    cb
+}
+
+#[test]
+fn marlin_r1cs_builder()
+{
+    let rng = &mut OsRng;
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let cb = circuit(Fr::rand(rng), Fr::rand(rng), Fr::rand(rng), Fr::rand(rng));
+    assert_eq!(cb.unsatisfied(), None);
+    assert_eq!(cb.value(ONE), Fr::one());
+
+    let r1cs = cb.finalize().unwrap();
+    assert!(r1cs.verify());
+    assert_eq!(r1cs.public_inputs, 4);
+    assert_eq!(r1cs.public(), vec![Fr::one(), cb.value(cb.variable("x1").unwrap()),
+        cb.value(cb.variable("x2").unwrap()), cb.value(cb.variable("x3").unwrap())]);
+
+    let index = Index::<Affine>::create
+    (
+        r1cs.a.clone(),
+        r1cs.b.clone(),
+        r1cs.c.clone(),
+        r1cs.public_inputs,
+        r1cs.witness.len(),
+        oracle::bn_382::fq::params(),
+        oracle::bn_382::fp::params(),
+        SRSSpec::Use(Arc::new(SRS::create(r1cs.witness.len())))
+    ).unwrap();
+    assert!(index.verify(&r1cs.witness));
+
+    let verifier_index = index.verifier_index();
+    let proof = ProverProof::create::<DefaultFqSponge<Bn_382GParameters, SC>, DefaultFrSponge<Fr, SC>>
+        (&group_map, &r1cs.witness, &index, vec![], rng).unwrap();
+    assert_eq!(proof.public, r1cs.public());
+    assert!(ProverProof::verify::<DefaultFqSponge<Bn_382GParameters, SC>, DefaultFrSponge<Fr, SC>>
+        (&group_map, &vec![(&verifier_index, proof)], rng));
+
+    // the reassigned private variable breaks the constraints
+    let mut cb = cb;
+    let s = cb.variable("s").unwrap();
+    cb.assign(s, cb.value(s) + &Fr::one());
+    assert_eq!(cb.unsatisfied(), Some(0));
+    let r1cs = cb.finalize().unwrap();
+    assert!(!r1cs.verify());
+    assert!(!index.verify(&r1cs.witness));
+
+    // the unused public input domain positions stay zero
+    let mut cb = R1CSBuilder::<Fr>::new();
+    let x = cb.public("x", Fr::rand(rng));
+    let y = cb.private("y", cb.value(x).square());
+    cb.enforce(x, x, y);
+    let r1cs = cb.finalize().unwrap();
+    assert!(r1cs.verify());
+    assert_eq!(r1cs.public_inputs, 2);
+    assert!(r1cs.witness.iter().filter(|w| w.is_zero()).count() >= r1cs.witness.len() - 3);
+}
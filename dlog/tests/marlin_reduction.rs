@@ -153,11 +153,11 @@ where <Fr as std::str::FromStr>::Err : std::fmt::Debug
 
     let prev = {
       let index = &indexes[0];
-      let k = ceil_log2(index.srs.get_ref().g.len());
+      let k = ceil_log2(index.srs.g().len());
       let chals : Vec<_> = (0..k).map(|_| Fr::rand(rng)).collect();
       let comm = {
           let b = DensePolynomial::from_coefficients_vec(b_poly_coefficients(&chals));
-          index.srs.get_ref().commit_non_hiding(&b, None)
+          index.srs.commit_non_hiding(&b, None)
       };
       ( chals, comm )
     };
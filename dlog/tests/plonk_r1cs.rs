@@ -0,0 +1,108 @@
+/*********************************************************************************************************
+
+This source file tests the compiler of the Marlin R1CS into the Plonk circuit of generic gates with the
+Weierstrass curve group addition constraints of non-special pairs of points
+
+    (x2 - x1) * s = y2 - y1
+    s * s = x1 + x2 + x3
+    (x1 - x3) * s = y3 + y1
+
+and the wide linear combination constraint
+
+    (x1 + x2 + x3 + y1 + y2 + y3 + 5) * 1 = t
+
+with the public x1, x2, x3 and the private y1, y2, y3, s, t. The same witness is proved over Tweedledee
+with both the dlog Marlin and the dlog turbo-Plonk provers, the proofs sharing the public inputs.
+
+**********************************************************************************************************/
+
+use marlin_circuits::r1cs::{R1CSBuilder, LinearCombination, ONE};
+use plonk_circuits::{r1cs::compile, gate::GateType, constraints::ConstraintSystem};
+use commitment_dlog::{srs::{SRS, endos}, commitment::CommitmentCurve};
+use oracle::{sponge::{DefaultFrSponge, DefaultFqSponge}, poseidon::{MarlinSpongeConstants as MSC, PlonkSpongeConstants as PSC}};
+use algebra::{UniformRand, tweedle::{dee::{Affine, TweedledeeParameters}, dum::Affine as DumAffine, fp::Fp}, Field, One};
+use ff_fft::EvaluationDomain;
+use rand_core::OsRng;
+use std::sync::Arc;
+use groupmap::GroupMap;
+
+type LC = LinearCombination<Fp>;
+
+fn circuit(x1: Fp, y1: Fp, x2: Fp, y2: Fp) -> R1CSBuilder<Fp>
+{
+    let s = (y2 - &y1) / &(x2 - &x1);
+    let x3 = s.square() - &x1 - &x2;
+    let y3 = (x1 - &x3) * &s - &y1;
+    let t = x1 + &x2 + &x3 + &y1 + &y2 + &y3 + &Fp::from(5u64);
+
+    let mut cb = R1CSBuilder::<Fp>::new();
+    let (x1, x2, x3) = (cb.public("x1", x1), cb.public("x2", x2), cb.public("x3", x3));
+    let (y1, y2, y3) = (cb.private("y1", y1), cb.private("y2", y2), cb.private("y3", y3));
+    let (s, t) = (cb.private("s", s), cb.private("t", t));
+
+    cb.enforce(LC::from(x2) - x1, s, LC::from(y2) - y1);
+    cb.enforce(s, s, LC::from(x1) + x2 + x3);
+    cb.enforce(LC::from(x1) - x3, s, LC::from(y3) + y1);
+    cb.enforce(LC::constant(Fp::from(5u64)) + x1 + x2 + x3 + y1 + y2 + y3, ONE, t);
+    cb
+}
+
+#[test]
+fn plonk_r1cs()
+{
+    let rng = &mut OsRng;
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let cb = circuit(Fp::rand(rng), Fp::rand(rng), Fp::rand(rng), Fp::rand(rng));
+    let r1cs = cb.finalize().unwrap();
+    assert!(r1cs.verify());
+
+    let circuit = compile(&r1cs.a, &r1cs.b, &r1cs.c, r1cs.public_inputs, &r1cs.witness).unwrap();
+    assert_eq!(circuit.public, r1cs.public_inputs);
+    assert!(circuit.gates.iter().all(|g| g.typ == GateType::Generic || g.typ == GateType::Zero));
+
+    // Plonk proof
+    let cs = ConstraintSystem::<Fp>::create(circuit.gates.clone(), oracle::tweedle::fp::params(), circuit.public).unwrap();
+    assert!(cs.verify(&circuit.witness));
+    let (endo_q, _) = endos::<DumAffine>();
+    let srs = SRS::<Affine>::create(cs.domain.d1.size());
+    let index = plonk_protocol_dlog::index::Index::<Affine>::create
+        (cs, oracle::tweedle::fq::params(), endo_q, Arc::new(srs));
+    let verifier_index = index.verifier_index();
+    let plonk_proof = plonk_protocol_dlog::prover::ProverProof::create::<DefaultFqSponge<TweedledeeParameters, PSC>, DefaultFrSponge<Fp, PSC>>
+        (&circuit.witness, &index, vec![]).unwrap();
+    assert!(plonk_protocol_dlog::prover::ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, PSC>, DefaultFrSponge<Fp, PSC>>
+        (&vec![(&verifier_index, &plonk_proof)]).is_ok());
+
+    // Marlin proof of the same statement
+    let index = marlin_protocol_dlog::index::Index::<Affine>::create
+    (
+        r1cs.a.clone(),
+        r1cs.b.clone(),
+        r1cs.c.clone(),
+        r1cs.public_inputs,
+        r1cs.witness.len(),
+        oracle::tweedle::fp::params(),
+        oracle::tweedle::fq::params(),
+        marlin_protocol_dlog::index::SRSSpec::Use(Arc::new(SRS::create(r1cs.witness.len())))
+    ).unwrap();
+    let verifier_index = index.verifier_index();
+    let marlin_proof = marlin_protocol_dlog::prover::ProverProof::create::<DefaultFqSponge<TweedledeeParameters, MSC>, DefaultFrSponge<Fp, MSC>>
+        (&group_map, &r1cs.witness, &index, vec![], rng).unwrap();
+    assert_eq!(marlin_proof.public, r1cs.public());
+    assert_eq!(plonk_proof.public, marlin_proof.public);
+    assert!(marlin_protocol_dlog::prover::ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, MSC>, DefaultFrSponge<Fp, MSC>>
+        (&group_map, &vec![(&verifier_index, marlin_proof)], rng));
+
+    // the witness breaking the constraints breaks the compiled circuit
+    let mut cb = cb;
+    let t = cb.variable("t").unwrap();
+    cb.assign(t, cb.value(t) + &Fp::one());
+    let r1cs = cb.finalize().unwrap();
+    let bad = compile(&r1cs.a, &r1cs.b, &r1cs.c, r1cs.public_inputs, &r1cs.witness).unwrap();
+    let cs = ConstraintSystem::<Fp>::create(bad.gates.clone(), oracle::tweedle::fp::params(), bad.public).unwrap();
+    assert!(!cs.verify(&bad.witness));
+
+    // the matrices and the witness not in the Marlin layout are rejected
+    assert!(compile(&r1cs.a, &r1cs.b, &r1cs.c, r1cs.public_inputs, &r1cs.witness[1..]).is_none());
+    assert!(compile(&r1cs.a, &r1cs.b, &r1cs.c, 0, &r1cs.witness).is_none());
+}
@@ -5,15 +5,15 @@ This source file benchmarks the constraints for the Poseidon hash permutations
 **********************************************************************************************************/
 
 use oracle::{poseidon::*, sponge::{DefaultFqSponge, DefaultFrSponge}};
-use commitment_dlog::{srs::{SRS, endos}, commitment::{CommitmentCurve, ceil_log2, b_poly_coefficients}};
+use commitment_dlog::{srs::{SRS, endos}, commitment::{ceil_log2, b_poly_coefficients}};
 use plonk_circuits::{wires::GateWires, gate::CircuitGate, constraints::ConstraintSystem};
 use algebra::{ tweedle::{dum::{Affine as Other}, dee::{Affine, TweedledeeParameters}, fp::Fp}, UniformRand};
-use plonk_protocol_dlog::{prover::{ProverProof}, index::{Index, SRSSpec}};
+use plonk_protocol_dlog::{prover::ProverProof, index::Index};
 use ff_fft::DensePolynomial;
 use std::{io, io::Write};
-use groupmap::GroupMap;
 use std::time::Instant;
 use colored::Colorize;
+use std::sync::Arc;
 use rand_core::OsRng;
 
 const PERIOD: usize = PlonkSpongeConstants::ROUNDS_FULL + 1;
@@ -66,7 +66,7 @@ fn poseidon_tweedledee()
         ConstraintSystem::<Fp>::create(gates, oracle::tweedle::fp::params(), PUBLIC).unwrap(),
         oracle::tweedle::fq::params(),
         endo_q,
-        SRSSpec::Use(&srs)
+        Arc::new(srs)
     );
 
     positive(&index);
@@ -81,7 +81,6 @@ where <Fp as std::str::FromStr>::Err : std::fmt::Debug
     let mut sponge = ArithmeticSponge::<Fp, PlonkSpongeConstants>::new();
 
     let mut batch = Vec::new();
-    let group_map = <Affine as CommitmentCurve>::Map::setup();
 
     println!("{}{:?}", "Circuit size: ".yellow(), N);
     println!("{}{:?}", "Polycommitment chunk size: ".yellow(), MAX_SIZE);
@@ -148,18 +147,18 @@ where <Fp as std::str::FromStr>::Err : std::fmt::Debug
         assert_eq!(index.cs.verify(&witness), true);
 
         let prev = {
-            let k = ceil_log2(index.srs.get_ref().g.len());
+            let k = ceil_log2(index.pcs.g().len());
             let chals : Vec<_> = (0..k).map(|_| Fp::rand(rng)).collect();
             let comm = {
                 let b = DensePolynomial::from_coefficients_vec(b_poly_coefficients(&chals));
-                index.srs.get_ref().commit_non_hiding(&b, None)
+                index.pcs.commit_non_hiding(&b, None)
             };
             ( chals, comm )
         };
 
         // add the proof to the batch
         batch.push(ProverProof::create::<DefaultFqSponge<TweedledeeParameters, PlonkSpongeConstants>, DefaultFrSponge<Fp, PlonkSpongeConstants>>(
-            &group_map, &witness, &index, vec![prev]).unwrap());
+            &witness, &index, vec![prev]).unwrap());
 
         print!("{:?}\r", test);
         io::stdout().flush().unwrap();
@@ -168,13 +167,12 @@ where <Fp as std::str::FromStr>::Err : std::fmt::Debug
 
     let verifier_index = index.verifier_index();
 
-    let lgr_comms = vec![];
-    let batch : Vec<_> = batch.iter().map(|p| (&verifier_index, &lgr_comms, p)).collect();
+    let batch : Vec<_> = batch.iter().map(|p| (&verifier_index, p)).collect();
 
     // verify the proofs in batch
     println!("{}", "Verifier zk-proofs verification".green());
     start = Instant::now();
-    match ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, PlonkSpongeConstants>, DefaultFrSponge<Fp, PlonkSpongeConstants>>(&group_map, &batch)
+    match ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, PlonkSpongeConstants>, DefaultFrSponge<Fp, PlonkSpongeConstants>>(&batch)
     {
         Err(error) => {panic!("Failure verifying the prover's proofs in batch: {}", error)},
         Ok(_) => {println!("{}{:?}", "Execution time: ".yellow(), start.elapsed());}
@@ -0,0 +1,77 @@
+/*********************************************************************************************************
+
+This source file profiles the Plonk prover over a circuit of the chained Poseidon hashes, reporting the
+time spent in the phases of the proof computation: the commitments, the permutation polynomial, the
+quotient polynomial, the linearization polynomial and the batched opening proof
+
+**********************************************************************************************************/
+
+use plonk_circuits::gadgets::{builder::CircuitBuilder, poseidon::SpongeGadget};
+use oracle::{poseidon::{ArithmeticSpongeParams, PlonkSpongeConstants as SC}, sponge::{DefaultFqSponge, DefaultFrSponge}};
+use commitment_dlog::srs::{SRS, endos};
+use plonk_protocol_dlog::{prover::{ProverProof, ProverProfile}, index::Index};
+use algebra::{tweedle::{dee::{Affine, TweedledeeParameters}, dum::Affine as DumAffine, fp::Fp}, UniformRand};
+use ff_fft::EvaluationDomain;
+use colored::Colorize;
+use std::sync::Arc;
+use rand_core::OsRng;
+
+const HASHES: usize = 64; // number of chained Poseidon hashes in the circuit
+const PROOFS: usize = 4; // number of proofs to profile
+
+type FqSponge = DefaultFqSponge<TweedledeeParameters, SC>;
+type FrSponge = DefaultFrSponge<Fp, SC>;
+
+#[test]
+fn prover_profile()
+{
+    let rng = &mut OsRng;
+    let params: ArithmeticSpongeParams<Fp> = oracle::tweedle::fp::params();
+    let mut cb = CircuitBuilder::<Fp>::new();
+
+    let x = cb.witness(Fp::rand(rng));
+    cb.public(x);
+    let h = (0..HASHES).fold
+    (
+        x,
+        |h, _|
+        {
+            let mut sponge = SpongeGadget::new(&mut cb, params.clone());
+            sponge.absorb(&mut cb, &[h]);
+            sponge.squeeze(&mut cb)
+        }
+    );
+    cb.public(h);
+    let circuit = cb.finalize();
+
+    let (endo, _) = endos::<DumAffine>();
+    let cs = circuit.constraint_system(params).unwrap();
+    let n = cs.domain.d1.size();
+    let index = Index::<Affine>::create(cs, oracle::tweedle::fq::params(), endo, Arc::new(SRS::create(n)));
+    assert_eq!(index.cs.verify(&circuit.witness), true);
+    let verifier_index = index.verifier_index();
+
+    println!("{}{:?}", "Circuit size: ".yellow(), n);
+    println!("{}{:?}", "Number of proofs: ".yellow(), PROOFS);
+
+    let mut total = ProverProfile::default();
+    for _ in 0..PROOFS
+    {
+        let (proof, profile) = ProverProof::create_profiled::<FqSponge, FrSponge>(&circuit.witness, &index, vec![]).unwrap();
+        assert!(ProverProof::verify::<FqSponge, FrSponge>(&vec![(&verifier_index, &proof)]).is_ok());
+
+        total.commitments += profile.commitments;
+        total.z += profile.z;
+        total.quotient += profile.quotient;
+        total.linearization += profile.linearization;
+        total.opening += profile.opening;
+    }
+
+    let proofs = PROOFS as u32;
+    println!("{}{:?}", "Per-proof commitment time: ".green(), total.commitments / proofs);
+    println!("{}{:?}", "Per-proof permutation polynomial time: ".green(), total.z / proofs);
+    println!("{}{:?}", "Per-proof quotient polynomial time: ".green(), total.quotient / proofs);
+    println!("{}{:?}", "Per-proof linearization time: ".green(), total.linearization / proofs);
+    println!("{}{:?}", "Per-proof opening time: ".green(), total.opening / proofs);
+    println!("{}{:?}", "Per-proof prover time: ".green(), total.total() / proofs);
+}
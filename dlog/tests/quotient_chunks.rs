@@ -0,0 +1,138 @@
+/*********************************************************************************************************
+
+This source file tests the chunked evaluation mode of the Plonk quotient polynomial contributions against
+the resident selector evaluations. The circuit computes:
+
+1. Poseidon hash function permutation of the public input via custom Plonk constraints
+
+2. Tweedledum group addition and point doubling
+
+3. Tweedledum group endomorphism optimised variable base scalar multiplication
+
+The quotient contributions of every chunking of domain d8 have to interpolate to the same polynomial as
+of the resident evaluations, and the proofs of the chunked constraint systems have to verify
+
+**********************************************************************************************************/
+
+use plonk_circuits::{gadgets::{builder::{CircuitBuilder, Circuit}, poseidon::SpongeGadget, endo}, scalars::RandomOracles};
+use oracle::{poseidon::{ArithmeticSponge, ArithmeticSpongeParams, Sponge, PlonkSpongeConstants as SC}, sponge::{DefaultFqSponge, DefaultFrSponge}};
+use commitment_dlog::srs::{SRS, endos};
+use plonk_protocol_dlog::{prover::ProverProof, index::Index, range};
+use algebra::{tweedle::{dee::{Affine, TweedledeeParameters}, dum::Affine as DumAffine, fp::Fp, fq::Fq}, AffineCurve, ProjectiveCurve, One, Zero, UniformRand};
+use ff_fft::{DensePolynomial, EvaluationDomain};
+use std::time::Instant;
+use colored::Colorize;
+use std::sync::Arc;
+use rand_core::OsRng;
+use rand::Rng;
+
+type FqSponge = DefaultFqSponge<TweedledeeParameters, SC>;
+type FrSponge = DefaultFrSponge<Fp, SC>;
+
+fn circuit(endo: Fp) -> Circuit<Fp>
+{
+    let rng = &mut OsRng;
+    let params: ArithmeticSpongeParams<Fp> = oracle::tweedle::fp::params();
+    let mut cb = CircuitBuilder::<Fp>::new();
+
+    // Poseidon hash of the public input
+    let x = Fp::rand(rng);
+    let xv = cb.witness(x);
+    cb.public(xv);
+    let mut sponge = SpongeGadget::new(&mut cb, params.clone());
+    sponge.absorb(&mut cb, &[xv]);
+    let h = sponge.squeeze(&mut cb);
+    let mut native = ArithmeticSponge::<Fp, SC>::new();
+    native.absorb(&params, &[x]);
+    assert_eq!(cb.value(h), native.squeeze(&params));
+    cb.public(h);
+
+    // EC addition and doubling
+    let (t, q) = (DumAffine::prime_subgroup_generator().mul(Fq::rand(rng)).into_affine(),
+        DumAffine::prime_subgroup_generator().mul(Fq::rand(rng)).into_affine());
+    let tv = (cb.witness(t.x), cb.witness(t.y));
+    let qv = (cb.witness(q.x), cb.witness(q.y));
+    endo::add(&mut cb, tv, qv);
+    let dbl = endo::double(&mut cb, qv);
+    cb.public(dbl.0);
+
+    // group endomorphism optimised scalar multiplication
+    let bits = (0..8).map
+    (
+        |_|
+        {
+            let b = cb.witness(if rng.gen::<bool>() {Fp::one()} else {Fp::zero()});
+            cb.boolean(b);
+            b
+        }
+    ).collect::<Vec<_>>();
+    let r = endo::endo_scale(&mut cb, endo, tv, &bits);
+    cb.public(r.0);
+
+    cb.finalize()
+}
+
+#[test]
+fn quotient_chunks()
+{
+    let rng = &mut OsRng;
+    let (endo, _) = endos::<DumAffine>();
+    let circuit = circuit(endo);
+    let cs = circuit.constraint_system(oracle::tweedle::fp::params()).unwrap();
+    let n = cs.domain.d1.size();
+    println!("{}{:?}", "Circuit size: ".yellow(), n);
+
+    // quotient contributions of the random witness polynomials and oracles
+    let mut oracles = RandomOracles::<Fp>::zero();
+    oracles.alpha = Fp::rand(rng);
+    oracles.beta = Fp::rand(rng);
+    oracles.gamma = Fp::rand(rng);
+    let mut alpha = oracles.alpha;
+    let alpha = (0..range::CUSTOM.start + cs.custom.alphas()).map(|_| {alpha *= &oracles.alpha; alpha}).collect::<Vec<_>>();
+    let (l, r, o, z) = (DensePolynomial::<Fp>::rand(n - 1, rng), DensePolynomial::<Fp>::rand(n - 1, rng),
+        DensePolynomial::<Fp>::rand(n - 1, rng), DensePolynomial::<Fp>::rand(n - 1, rng));
+    let p = DensePolynomial::<Fp>::zero();
+
+    let quotient = |cs: &plonk_circuits::constraints::ConstraintSystem<Fp>| cs.quot_evals
+    (
+        &l, &r, &o, &z,
+        |lagrange, sel|
+        {
+            let (gen4, _) = cs.gnrc_quot(lagrange, sel, &p);
+            let (pos4, pos8, _) = cs.psdn_quot(lagrange, sel, &cs.fr_sponge_params, &alpha[range::PSDN]);
+            let (mul4, mul8) = cs.vbmul_quot(lagrange, sel, &alpha[range::MUL]);
+            let (emul4, emul8) = cs.endomul_quot(lagrange, sel, &alpha[range::ENDML]);
+            let eca = cs.ecad_quot(lagrange, sel, &alpha[range::ADD]);
+            let (cst4, cst8) = cs.custom_quot(lagrange, sel, &alpha[range::CUSTOM]);
+            let perm = cs.perm_quot(lagrange, sel, &oracles);
+            (
+                &(&(&gen4 + &pos4) + &(&mul4 + &emul4)) + &cst4,
+                &(&(&pos8 + &(&mul8 + &emul8)) + &(&eca + &perm)) + &cst8,
+            )
+        }
+    );
+
+    let start = Instant::now();
+    let resident = quotient(&cs);
+    println!("{}{:?}", "Resident quotient evaluation time: ".green(), start.elapsed());
+    assert!(!resident.is_zero());
+
+    let srs = Arc::new(SRS::<Affine>::create(n));
+    for chunks in [1, 2, 4, 8].iter()
+    {
+        let mut cs = cs.clone();
+        cs.set_quotient_chunks(Some(*chunks));
+
+        let start = Instant::now();
+        assert_eq!(quotient(&cs), resident);
+        println!("{}{:?}{}{:?}", "Quotient evaluation time over ".green(), chunks, " chunks: ".green(), start.elapsed());
+
+        // the proofs of the chunked constraint system verify
+        let index = Index::<Affine>::create(cs, oracle::tweedle::fq::params(), endo, srs.clone());
+        let start = Instant::now();
+        let proof = ProverProof::create::<FqSponge, FrSponge>(&circuit.witness, &index, vec![]).unwrap();
+        println!("{}{:?}", "Prover time: ".yellow(), start.elapsed());
+        let verifier_index = index.verifier_index();
+        assert!(ProverProof::verify::<FqSponge, FrSponge>(&vec![(&verifier_index, &proof)]).is_ok());
+    }
+}
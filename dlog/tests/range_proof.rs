@@ -0,0 +1,89 @@
+/*****************************************************************************************************************
+
+This source file tests the aggregated Bulletproofs range proofs over the tweedle and pasta curves: the
+proofs of the single and of the aggregated values, of the different bit sizes, are verified in one
+batch, the values out of the range cannot be proven and the tampered proofs and statements are rejected
+
+*****************************************************************************************************************/
+
+use algebra::{tweedle, pasta, AffineCurve, ProjectiveCurve, UniformRand, One, Zero};
+use commitment_dlog::{srs::SRS, commitment::CommitmentCurve, range::{RangeProof, commit}, CommitmentField};
+use oracle::{FqSponge, sponge::DefaultFqSponge, poseidon::PlonkSpongeConstants as SC};
+use colored::Colorize;
+use std::time::Instant;
+use groupmap::GroupMap;
+use rand::Rng;
+
+type Fr<G> = <G as AffineCurve>::ScalarField;
+type Fq<G> = <G as AffineCurve>::BaseField;
+
+fn check<G: CommitmentCurve, EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>>(sponge: EFqSponge)
+where G::ScalarField : CommitmentField
+{
+    let rng = &mut rand::thread_rng();
+    let srs = SRS::<G>::create(1 << 11);
+    let group_map = <G as CommitmentCurve>::Map::setup();
+
+    // the bit sizes and the numbers of the aggregated values
+    let statements = [(64, 1), (32, 4), (8, 2), (64, 8)].iter().map
+    (
+        |(n, m)|
+        {
+            let values = (0..*m).map(|_| Fr::<G>::from(rng.gen::<u64>() >> (64 - n))).collect::<Vec<_>>();
+            let blindings = (0..*m).map(|_| Fr::<G>::rand(rng)).collect::<Vec<_>>();
+            let start = Instant::now();
+            let (commitments, proof) = RangeProof::create(&srs, &group_map, *n, &values, &blindings, sponge.clone(), rng).unwrap();
+            println!("{}{:?} {:?}", "range proof time: ".yellow(), (n, m), start.elapsed());
+            assert_eq!(commitments, values.iter().zip(blindings.iter()).map(|(v, b)| commit(&srs, *v, *b)).collect::<Vec<_>>());
+            (*n, commitments, proof)
+        }
+    ).collect::<Vec<_>>();
+
+    let verify = |statements: &Vec<(usize, Vec<G>, RangeProof<G>)>, rng: &mut rand::rngs::ThreadRng| -> bool
+    {
+        let mut batch = statements.iter().map(|(n, c, p)| (sponge.clone(), *n, c, p)).collect::<Vec<_>>();
+        RangeProof::verify(&srs, &group_map, &mut batch, rng)
+    };
+
+    let start = Instant::now();
+    assert!(verify(&statements, rng));
+    println!("{}{:?}", "batch verification time: ".green(), start.elapsed());
+    for s in statements.iter() {assert!(verify(&vec![s.clone()], rng))}
+
+    // the values out of the range and the sizes not fitting the SRS cannot be proven
+    let two = Fr::<G>::from(2u64);
+    let blindings = vec![Fr::<G>::rand(rng); 2];
+    assert!(RangeProof::create(&srs, &group_map, 8, &[Fr::<G>::from(255u64), Fr::<G>::from(256u64)], &blindings, sponge.clone(), rng).is_err());
+    assert!(RangeProof::create(&srs, &group_map, 8, &[Fr::<G>::zero(), -Fr::<G>::one()], &blindings, sponge.clone(), rng).is_err());
+    assert!(RangeProof::create(&srs, &group_map, 8, &[two; 3], &[blindings[0]; 3], sponge.clone(), rng).is_err());
+    assert!(RangeProof::create(&srs, &group_map, 512, &[two; 2], &blindings, sponge.clone(), rng).is_err());
+    assert!(RangeProof::create(&srs, &group_map, 8, &[two; 2], &blindings, sponge.clone(), rng).is_ok());
+
+    // the tampered proofs and statements are rejected, separately and in the batch
+    let mut tampered = statements.clone();
+    tampered[1].1[2] = (tampered[1].1[2].into_projective() + &srs.h.into_projective()).into_affine();
+    assert!(!verify(&tampered, rng));
+    let mut tampered = statements.clone();
+    tampered[0].2.t += &Fr::<G>::one();
+    assert!(!verify(&tampered, rng));
+    let mut tampered = statements.clone();
+    tampered[3].2.ipa.a += &Fr::<G>::one();
+    assert!(!verify(&vec![tampered[3].clone()], rng));
+    let mut tampered = statements.clone();
+    tampered[2].0 = 16;
+    assert!(!verify(&tampered, rng));
+}
+
+#[test]
+fn range_proof_tweedle()
+{
+    check::<tweedle::dee::Affine, _>(DefaultFqSponge::<_, SC>::new(oracle::tweedle::fq::params()));
+    check::<tweedle::dum::Affine, _>(DefaultFqSponge::<_, SC>::new(oracle::tweedle::fp::params()));
+}
+
+#[test]
+fn range_proof_pasta()
+{
+    check::<pasta::vesta::Affine, _>(DefaultFqSponge::<_, SC>::new(oracle::pasta::fq::params()));
+    check::<pasta::pallas::Affine, _>(DefaultFqSponge::<_, SC>::new(oracle::pasta::fp::params()));
+}
@@ -0,0 +1,191 @@
+/*********************************************************************************************************
+
+This source file tests the two step recursive composition of the dlog Plonk proofs over the
+tweedledee/tweedledum cycle of curves:
+
+1. the inner proof is created over tweedledee for a small circuit over Fp
+
+2. the wrapping proof is created over tweedledum for the circuit over Fq verifying the inner proof
+   with the in-circuit verifier gadget. The values the gadget defers to tweedledee are the public
+   input of the circuit and have to be equal to the natively computed ones
+
+3. the step proof is created over tweedledee for the circuit over Fp evaluating the challenge
+   polynomial of the inner proof from the deferred prechallenges. The accumulator of the inner proof is passed as the previous
+   challenges of the step proof, so that the inner product argument of the step proof checks the
+   deferred challenge polynomial commitment of the inner proof
+
+**********************************************************************************************************/
+
+use plonk_circuits::gadgets::{builder::CircuitBuilder, verifier, bpoly::b_poly_prechallenges};
+use oracle::{poseidon::PlonkSpongeConstants as SC, sponge::{DefaultFqSponge, DefaultFrSponge}};
+use commitment_dlog::srs::{SRSRegistry, endos};
+use algebra::{tweedle::{dee, dum, fp::Fp, fq::Fq}, AffineCurve, ProjectiveCurve, PrimeField, Field, SWModelParameters, One, Zero, UniformRand};
+use plonk_protocol_dlog::{prover::ProverProof, index::Index, recursion::RecursionInput};
+use std::time::Instant;
+use std::sync::Arc;
+use colored::Colorize;
+use rand_core::OsRng;
+
+const DEE_SIZE: usize = 16; // tweedledee SRS size of the inner proof
+
+#[test]
+fn recursion()
+{
+    let rng = &mut OsRng;
+    let dee_srs = SRSRegistry::<dee::Affine>::new();
+    let dum_srs = SRSRegistry::<dum::Affine>::new();
+
+    // inner proof over tweedledee: x^3 = y for the public x, y
+
+    let inner =
+    {
+        let mut cb = CircuitBuilder::<Fp>::new();
+        let x = cb.witness(Fp::rand(rng));
+        cb.public(x);
+        let xx = cb.mul(x, x);
+        let y = cb.mul(xx, x);
+        cb.public(y);
+        cb.finalize()
+    };
+
+    let index = Index::<dee::Affine>::create
+    (
+        inner.constraint_system(oracle::tweedle::fp::params()).unwrap(),
+        oracle::tweedle::fq::params(),
+        endos::<dum::Affine>().0,
+        dee_srs.get(DEE_SIZE)
+    );
+    assert_eq!(index.cs.verify(&inner.witness), true);
+
+    let verifier_index = index.verifier_index();
+    assert!(Arc::ptr_eq(&index.pcs, &verifier_index.pcs));
+    let proof = ProverProof::create::<DefaultFqSponge<dee::TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &inner.witness, &index, vec![]).unwrap();
+    match ProverProof::verify::<DefaultFqSponge<dee::TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &vec![(&verifier_index, &proof)])
+    {
+        Err(error) => {panic!("Failure verifying the inner proof: {}", error)},
+        Ok(_) => {}
+    }
+
+    // wrapping proof over tweedledum
+
+    println!("{}", "Verifier circuit computation".green());
+    let mut start = Instant::now();
+
+    let (input, deferred, accumulator) = proof.recursion_input
+        ::<DefaultFqSponge<dee::TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(&verifier_index);
+    let endo_q = endos::<dee::Affine>().0;
+
+    let wrap =
+    {
+        let mut cb = CircuitBuilder::<Fq>::new();
+        let out = verifier::verify(&mut cb, oracle::tweedle::fq::params(), endo_q, dee::TweedledeeParameters::COEFF_B, &input);
+        assert_eq!(out.map(|v| cb.value(*v)), deferred);
+        for v in out.to_vec() {cb.public(v);}
+        cb.finalize()
+    };
+    println!("{}{}", "Verifier circuit rows: ".yellow(), wrap.gates.len());
+
+    let wrap_index = Index::<dum::Affine>::create
+    (
+        wrap.constraint_system(oracle::tweedle::fq::params()).unwrap(),
+        oracle::tweedle::fp::params(),
+        endo_q,
+        dum_srs.get(wrap.gates.len().next_power_of_two())
+    );
+    assert_eq!(wrap_index.cs.verify(&wrap.witness), true);
+    println!("{}{:?}", "Execution time: ".yellow(), start.elapsed());
+
+    // tampered witness has to be rejected
+    {
+        let mut witness = wrap.witness.clone();
+        witness[wrap.public] += &Fq::one();
+        assert_eq!(wrap_index.cs.verify(&witness), false);
+    }
+
+    // the proof points off the curve have to be rejected
+    {
+        let mut input = input.clone();
+        input.delta.1 += &Fq::one();
+        let mut cb = CircuitBuilder::<Fq>::new();
+        let out = verifier::verify(&mut cb, oracle::tweedle::fq::params(), endo_q, dee::TweedledeeParameters::COEFF_B, &input);
+        assert!(cb.unsatisfied().is_some());
+        for v in out.to_vec() {cb.public(v);}
+        let tampered = cb.finalize();
+        assert_eq!(tampered.gates.len(), wrap.gates.len());
+        assert_eq!(wrap_index.cs.verify(&tampered.witness), false);
+    }
+
+    println!("{}", "Prover wrapping zk-proof computation".green());
+    start = Instant::now();
+    let wrap_proof = ProverProof::create::<DefaultFqSponge<dum::TweedledumParameters, SC>, DefaultFrSponge<Fq, SC>>(
+        &wrap.witness, &wrap_index, vec![]).unwrap();
+    println!("{}{:?}", "Execution time: ".yellow(), start.elapsed());
+    assert_eq!(wrap_proof.public, deferred.to_vec());
+
+    let wrap_verifier_index = wrap_index.verifier_index();
+    match ProverProof::verify::<DefaultFqSponge<dum::TweedledumParameters, SC>, DefaultFrSponge<Fq, SC>>(
+        &vec![(&wrap_verifier_index, &wrap_proof)])
+    {
+        Err(error) => {panic!("Failure verifying the wrapping proof: {}", error)},
+        Ok(_) => {}
+    }
+
+    // the folded inner product argument commitments
+    {
+        let (chals, _) = &accumulator;
+        let folded = proof.proof.lr.iter().zip(chals.iter()).fold
+        (
+            <dee::Affine as AffineCurve>::Projective::zero(),
+            |acc, ((l, r), c)| acc + &l.mul(c.inverse().unwrap()) + &r.mul(*c)
+        ).into_affine();
+        assert_eq!((folded.x, folded.y), deferred.folded);
+    }
+
+    // step proof over tweedledee carrying the accumulator of the inner proof
+
+    let (chals, sg) = accumulator;
+    let step =
+    {
+        let mut cb = CircuitBuilder::<Fp>::new();
+        let x = Fp::rand(rng);
+        let xv = cb.witness(x);
+        let pre = deferred.prechallenges.iter().map
+        (
+            |p| {let v = cb.witness(Fp::from_repr(p.into_repr())); cb.public(v); v}
+        ).collect::<Vec<_>>();
+        let b = b_poly_prechallenges(&mut cb, endos::<dee::Affine>().1, &pre, xv);
+        assert_eq!(cb.value(b), commitment_dlog::commitment::b_poly(&chals, x));
+        cb.public(b);
+        cb.finalize()
+    };
+
+    let step_index = Index::<dee::Affine>::create
+    (
+        step.constraint_system(oracle::tweedle::fp::params()).unwrap(),
+        oracle::tweedle::fq::params(),
+        endos::<dum::Affine>().0,
+        dee_srs.get(step.gates.len().next_power_of_two())
+    );
+    assert_eq!(step_index.cs.verify(&step.witness), true);
+    // the accumulator commitment is over the prefix of the SRS of the step proof
+    assert_eq!(step_index.pcs.g()[..DEE_SIZE], index.pcs.g()[..]);
+
+    let step_proof = ProverProof::create::<DefaultFqSponge<dee::TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &step.witness, &step_index, vec![(chals.clone(), sg)]).unwrap();
+
+    let step_verifier_index = step_index.verifier_index();
+    match ProverProof::verify::<DefaultFqSponge<dee::TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &vec![(&step_verifier_index, &step_proof)])
+    {
+        Err(error) => {panic!("Failure verifying the step proof: {}", error)},
+        Ok(_) => {}
+    }
+
+    // the step proof has to be rejected with the wrong accumulator
+    let mut wrong = step_proof.clone();
+    wrong.prev_challenges[0].0[0] += &Fp::one();
+    assert!(ProverProof::verify::<DefaultFqSponge<dee::TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
+        &vec![(&step_verifier_index, &wrong)]).is_err());
+}
@@ -0,0 +1,103 @@
+/*****************************************************************************************************************
+
+This source file tests the SRS derivation: the bases are pinned by the test vectors of the field elements
+hashed from the indexes and mapped to the curve, the parallel and the streamed derivations agree with
+SRS::write, and verify_derivation accepts the honestly derived SRS files only
+
+*****************************************************************************************************************/
+
+use algebra::{tweedle, pasta, Field, PrimeField, Zero, One};
+use commitment_dlog::{srs::{SRS, base, misc_base, field_of_random_bytes}, commitment::CommitmentCurve, CommitmentField};
+use colored::Colorize;
+use std::time::Instant;
+use std::str::FromStr;
+use std::io::Cursor;
+use groupmap::GroupMap;
+
+// the field elements, as the decimal strings, hashed from the indexes of the bases and of the miscellaneous bases
+const VECTORS: [(bool, usize, &str); 4] =
+[
+    (false, 0, "8075097129111732113081222347400295842655239317049664062858624697736121129"),
+    (false, 1, "88183975327861247941609256729256966797409160178728773398917305842886283323"),
+    (false, (1 << 20) - 1, "160578215605540705716029477013477900525316982943169561611788493548397042029"),
+    (true, 0, "75618827056234847177843871670491083232536414597832767907671613988110599211"),
+];
+
+fn check<G: CommitmentCurve>()
+where G::BaseField : PrimeField + FromStr, G::ScalarField : CommitmentField
+{
+    let m = G::Map::setup();
+
+    // the test vectors
+    for (misc, i, t) in VECTORS.iter()
+    {
+        let (x, y) = m.to_group(G::BaseField::from_str(t).map_err(|_| ()).unwrap());
+        assert_eq!(if *misc {misc_base::<G>(&m, *i)} else {base::<G>(&m, *i)}, G::of_coordinates(x, y));
+    }
+
+    // the bits, in the byte order and from the least significant one of every byte, are read as the
+    // big-endian 248-bit integer
+    let two = G::BaseField::from(2u64);
+    let mut bytes = [0u8; 64];
+    assert_eq!(field_of_random_bytes::<G::BaseField>(&bytes), G::BaseField::zero());
+    bytes[30] = 0x80;
+    assert_eq!(field_of_random_bytes::<G::BaseField>(&bytes), G::BaseField::one());
+    bytes[30] = 0;
+    bytes[0] = 1;
+    assert_eq!(field_of_random_bytes::<G::BaseField>(&bytes), two.pow([247]));
+    bytes[0] = 0x80;
+    bytes[1] = 1;
+    assert_eq!(field_of_random_bytes::<G::BaseField>(&bytes), two.pow([240]) + &two.pow([239]));
+    bytes[31] = 0xff;
+    assert_eq!(field_of_random_bytes::<G::BaseField>(&bytes), two.pow([240]) + &two.pow([239]));
+
+    // the parallel, the streamed and the sequential derivations
+    let depth = (1 << 14) + 3;
+    let start = Instant::now();
+    let srs = SRS::<G>::create(depth);
+    println!("{}{:?}", "SRS creation time: ".yellow(), start.elapsed());
+    assert_eq!(srs.h, misc_base::<G>(&m, 0));
+    for i in [0, 1, 1 << 14, depth - 1].iter() {assert_eq!(srs.g()[*i], base::<G>(&m, *i))}
+
+    let mut written = vec![];
+    srs.write(&mut written).unwrap();
+    let mut streamed = Cursor::new(vec![]);
+    let start = Instant::now();
+    SRS::<G>::write_derivation(depth, &mut streamed).unwrap();
+    println!("{}{:?}", "SRS streaming time: ".yellow(), start.elapsed());
+    assert_eq!(written, streamed.into_inner());
+
+    let start = Instant::now();
+    assert!(SRS::<G>::verify_derivation(&written[..]).unwrap());
+    println!("{}{:?}", "SRS derivation verification time: ".green(), start.elapsed());
+
+    // the tampered files are rejected
+    let mut g = srs.g().to_vec();
+    g.swap(1, (1 << 14) + 1);
+    let tampered = SRS::<G>::of_bases(g, srs.h);
+    let mut bytes = vec![];
+    tampered.write(&mut bytes).unwrap();
+    assert!(!SRS::<G>::verify_derivation(&bytes[..]).unwrap());
+
+    let mut tampered = srs.clone();
+    tampered.h = srs.g()[0];
+    let mut bytes = vec![];
+    tampered.write(&mut bytes).unwrap();
+    assert!(!SRS::<G>::verify_derivation(&bytes[..]).unwrap());
+
+    assert!(SRS::<G>::verify_derivation(&written[0..written.len() - 1]).is_err());
+}
+
+#[test]
+fn srs_derivation_tweedle()
+{
+    check::<tweedle::dee::Affine>();
+    check::<tweedle::dum::Affine>();
+}
+
+#[test]
+fn srs_derivation_pasta()
+{
+    check::<pasta::vesta::Affine>();
+    check::<pasta::pallas::Affine>();
+}
@@ -0,0 +1,111 @@
+/*****************************************************************************************************************
+
+This source file tests the versioned SRS file format: the round trip of the SRS, the loading of the SRS
+prefix and the in-memory truncation, and the rejection of the files with the wrong magic, version, curve
+or length, of the corrupted, truncated and tampered files
+
+*****************************************************************************************************************/
+
+use algebra::tweedle::{dee::Affine, dum::Affine as DumAffine};
+use commitment_dlog::srs::{SRS, VERSION, curve_id};
+use ff_fft::{EvaluationDomain, Radix2EvaluationDomain as D};
+use std::io::ErrorKind;
+
+// offsets of the header fields
+const VERSION_OFFSET: usize = 8;
+const ID_OFFSET: usize = 12;
+const LENGTH_OFFSET: usize = 44;
+const HASH_OFFSET: usize = 52;
+const BODY_OFFSET: usize = 116;
+
+#[test]
+fn srs_file()
+{
+    let size = 1 << 9;
+    let srs = SRS::<Affine>::create(size);
+    srs.add_lagrange_basis(D::new(1 << 6).unwrap());
+    srs.add_lagrange_basis(D::new(1 << 8).unwrap());
+
+    let mut file = vec![];
+    srs.write(&mut file).unwrap();
+    assert_eq!(&file[0..VERSION_OFFSET], b"dlog-srs");
+    assert_eq!(file[VERSION_OFFSET..ID_OFFSET], VERSION.to_le_bytes());
+    assert_eq!(file[ID_OFFSET..LENGTH_OFFSET], curve_id::<Affine>()[..]);
+    assert_eq!(file[LENGTH_OFFSET..HASH_OFFSET], (size as u64).to_le_bytes());
+    assert_ne!(curve_id::<Affine>(), curve_id::<DumAffine>());
+
+    // the round trip
+    let read = SRS::<Affine>::read(&file[..]).unwrap();
+    assert_eq!((read.g(), read.h, read.endo_q, read.endo_r), (srs.g(), srs.h, srs.endo_q, srs.endo_r));
+
+    // the prefix loading and the in-memory prefix and truncation
+    let k = 100;
+    let prefix = SRS::<Affine>::read_prefix(&file[..], k).unwrap();
+    assert_eq!((prefix.g(), prefix.h, prefix.endo_r), (&srs.g()[0..k], srs.h, srs.endo_r));
+    assert_eq!(SRS::<Affine>::read_prefix(&file[..], size).unwrap().g(), srs.g());
+    assert_eq!(SRS::<Affine>::read_prefix(&file[..], size + 1).err().unwrap().kind(), ErrorKind::InvalidInput);
+
+    let p = srs.prefix(k);
+    assert_eq!(p.g(), prefix.g());
+    assert!(p.lagrange_bases.contains_key(&(1 << 6)) && !p.lagrange_bases.contains_key(&(1 << 8)));
+    let mut truncated = srs.clone();
+    truncated.truncate(1 << 8);
+    assert_eq!(truncated.g(), &srs.g()[0..1 << 8]);
+    assert_eq!(truncated.lagrange_bases.len(), 2);
+    truncated.truncate(k);
+    assert_eq!(truncated.g(), p.g());
+    assert_eq!(truncated.lagrange_bases.len(), 1);
+
+    // the wrong magic, version, curve and length
+    let rejected = |bytes: &Vec<u8>| SRS::<Affine>::read(&bytes[..]).is_err() && SRS::<Affine>::read_prefix(&bytes[..], 1).is_err();
+    let mut bytes = file.clone();
+    bytes[0] = b'D';
+    assert!(rejected(&bytes));
+    let mut bytes = file.clone();
+    bytes[VERSION_OFFSET] += 1;
+    assert!(rejected(&bytes));
+    assert!(SRS::<DumAffine>::read(&file[..]).is_err());
+    let mut bytes = file.clone();
+    bytes[LENGTH_OFFSET..HASH_OFFSET].copy_from_slice(&((1u64 << 32) + 1).to_le_bytes());
+    assert!(rejected(&bytes));
+    let mut bytes = file.clone();
+    bytes[LENGTH_OFFSET..HASH_OFFSET].copy_from_slice(&(size as u64 + 1).to_le_bytes());
+    assert!(rejected(&bytes));
+
+    // the corrupted, truncated and tampered files
+    let mut bytes = file.clone();
+    bytes[HASH_OFFSET] ^= 1;
+    assert!(rejected(&bytes));
+    let mut bytes = file.clone();
+    let last = bytes.len() - 10;
+    bytes[last] ^= 1;
+    assert!(SRS::<Affine>::read(&bytes[..]).is_err());
+    assert!(SRS::<Affine>::read_prefix(&bytes[..], k).is_err());
+    let mut bytes = file.clone();
+    bytes[BODY_OFFSET + 100] ^= 1;
+    assert!(rejected(&bytes));
+    assert!(rejected(&file[0..file.len() - 1].to_vec()));
+
+    let mut g = srs.g().to_vec();
+    g.swap(0, 1);
+    let tampered = SRS::<Affine>::of_bases(g, srs.h);
+    let mut bytes = vec![];
+    tampered.write(&mut bytes).unwrap();
+    assert_eq!(SRS::<Affine>::read(&bytes[..]).unwrap().g(), tampered.g());
+    bytes[HASH_OFFSET..BODY_OFFSET].copy_from_slice(&file[HASH_OFFSET..BODY_OFFSET]);
+    assert_eq!(SRS::<Affine>::read(&bytes[..]).err().unwrap().kind(), ErrorKind::InvalidData);
+
+    let mut tampered = srs.clone();
+    tampered.endo_q = srs.endo_q * &srs.endo_q;
+    let mut bytes = vec![];
+    tampered.write(&mut bytes).unwrap();
+    assert!(rejected(&bytes));
+
+    // the conjugate endomorphism acts on the curve as well, but is not the one of the verifier
+    let mut tampered = srs.clone();
+    tampered.endo_q = srs.endo_q * &srs.endo_q;
+    tampered.endo_r = srs.endo_r * &srs.endo_r;
+    let mut bytes = vec![];
+    tampered.write(&mut bytes).unwrap();
+    assert!(rejected(&bytes));
+}
@@ -23,15 +23,15 @@ This source file tests constraints for the following computatios:
 
 use plonk_circuits::{wires::GateWires, gate::CircuitGate, constraints::ConstraintSystem};
 use oracle::{poseidon::{ArithmeticSponge, ArithmeticSpongeParams, Sponge, PlonkSpongeConstants as SC}, sponge::{DefaultFqSponge, DefaultFrSponge}};
-use commitment_dlog::{srs::SRS, commitment::{CommitmentCurve, ceil_log2, b_poly_coefficients}};
+use commitment_dlog::{srs::SRS, commitment::{ceil_log2, b_poly_coefficients}};
 use algebra::{Field, tweedle::{dee::{Affine, TweedledeeParameters}, fp::Fp}, One, Zero, UniformRand};
-use plonk_protocol_dlog::{prover::{ProverProof}, index::{Index, SRSSpec}};
-use ff_fft::{Evaluations, DensePolynomial, Radix2EvaluationDomain as D};
+use plonk_protocol_dlog::{prover::ProverProof, index::Index};
+use ff_fft::DensePolynomial;
 use std::{io, io::Write};
 use oracle::poseidon::*;
-use groupmap::GroupMap;
 use std::time::Instant;
 use colored::Colorize;
+use std::sync::Arc;
 use rand_core::OsRng;
 
 const MAX_SIZE: usize = 128; // max size of poly chunks
@@ -159,7 +159,7 @@ fn turbo_plonk()
         ConstraintSystem::<Fp>::create(gates, oracle::tweedle::fp::params() as ArithmeticSpongeParams<Fp>, PUBLIC).unwrap(),
         oracle::tweedle::fq::params(),
         endo_q,
-        SRSSpec::Use(&srs)
+        Arc::new(srs)
     );
 
     positive(&index);
@@ -177,16 +177,6 @@ where <Fp as std::str::FromStr>::Err : std::fmt::Debug
     let z = Fp::zero();
     let mut batch = Vec::new();
     let points = sample_points();
-    let group_map = <Affine as CommitmentCurve>::Map::setup();
-
-    let lgr_comms : Vec<_> = (0..PUBLIC).map(|i| {
-        let mut v = vec![Fp::zero(); i + 1];
-        v[i] = Fp::one();
-
-        let p = Evaluations::<Fp, D<Fp>>::from_vec_and_domain(
-            v, index.cs.domain.d1).interpolate();
-        index.srs.get_ref().commit_non_hiding(&p, None)
-    }).collect();
 
     println!("{}", "Prover 100 zk-proofs computation".green());
     let mut start = Instant::now();
@@ -353,18 +343,18 @@ where <Fp as std::str::FromStr>::Err : std::fmt::Debug
         assert_eq!(index.cs.verify(&witness), true);
 
         let prev = {
-            let k = ceil_log2(index.srs.get_ref().g.len());
+            let k = ceil_log2(index.pcs.g().len());
             let chals : Vec<_> = (0..k).map(|_| Fp::rand(rng)).collect();
             let comm = {
                 let b = DensePolynomial::from_coefficients_vec(b_poly_coefficients(&chals));
-                index.srs.get_ref().commit_non_hiding(&b, None)
+                index.pcs.commit_non_hiding(&b, None)
             };
             ( chals, comm )
         };
 
         // add the proof to the batch
         batch.push(ProverProof::create::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
-            &group_map, &witness, &index, vec![prev]).unwrap());
+            &witness, &index, vec![prev]).unwrap());
 
         print!("{:?}\r", test);
         io::stdout().flush().unwrap();
@@ -373,7 +363,7 @@ where <Fp as std::str::FromStr>::Err : std::fmt::Debug
 
     // verify one proof serially
     match ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
-        &group_map, &vec![ (&verifier_index, &lgr_comms, &batch[0]) ])
+        &vec![ (&verifier_index, &batch[0]) ])
     {
         Err(error) => {panic!("Failure verifying the prover's proof: {}", error)},
         Ok(_) => {}
@@ -382,9 +372,9 @@ where <Fp as std::str::FromStr>::Err : std::fmt::Debug
     // verify the proofs in batch
     println!("{}", "Verifier zk-proofs verification".green());
     start = Instant::now();
-    let batch : Vec<_> = batch.iter().map(|p| (&verifier_index, &lgr_comms, p)).collect();
+    let batch : Vec<_> = batch.iter().map(|p| (&verifier_index, p)).collect();
     match ProverProof::verify::<DefaultFqSponge<TweedledeeParameters, SC>, DefaultFrSponge<Fp, SC>>(
-        &group_map, &batch)
+        &batch)
     {
         Err(error) => {panic!("Failure verifying the prover's proofs in batch: {}", error)},
         Ok(_) => {println!("{}{:?}", "Execution time: ".yellow(), start.elapsed());}
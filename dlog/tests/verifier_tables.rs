@@ -0,0 +1,161 @@
+/*********************************************************************************************************
+
+This source file benchmarks the Plonk verifier with the precomputed fixed-base tables of the index
+polynomial commitments against the plain verifier: a server verifying the proofs for one Poseidon
+circuit one at a time, the segmented index commitments combined either by the variable-base MSM or
+from the tables. Both verifiers have to agree on the valid and on the tampered proofs, and the tables
+not of the index commitments have to be ignored by the verifier
+
+**********************************************************************************************************/
+
+use oracle::{poseidon::*, sponge::{DefaultFqSponge, DefaultFrSponge}};
+use commitment_dlog::{srs::{SRS, endos}, commitment::{ceil_log2, b_poly_coefficients}};
+use plonk_circuits::{wires::GateWires, gate::CircuitGate, constraints::ConstraintSystem};
+use algebra::{tweedle::{dum::{Affine as Other}, dee::{Affine, TweedledeeParameters}, fp::Fp}, One, UniformRand};
+use plonk_protocol_dlog::{prover::ProverProof, index::{Index, VerifierIndex}};
+use ff_fft::DensePolynomial;
+use std::time::{Instant, Duration};
+use colored::Colorize;
+use std::sync::Arc;
+use rand_core::OsRng;
+
+const PERIOD: usize = PlonkSpongeConstants::ROUNDS_FULL + 1;
+const MAX_SIZE: usize = 512; // max size of poly chunks
+const NUM_POS: usize = 16; // number of Poseidon hashes in the circuit
+const N: usize = PERIOD * NUM_POS; // Plonk domain size
+const M: usize = PERIOD * (NUM_POS-1);
+const PROOFS: usize = 20; // number of proofs to verify
+const WINDOW: usize = 8; // fixed-base table window bit size
+
+type FqSponge = DefaultFqSponge<TweedledeeParameters, PlonkSpongeConstants>;
+type FrSponge = DefaultFrSponge<Fp, PlonkSpongeConstants>;
+
+#[test]
+fn verifier_tables()
+{
+    let c = &oracle::tweedle::fp::params().round_constants;
+
+    // custom constraints for Poseidon hash function permutation
+    let mut i = 0;
+    let mut gates: Vec<CircuitGate::<Fp>> = Vec::with_capacity(N);
+    for _ in 0..NUM_POS-1
+    {
+        for j in 0..PlonkSpongeConstants::ROUNDS_FULL
+        {
+            gates.push(CircuitGate::<Fp>::create_poseidon(GateWires::wires((i, (i+PERIOD)%M), (i+N, N+((i+PERIOD)%M)), (i+2*N, 2*N+((i+PERIOD)%M))), [c[j+1][0],c[j+1][1],c[j+1][2]]));
+            i+=1;
+        }
+        gates.push(CircuitGate::<Fp>::zero(GateWires::wires((i, (i+PERIOD)%M), (i+N, N+((i+PERIOD)%M)), (i+2*N, 2*N+((i+PERIOD)%M)))));
+        i+=1;
+    }
+    for j in 0..PlonkSpongeConstants::ROUNDS_FULL-2
+    {
+        gates.push(CircuitGate::<Fp>::create_poseidon(GateWires::wires((i, i), (i+N, N+(i)), (i+2*N, 2*N+(i))), [c[j+1][0],c[j+1][1],c[j+1][2]]));
+        i+=1;
+    }
+    for _ in 0..3
+    {
+        gates.push(CircuitGate::<Fp>::zero(GateWires::wires((i, i), (i+N, N+(i)), (i+2*N, 2*N+(i)))));
+        i+=1;
+    }
+
+    let (endo_q, _endo_r) = endos::<Other>();
+    let index = Index::<Affine>::create
+    (
+        ConstraintSystem::<Fp>::create(gates, oracle::tweedle::fp::params(), 0).unwrap(),
+        oracle::tweedle::fq::params(),
+        endo_q,
+        Arc::new(SRS::create(MAX_SIZE))
+    );
+
+    let rng = &mut OsRng;
+    let params = oracle::tweedle::fp::params();
+    let mut sponge = ArithmeticSponge::<Fp, PlonkSpongeConstants>::new();
+
+    println!("{}{:?}", "Circuit size: ".yellow(), N);
+    println!("{}{:?}", "Polycommitment chunk size: ".yellow(), MAX_SIZE);
+    println!("{}{:?}", "Number of proofs: ".yellow(), PROOFS);
+
+    let batch = (0..PROOFS).map
+    (
+        |_|
+        {
+            let (mut l, mut r, mut o) = (Vec::with_capacity(N), Vec::with_capacity(N), Vec::with_capacity(N));
+            let (x, y, z) = (Fp::rand(rng), Fp::rand(rng), Fp::rand(rng));
+            for k in 0..NUM_POS
+            {
+                sponge.state = vec![x, y, z];
+                l.push(sponge.state[0]);
+                r.push(sponge.state[1]);
+                o.push(sponge.state[2]);
+                let rounds = if k < NUM_POS-1 {PlonkSpongeConstants::ROUNDS_FULL} else {PlonkSpongeConstants::ROUNDS_FULL-2};
+                for j in 0..rounds
+                {
+                    sponge.full_round(j, &params);
+                    l.push(sponge.state[0]);
+                    r.push(sponge.state[1]);
+                    o.push(sponge.state[2]);
+                }
+            }
+            for _ in 0..2 {l.push(Fp::rand(rng)); r.push(Fp::rand(rng)); o.push(Fp::rand(rng))}
+
+            let mut witness = l;
+            witness.append(&mut r);
+            witness.append(&mut o);
+            assert_eq!(index.cs.verify(&witness), true);
+
+            let prev = {
+                let k = ceil_log2(index.pcs.g().len());
+                let chals : Vec<_> = (0..k).map(|_| Fp::rand(rng)).collect();
+                let b = DensePolynomial::from_coefficients_vec(b_poly_coefficients(&chals));
+                (chals, index.pcs.commit_non_hiding(&b, None))
+            };
+            ProverProof::create::<FqSponge, FrSponge>(&witness, &index, vec![prev]).unwrap()
+        }
+    ).collect::<Vec<_>>();
+
+    let plain = index.verifier_index();
+    let mut tabled = index.verifier_index();
+    let start = Instant::now();
+    tabled.precompute_tables(WINDOW);
+    println!("{}{:?}", "Table precomputation time: ".yellow(), start.elapsed());
+    assert!(plain.linearization_comm().iter().any(|c| c.unshifted.len() > 1));
+
+    // verify the proofs one at a time, as they come to the server
+    let verify = |index: &VerifierIndex<Affine>, proof: &ProverProof<Affine>| -> (bool, Duration)
+    {
+        let start = Instant::now();
+        let res = ProverProof::verify::<FqSponge, FrSponge>(&vec![(index, proof)]).is_ok();
+        (res, start.elapsed())
+    };
+    let (mut plain_time, mut tabled_time) = (Duration::default(), Duration::default());
+    for proof in batch.iter()
+    {
+        let (p, t) = (verify(&plain, proof), verify(&tabled, proof));
+        assert!(p.0 && t.0);
+        plain_time += p.1;
+        tabled_time += t.1;
+    }
+    println!("{}{:?}", "Per-proof verification time without tables: ".green(), plain_time / PROOFS as u32);
+    println!("{}{:?}", "Per-proof verification time with tables: ".green(), tabled_time / PROOFS as u32);
+
+    // the tampered proofs are rejected by both verifiers
+    let mut proof = batch[0].clone();
+    proof.commitments.z_comm = proof.commitments.l_comm.clone();
+    assert!(!verify(&plain, &proof).0 && !verify(&tabled, &proof).0);
+    let mut proof = batch[1].clone();
+    proof.evals[0].f[0] += &Fp::one();
+    assert!(!verify(&plain, &proof).0 && !verify(&tabled, &proof).0);
+
+    // the stale tables are not used, the verifier combining the index commitments
+    let mut stale = index.verifier_index();
+    stale.tables = tabled.tables.clone().map(|mut t| {t.pop(); t});
+    assert!(verify(&stale, &batch[0]).0);
+    let mut stale = index.verifier_index();
+    stale.tables = tabled.tables.clone().map(|mut t| {t.swap(0, 1); t});
+    assert!(verify(&stale, &batch[0]).0);
+    let mut stale = index.verifier_index();
+    stale.tables = tabled.tables.clone();
+    stale.sigma_comm[2] = stale.sigma_comm[0].clone();
+    assert!(!verify(&stale, &batch[0]).0);
+}
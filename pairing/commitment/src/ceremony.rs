@@ -0,0 +1,315 @@
+/*****************************************************************************************************************
+
+This source file implements the multi-party URS generation ceremony.
+
+The ceremony starts from the trivial URS with the secret x = 1, which is computed from the depth and
+the degrees of the URS and needs no trust. Every participant updates the URS with the fresh secret
+update scalar (URS::update_with), so that the final URS secret is the product of all the update
+scalars and is unknown as long as at least one participant has discarded its update scalar.
+
+Every update is recorded in the transcript as the contribution
+
+    gp1 = g^(x * y), hx = h^(x * y), prf = g^y, pok = (R = g^k, s = k + c * y)
+
+where x is the previous URS secret, y is the update scalar and pok is the Schnorr proof of knowledge
+of y with the challenge c hashed from the transcript digest and the contribution, so that the
+contribution can be neither copied nor computed without the knowledge of the update scalar.
+
+The transcript verification checks that the contribution points are in the prime order subgroups,
+the pairing checks being meaningless otherwise, and the chain of contributions with the pairings
+
+    e(prf, hx_prev) = e(g, hx), e(gp1, h) = e(g, hx)
+
+and the final URS against the last contribution and with URS::check.
+
+*****************************************************************************************************************/
+
+use algebra::{ToBytes, FromBytes, AffineCurve, ProjectiveCurve, PairingEngine, UniformRand, Zero};
+use blake2::{Blake2b, Digest};
+use std::collections::HashMap;
+use std::io::{Read, Write, Result as IoResult, Error as IoError, ErrorKind};
+use std::fmt;
+use rand_core::RngCore;
+pub use super::urs::URS;
+use super::serialization::{in_subgroup, write_point, read_point, write_scalar, read_scalar};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CeremonyError
+{
+    Subgroup(usize),            // contribution point not in the prime order subgroup
+    ProofOfKnowledge(usize),    // invalid proof of knowledge of the update scalar of the contribution
+    UpdateConsistency(usize),   // contribution inconsistent with the previous one
+    FinalMismatch,              // URS does not match the transcript
+    URSCheck,                   // URS consistency check failure
+}
+
+impl fmt::Display for CeremonyError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            CeremonyError::Subgroup(i) => write!(f, "point of contribution {} not in the prime order subgroup", i),
+            CeremonyError::ProofOfKnowledge(i) => write!(f, "invalid proof of knowledge of contribution {}", i),
+            CeremonyError::UpdateConsistency(i) => write!(f, "inconsistent update of contribution {}", i),
+            CeremonyError::FinalMismatch => write!(f, "URS does not match the transcript"),
+            CeremonyError::URSCheck => write!(f, "URS consistency check failure"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Contribution<E: PairingEngine>
+{
+    pub gp1: E::G1Affine,       // updated URS gp[1]
+    pub hx: E::G2Affine,        // updated URS hx
+    pub prf: E::G1Affine,       // g^y for the update scalar y
+    pub pok_r: E::G1Affine,     // proof of knowledge of y commitment g^k
+    pub pok_s: E::Fr,           // proof of knowledge of y response k + c * y
+}
+
+impl<E: PairingEngine> Contribution<E>
+{
+    // This function writes the contribution with compressed points
+    pub fn write<W : Write>(&self, mut writer : W) -> IoResult<()>
+    {
+        write_point(&mut writer, &self.gp1)?;
+        write_point(&mut writer, &self.hx)?;
+        write_point(&mut writer, &self.prf)?;
+        write_point(&mut writer, &self.pok_r)?;
+        write_scalar(&mut writer, &self.pok_s)
+    }
+
+    // This function reads the contribution rejecting the points not in the prime order subgroups
+    pub fn read<R : Read>(mut reader : R) -> IoResult<Self>
+    {
+        let gp1 = read_point(&mut reader)?;
+        let hx = read_point(&mut reader)?;
+        let prf = read_point(&mut reader)?;
+        let pok_r = read_point(&mut reader)?;
+        let pok_s = read_scalar(&mut reader)?;
+        Ok(Contribution {gp1, hx, prf, pok_r, pok_s})
+    }
+
+    // This function checks that the points of the contribution are in the prime order subgroups
+    fn in_subgroups(&self) -> bool
+    {
+        in_subgroup(&self.gp1) && in_subgroup(&self.hx) && in_subgroup(&self.prf) && in_subgroup(&self.pok_r)
+    }
+
+    // This function computes the proof of knowledge challenge
+    //     digest: transcript digest before the contribution
+    fn challenge(&self, digest: &[u8]) -> E::Fr
+    {
+        let mut h = Blake2b::new();
+        h.input(digest);
+        h.input(&bytes(|w| self.gp1.write(w)));
+        h.input(&bytes(|w| self.hx.write(w)));
+        h.input(&bytes(|w| self.prf.write(w)));
+        h.input(&bytes(|w| self.pok_r.write(w)));
+        let base: E::Fr = (256 as u64).into();
+        h.result().iter().fold(E::Fr::zero(), |acc, b| acc * &base + &(*b as u64).into())
+    }
+}
+
+// maximal URS depth and number of the contributions of the transcript read from the file, bounding the
+// allocations of the corrupt or hostile transcripts
+pub const MAX_DEPTH: usize = 1 << 28;
+pub const MAX_CONTRIBUTIONS: usize = 1 << 16;
+
+pub struct Transcript<E: PairingEngine>
+{
+    pub depth: usize,                           // URS depth
+    pub degrees: Vec<usize>,                    // URS commitment degrees
+    pub contributions: Vec<Contribution<E>>,    // sequence of the URS updates
+}
+
+fn bytes<F: FnOnce(&mut Vec<u8>) -> IoResult<()>>(f: F) -> Vec<u8>
+{
+    let mut v = Vec::new();
+    f(&mut v).unwrap();
+    v
+}
+
+impl<E: PairingEngine> Transcript<E>
+{
+    pub fn new(depth: usize, degrees: Vec<usize>) -> Self
+    {
+        Transcript {depth, degrees, contributions: Vec::new()}
+    }
+
+    // This function computes the trivial URS the ceremony starts from
+    pub fn initial(&self) -> URS<E>
+    {
+        let g = E::G1Affine::prime_subgroup_generator();
+        let h = E::G2Affine::prime_subgroup_generator();
+        let mut hn: HashMap<usize, E::G2Affine> = HashMap::new();
+        for i in self.degrees.iter() {hn.insert(self.depth - *i, h);}
+        URS
+        {
+            depth: self.depth,
+            gp: vec![g; self.depth],
+            hn,
+            hx: h,
+            prf: g,
+        }
+    }
+
+    // This function computes the transcript digest
+    //     n: number of the contributions to digest
+    pub fn digest(&self, n: usize) -> Vec<u8>
+    {
+        let mut h = Blake2b::new();
+        h.input(&bytes(|w| (self.depth as u64).write(w)));
+        for d in self.degrees.iter() {h.input(&bytes(|w| (*d as u64).write(w)))}
+        let mut digest = h.result().to_vec();
+        for c in self.contributions[0..n].iter()
+        {
+            let mut h = Blake2b::new();
+            h.input(&digest);
+            h.input(&bytes(|w| c.write(w)));
+            digest = h.result().to_vec();
+        }
+        digest
+    }
+
+    // This function updates the URS, which has to be the result of the transcript,
+    // and appends the contribution to the transcript
+    //     urs: URS to update
+    //     rng: randomness source context
+    pub fn contribute(&mut self, urs: &mut URS<E>, rng: &mut dyn RngCore)
+    {
+        assert_eq!(urs.hx, self.contributions.last().map_or(E::G2Affine::prime_subgroup_generator(), |c| c.hx));
+
+        let y = E::Fr::rand(rng);
+        urs.update_with(y);
+
+        let k = E::Fr::rand(rng);
+        let mut c = Contribution
+        {
+            gp1: urs.gp[1],
+            hx: urs.hx,
+            prf: urs.prf,
+            pok_r: E::G1Affine::prime_subgroup_generator().mul(k).into_affine(),
+            pok_s: E::Fr::zero(),
+        };
+        c.pok_s = k + &(c.challenge(&self.digest(self.contributions.len())) * &y);
+        self.contributions.push(c);
+    }
+
+    // This function verifies the chain of the contributions and the final URS
+    //     urs: final URS of the ceremony
+    //     rng: randomness source context
+    //     RETURN: verification status
+    pub fn verify_transcript(&self, urs: &URS<E>, rng: &mut dyn RngCore) -> Result<(), CeremonyError>
+    {
+        let initial = self.initial();
+        let g = E::G1Affine::prime_subgroup_generator();
+        let h = E::G2Affine::prime_subgroup_generator();
+
+        let mut hx = initial.hx;
+        for (i, c) in self.contributions.iter().enumerate()
+        {
+            // the contributions built in memory have not been checked by Contribution::read
+            if !c.in_subgroups() {return Err(CeremonyError::Subgroup(i))}
+
+            // proof of knowledge of the update scalar
+            let chal = c.challenge(&self.digest(i));
+            if c.prf.is_zero() || g.mul(c.pok_s) != c.pok_r.into_projective() + &c.prf.mul(chal)
+            {
+                return Err(CeremonyError::ProofOfKnowledge(i))
+            }
+
+            // update consistency
+            let ghx = E::pairing(g, c.hx);
+            if E::pairing(c.prf, hx) != ghx || E::pairing(c.gp1, h) != ghx
+            {
+                return Err(CeremonyError::UpdateConsistency(i))
+            }
+            if i + 1 < self.contributions.len() {hx = c.hx}
+        }
+
+        // final URS
+        let mut keys = urs.hn.keys().cloned().collect::<Vec<_>>();
+        let mut expected = initial.hn.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        expected.sort();
+        if urs.depth != self.depth || urs.gp.len() != self.depth || keys != expected
+        {
+            return Err(CeremonyError::FinalMismatch)
+        }
+        match self.contributions.last()
+        {
+            None =>
+            {
+                if urs.gp != initial.gp || urs.hn != initial.hn || urs.hx != initial.hx || urs.prf != initial.prf
+                {
+                    return Err(CeremonyError::FinalMismatch)
+                }
+                Ok(())
+            }
+            Some(c) =>
+            {
+                if urs.gp[1] != c.gp1 || urs.hx != c.hx || urs.prf != c.prf {return Err(CeremonyError::FinalMismatch)}
+                if urs.clone().check(hx, rng) {Ok(())} else {Err(CeremonyError::URSCheck)}
+            }
+        }
+    }
+
+    pub fn write<W : Write>(&self, mut writer : W) -> IoResult<()>
+    {
+        u64::write(&(self.depth as u64), &mut writer)?;
+        u64::write(&(self.degrees.len() as u64), &mut writer)?;
+        for d in self.degrees.iter() {u64::write(&(*d as u64), &mut writer)?;}
+        u64::write(&(self.contributions.len() as u64), &mut writer)?;
+        for c in self.contributions.iter() {c.write(&mut writer)?;}
+        Ok(())
+    }
+
+    // This function reads the transcript written by Transcript::write, rejecting the depth over MAX_DEPTH,
+    // the degrees exceeding the depth and more than MAX_CONTRIBUTIONS contributions before allocating
+    pub fn read<R : Read>(mut reader : R) -> IoResult<Self>
+    {
+        let invalid = |msg: &str| IoError::new(ErrorKind::InvalidData, msg);
+
+        let depth = u64::read(&mut reader)?;
+        if depth > MAX_DEPTH as u64 {return Err(invalid("URS depth out of range"))}
+        let depth = depth as usize;
+
+        let n = u64::read(&mut reader)?;
+        if n > depth as u64 {return Err(invalid("more URS degrees than the depth"))}
+        let mut degrees = Vec::with_capacity(n as usize);
+        for _ in 0..n
+        {
+            let d = u64::read(&mut reader)?;
+            if d > depth as u64 {return Err(invalid("URS degree out of range"))}
+            degrees.push(d as usize);
+        }
+
+        let n = u64::read(&mut reader)?;
+        if n > MAX_CONTRIBUTIONS as u64 {return Err(invalid("too many transcript contributions"))}
+        let mut contributions = Vec::with_capacity(n as usize);
+        for _ in 0..n {contributions.push(Contribution::read(&mut reader)?);}
+        Ok(Transcript {depth, degrees, contributions})
+    }
+}
+
+// This function simulates the ceremony with the local participants
+//     depth: URS depth
+//     degrees: URS commitment degrees
+//     participants: number of the participants
+//     rng: randomness source context
+//     RETURN: ceremony transcript and the final URS
+pub fn simulate<E: PairingEngine>
+(
+    depth: usize,
+    degrees: Vec<usize>,
+    participants: usize,
+    rng: &mut dyn RngCore
+) -> (Transcript<E>, URS<E>)
+{
+    let mut transcript = Transcript::<E>::new(depth, degrees);
+    let mut urs = transcript.initial();
+    for _ in 0..participants {transcript.contribute(&mut urs, rng)}
+    (transcript, urs)
+}
@@ -14,14 +14,21 @@ knowledge protocol:
      d. The given masking base field element
      e. Commitment opening proof
 
+The URS also implements the PolynomialCommitment interface shared with the dlog commitment scheme. There the
+polynomials are committed in segments of the URS depth, the degree bound being enforced on the last segment,
+and the batch is opened at the evaluation points with the multi-point opening proof. The commitments are not hiding, the
+commitment randomness being zero.
+
 *****************************************************************************************************************/
 
-use oracle::rndoracle::ProofError;
+use oracle::{FqSponge, sponge::ScalarChallenge, rndoracle::ProofError};
+use poly_commitment::commitment::{PolyComm, PolynomialCommitment, OpeningBatch};
 use algebra::{AffineCurve, ProjectiveCurve, Field, PrimeField, PairingEngine, UniformRand, VariableBaseMSM, One, Zero};
 use std::collections::HashMap;
 use oracle::utils::PolyUtils;
 use ff_fft::DensePolynomial;
 pub use super::urs::URS;
+use crate::multipoint::MultiPointProof;
 use rand_core::RngCore;
 
 impl<E: PairingEngine> URS<E>
@@ -223,6 +230,141 @@ impl<E: PairingEngine> URS<E>
     }
 }
 
+impl<E: PairingEngine> URS<E>
+{
+    // This function lists the segments of the polynomial of the URS depth, none for the zero polynomial
+    fn segments(&self, plnm: &DensePolynomial<E::Fr>) -> Vec<DensePolynomial<E::Fr>>
+    {
+        if plnm.is_zero() {return Vec::new()}
+        plnm.coeffs.chunks(self.depth).map(|c| DensePolynomial::<E::Fr>::from_coefficients_slice(c)).collect()
+    }
+}
+
+impl<E: PairingEngine> PolynomialCommitment<E::G1Affine> for URS<E>
+{
+    // multi-point opening proof of the segments at the evaluation points
+    type Proof = MultiPointProof<E>;
+
+    fn max_poly_size(&self) -> usize
+    {
+        self.depth
+    }
+
+    fn hiding(&self) -> bool {false}
+
+    fn challenge(&self, chal: &ScalarChallenge<E::Fr>) -> E::Fr
+    {
+        chal.0
+    }
+
+    fn commit(&self, plnm: &DensePolynomial<E::Fr>, max: Option<usize>, _rng: &mut dyn RngCore) -> (PolyComm<E::G1Affine>, PolyComm<E::Fr>)
+    {
+        let comm = PolynomialCommitment::commit_non_hiding(self, plnm, max);
+        let blinding = comm.map(|_| E::Fr::zero());
+        (comm, blinding)
+    }
+
+    fn commit_non_hiding(&self, plnm: &DensePolynomial<E::Fr>, max: Option<usize>) -> PolyComm<E::G1Affine>
+    {
+        let n = self.depth;
+        let p = plnm.coeffs.len();
+
+        // committing all the segments without shifting
+        let unshifted = self.segments(plnm).iter().map
+        (
+            |s| VariableBaseMSM::multi_scalar_mul
+            (
+                &self.gp[0..s.coeffs.len()],
+                &s.coeffs.iter().map(|s| s.into_repr()).collect::<Vec<_>>()
+            ).into_affine()
+        ).collect();
+
+        // committing only last segment shifted to the right edge of URS
+        let shifted = match max
+        {
+            None => None,
+            Some(max) =>
+            {
+                let start = max - (max % n);
+                if plnm.is_zero() || start >= p {Some(E::G1Affine::zero())}
+                else if max % n == 0 {None}
+                else
+                {
+                    Some(VariableBaseMSM::multi_scalar_mul
+                    (
+                        &self.gp[n - (max%n)..n - (max%n) + p - start],
+                        &plnm.coeffs[start..p].iter().map(|s| s.into_repr()).collect::<Vec<_>>()
+                    ).into_affine())
+                }
+            }
+        };
+
+        PolyComm::<E::G1Affine>{unshifted, shifted}
+    }
+
+    fn open<EFqSponge: Clone + FqSponge<E::Fq, E::G1Affine, E::Fr>>
+    (
+        &self,
+        plnms: Vec<(&DensePolynomial<E::Fr>, Option<usize>, PolyComm<E::Fr>)>,
+        elm: &Vec<E::Fr>,
+        polyscale: E::Fr,
+        _evalscale: E::Fr,
+        sponge: EFqSponge,
+        _rng: &mut dyn RngCore,
+    ) -> Result<Self::Proof, ProofError>
+    {
+        // the degree bounds are enforced by the verifier pairing the shifted commitments against the
+        // unshifted ones, so that only the unshifted segments are opened
+        let segments = plnms.iter().map(|(p, _, _)| self.segments(p)).flatten().collect::<Vec<_>>();
+        self.open_multipoint(segments.iter().map(|s| (s, elm.clone())).collect(), polyscale, sponge)
+    }
+
+    fn verify<EFqSponge: Clone + FqSponge<E::Fq, E::G1Affine, E::Fr>>
+    (
+        &self,
+        batch: &mut OpeningBatch<E::G1Affine, EFqSponge, Self::Proof>,
+        rng: &mut dyn RngCore,
+    ) -> bool
+    {
+        let n = self.depth;
+        let mut openings = Vec::new();
+
+        for (sponge, elm, polyscale, _, polys, proof) in batch.iter()
+        {
+            let mut segments = Vec::new();
+            for (comm, evals, max) in polys.iter()
+            {
+                if evals.len() != elm.len() || evals.iter().any(|e| e.len() != comm.unshifted.len()) {return false}
+
+                // the shifted commitment is checked against the segment containing the degree bound
+                let last = match (max, comm.shifted)
+                {
+                    (Some(m), Some(s)) =>
+                    {
+                        let last = m / n;
+                        if comm.unshifted.len() > last + 1 {return false}
+                        if comm.unshifted.len() <= last {if !s.is_zero() {return false} None}
+                        else {Some((last, (s, m % n)))}
+                    }
+                    (Some(m), None) => if m % n != 0 || comm.unshifted.len() > m / n {return false} else {None},
+                    _ => None
+                };
+                segments.extend(comm.unshifted.iter().enumerate().map
+                (
+                    |(i, c)|
+                    (
+                        *c,
+                        elm.iter().zip(evals.iter()).map(|(x, e)| (*x, e[i])).collect::<Vec<_>>(),
+                        match last {Some((l, s)) if l == i => Some(s), _ => None}
+                    )
+                ));
+            }
+            openings.push((sponge.clone(), *polyscale, segments, *proof));
+        }
+        self.verify_multipoint(&mut openings, rng)
+    }
+}
+
 pub trait Divide<F: Field>
 {
     fn divide(&self, elm: F) -> Self;
@@ -1,2 +1,5 @@
 pub mod urs;
 pub mod commitment;
+pub mod ceremony;
+pub mod multipoint;
+pub mod serialization;
@@ -0,0 +1,342 @@
+/*****************************************************************************************************************
+
+This source file implements the multi-point batched opening of the polynomial commitments against the URS
+instance, following Boneh, Drake, Fisch and Gabizon, "Efficient polynomial commitment schemes for multiple
+points and polynomials". Every polynomial of the batch is opened at its own set of points, the opening proof
+consisting of a single group element if all the sets consist of the same single point and of two otherwise.
+
+With T the union of the opening sets S_i, r_i the polynomial interpolating f_i over S_i and gamma the
+polynomial scaling factor, the prover commits to
+
+    h(X) = sum_i gamma^i * Z_{T\S_i}(X) * (f_i(X) - r_i(X)) / Z_T(X)
+
+and, for the challenge z sampled after the commitment to h, opens at z the polynomial
+
+    L(X) = sum_i gamma^i * Z_{T\S_i}(z) * (f_i(X) - r_i(z)) - Z_T(z) * h(X)
+
+that vanishes at z. The verifier computes the commitment to L from the commitments to f_i and h and checks
+the opening with one pairing equation together with the degree bounds of the shifted commitments. The
+equation is returned unevaluated as a PairingCheck, the batch of the proofs being checked with a single
+product of pairings of the randomly combined equations.
+
+*****************************************************************************************************************/
+
+use algebra::{AffineCurve, ProjectiveCurve, FftField, PrimeField, PairingEngine, UniformRand, VariableBaseMSM, One, Zero};
+use ff_fft::{DensePolynomial, DenseOrSparsePolynomial};
+use oracle::{FqSponge, rndoracle::ProofError};
+use std::collections::HashMap;
+use crate::commitment::Divide;
+use oracle::utils::PolyUtils;
+pub use super::urs::URS;
+use rand_core::RngCore;
+
+#[derive(Clone, Debug)]
+pub struct MultiPointProof<E: PairingEngine>
+{
+    pub w: E::G1Affine,                 // commitment to the quotient h, or the opening proof for the single point
+    pub w1: Option<E::G1Affine>,        // opening proof of the linearized polynomial L at the challenge point
+}
+
+// This function computes the polynomial vanishing on the points
+fn vanishing<F: FftField>(points: &[F]) -> DensePolynomial<F>
+{
+    points.iter().fold
+    (
+        DensePolynomial::from_coefficients_slice(&[F::one()]),
+        |acc, p| &acc * &DensePolynomial::from_coefficients_slice(&[-*p, F::one()])
+    )
+}
+
+// This function evaluates at elm the polynomial interpolating the values at the points
+fn interpolate<F: FftField>(values: &[(F, F)], elm: F) -> F
+{
+    values.iter().enumerate().map
+    (
+        |(i, (x, y))|
+        {
+            let (num, den) = values.iter().enumerate().filter(|(j, _)| *j != i).fold
+                ((F::one(), F::one()), |(n, d), (_, (xj, _))| (n * &(elm - xj), d * &(*x - xj)));
+            *y * &num * &den.inverse().unwrap()
+        }
+    ).fold(F::zero(), |x, y| x + &y)
+}
+
+// This function computes the polynomial interpolating the values at the points
+fn interpolation<F: FftField>(values: &[(F, F)]) -> DensePolynomial<F>
+{
+    values.iter().enumerate().fold
+    (
+        DensePolynomial::zero(),
+        |acc, (i, (x, y))|
+        {
+            let others = values.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, (xj, _))| *xj).collect::<Vec<_>>();
+            let den = others.iter().fold(F::one(), |d, xj| d * &(*x - xj));
+            &acc + &vanishing(&others).scale(*y * &den.inverse().unwrap())
+        }
+    )
+}
+
+// This function lists the distinct points of the opening sets
+fn union<'a, F: FftField>(sets: impl Iterator<Item = &'a F>) -> Vec<F>
+{
+    let mut points: Vec<F> = Vec::new();
+    for p in sets
+    {
+        if !points.contains(p) {points.push(*p)}
+    }
+    points
+}
+
+// This function samples the challenge point of the opening from the argument transcript
+fn challenge<E: PairingEngine, EFqSponge: FqSponge<E::Fq, E::G1Affine, E::Fr>>
+    (sponge: &mut EFqSponge, gamma: E::Fr, w: E::G1Affine) -> E::Fr
+{
+    sponge.absorb_fr(&[gamma]);
+    sponge.absorb_g(&[w]);
+    sponge.challenge()
+}
+
+impl<E: PairingEngine> URS<E>
+{
+    // This function opens the polynomial commitment batch at the sets of points
+    //     plnms: committed polynomials with the points to open them at, the points of a set being distinct
+    //     gamma: polynomial scaling factor
+    //     sponge: transcript of the argument binding the commitments and the evaluations
+    //     RETURN: commitment opening proof
+    pub fn open_multipoint<EFqSponge: FqSponge<E::Fq, E::G1Affine, E::Fr>>
+    (
+        &self,
+        plnms: Vec<(&DensePolynomial<E::Fr>, Vec<E::Fr>)>,
+        gamma: E::Fr,
+        mut sponge: EFqSponge,
+    ) -> Result<MultiPointProof<E>, ProofError>
+    {
+        let t = union(plnms.iter().map(|(_, s)| s.iter()).flatten());
+
+        // all the polynomials are opened at the same single point
+        if t.len() == 1 && plnms.iter().all(|(_, s)| s.len() == 1)
+        {
+            let mut acc = DensePolynomial::<E::Fr>::zero();
+            let mut scale = E::Fr::one();
+            for (p, _) in plnms.iter()
+            {
+                acc += &p.scale(scale);
+                scale *= &gamma;
+            }
+            return Ok(MultiPointProof {w: self.commit(&acc.divide(t[0]))?, w1: None})
+        }
+
+        // compute and commit to the quotient h
+        let zt = vanishing(&t);
+        let mut f = DensePolynomial::<E::Fr>::zero();
+        let mut scale = E::Fr::one();
+        for (p, s) in plnms.iter()
+        {
+            let r = interpolation(&s.iter().map(|x| (*x, p.evaluate(*x))).collect::<Vec<_>>());
+            let rest = t.iter().filter(|x| !s.contains(*x)).map(|x| *x).collect::<Vec<_>>();
+            f += &(&vanishing(&rest) * &(*p - &r)).scale(scale);
+            scale *= &gamma;
+        }
+        let (h, res) = DenseOrSparsePolynomial::divide_with_q_and_r(&(&f).into(), &(&zt).into())
+            .map_or(Err(ProofError::PolyDivision), |s| Ok(s))?;
+        if !res.is_zero() {return Err(ProofError::PolyDivision)}
+        let w = self.commit(&h)?;
+
+        // open the linearized polynomial L at the challenge point
+        let z = challenge::<E, EFqSponge>(&mut sponge, gamma, w);
+        let mut l = h.scale(-zt.evaluate(z));
+        let mut scale = E::Fr::one();
+        for (p, s) in plnms.iter()
+        {
+            let rz = interpolate(&s.iter().map(|x| (*x, p.evaluate(*x))).collect::<Vec<_>>(), z);
+            let rest = t.iter().filter(|x| !s.contains(*x)).map(|x| *x).collect::<Vec<_>>();
+            l += &(*p - &DensePolynomial::from_coefficients_slice(&[rz])).scale(scale * &vanishing(&rest).evaluate(z));
+            scale *= &gamma;
+        }
+
+        Ok(MultiPointProof {w, w1: Some(self.commit(&l.divide(z))?)})
+    }
+
+    // This function computes the pairing equation of the multi-point polynomial commitment opening proof
+    //     sponge: transcript of the argument binding the commitments and the evaluations
+    //     gamma: polynomial scaling factor
+    //     polys: polynomial commitment batch of
+    //         commitment value
+    //         polynomial evaluations at the points the polynomial is opened at
+    //         optional shifted commitment with the max positive powers size of the polynomial
+    //     proof: commitment opening proof
+    //     rng: randomness source context
+    //     RETURN: pairing equation with the opening challenge point, none if the proof is malformed
+    pub fn multipoint_check<EFqSponge: FqSponge<E::Fq, E::G1Affine, E::Fr>>
+    (
+        &self,
+        sponge: &mut EFqSponge,
+        gamma: E::Fr,
+        polys: &Vec<(E::G1Affine, Vec<(E::Fr, E::Fr)>, Option<(E::G1Affine, usize)>)>,
+        proof: &MultiPointProof<E>,
+        rng: &mut dyn RngCore
+    ) -> Option<(PairingCheck<E>, Option<E::Fr>)>
+    {
+        // e(F + z * W, h) = e(W, h^x) where W is the opening proof at z and F is the commitment
+        // to the opened polynomial less its evaluation at z
+
+        let t = union(polys.iter().map(|(_, s, _)| s.iter().map(|(x, _)| x)).flatten());
+        if t.len() == 0 {return Some((PairingCheck {terms: Vec::new()}, None))}
+
+        let mut points = Vec::new();
+        let mut scalars = Vec::new();
+        let mut eval = E::Fr::zero();
+        let (openx, challenge) = match proof.w1
+        {
+            None =>
+            {
+                if t.len() != 1 || polys.iter().any(|(_, s, _)| s.len() != 1) {return None}
+                let mut scale = E::Fr::one();
+                for (comm, evals, _) in polys.iter()
+                {
+                    points.push(*comm);
+                    scalars.push(scale);
+                    eval -= &(evals[0].1 * &scale);
+                    scale *= &gamma;
+                }
+                points.push(proof.w);
+                scalars.push(t[0]);
+                (proof.w, None)
+            }
+            Some(w1) =>
+            {
+                let z = challenge::<E, EFqSponge>(sponge, gamma, proof.w);
+                let mut scale = E::Fr::one();
+                for (comm, evals, _) in polys.iter()
+                {
+                    let rest = t.iter().filter(|x| !evals.iter().any(|(y, _)| y == *x)).map(|x| *x).collect::<Vec<_>>();
+                    let s = scale * &vanishing(&rest).evaluate(z);
+                    points.push(*comm);
+                    scalars.push(s);
+                    eval -= &(interpolate(evals, z) * &s);
+                    scale *= &gamma;
+                }
+                points.push(proof.w);
+                scalars.push(-vanishing(&t).evaluate(z));
+                points.push(w1);
+                scalars.push(z);
+                (w1, Some(z))
+            }
+        };
+        points.push(self.gp[0]);
+        scalars.push(eval);
+
+        // verify shifted commitments against unshifted commitments:
+        // e(ushComm, h^0) = e(shComm, h^x^(max-d))
+
+        let mut terms = Vec::new();
+        let mut shifted: HashMap<usize, (Vec<E::G1Affine>, Vec<E::Fr>)> = HashMap::new();
+        for (comm, _, s) in polys.iter()
+        {
+            if let Some((p, m)) = s
+            {
+                if !self.hn.contains_key(&(self.depth-m)) {return None}
+                let rnd = E::Fr::rand(rng);
+                points.push(*comm);
+                scalars.push(rnd);
+                let entry = shifted.entry(*m).or_insert((Vec::new(), Vec::new()));
+                entry.0.push(*p);
+                entry.1.push(rnd);
+            }
+        }
+        for (max, (p, s)) in shifted.into_iter()
+        {
+            terms.push((p, s, -self.hn[&(self.depth-max)]));
+        }
+        terms.push((vec![openx], vec![-E::Fr::one()], self.hx));
+        terms.push((points, scalars, E::G2Affine::prime_subgroup_generator()));
+
+        Some((PairingCheck {terms}, challenge))
+    }
+
+    // This function verifies the batch of the multi-point polynomial commitment opening proofs
+    //     batch: batch of the opening proofs, every one of
+    //         transcript of the argument binding the commitments and the evaluations
+    //         polynomial scaling factor
+    //         polynomial commitment batch of
+    //             commitment value
+    //             polynomial evaluations at the points the polynomial is opened at
+    //             optional shifted commitment with the max positive powers size of the polynomial
+    //         commitment opening proof
+    //     rng: randomness source context
+    //     RETURN: verification status
+    pub fn verify_multipoint<EFqSponge: FqSponge<E::Fq, E::G1Affine, E::Fr>>
+    (
+        &self,
+        batch: &mut Vec
+        <(
+            EFqSponge,
+            E::Fr,
+            Vec<(E::G1Affine, Vec<(E::Fr, E::Fr)>, Option<(E::G1Affine, usize)>)>,
+            &MultiPointProof<E>,
+        )>,
+        rng: &mut dyn RngCore
+    ) -> bool
+    {
+        let mut checks = Vec::new();
+        for (sponge, gamma, polys, proof) in batch.iter_mut()
+        {
+            match self.multipoint_check(sponge, *gamma, polys, proof, rng)
+            {
+                Some((check, _)) => checks.push(check),
+                None => return false
+            }
+        }
+        PairingCheck::combine(checks, rng).verify()
+    }
+}
+
+// The pairing equation prod_i e(sum_j s_ij * P_ij, Q_i) = 1 with the G1 bases P_ij, their scalars s_ij
+// and the G2 points Q_i, kept unevaluated so that it can be checked natively, batched with other
+// equations or handed to another system
+#[derive(Clone)]
+pub struct PairingCheck<E: PairingEngine>
+{
+    pub terms: Vec<(Vec<E::G1Affine>, Vec<E::Fr>, E::G2Affine)>,
+}
+
+impl<E: PairingEngine> PairingCheck<E>
+{
+    // This function combines the pairing equations with random scalars into the single equation
+    //     checks: pairing equations
+    //     rng: randomness source context
+    //     RETURN: pairing equation holding if all the combined equations hold
+    pub fn combine(checks: Vec<Self>, rng: &mut dyn RngCore) -> Self
+    {
+        let mut terms: Vec<(Vec<E::G1Affine>, Vec<E::Fr>, E::G2Affine)> = Vec::new();
+        for check in checks.into_iter()
+        {
+            let rnd = E::Fr::rand(rng);
+            for (p, s, q) in check.terms.into_iter()
+            {
+                let s = s.into_iter().map(|s| s * &rnd);
+                match terms.iter_mut().find(|t| t.2 == q)
+                {
+                    Some(t) => {t.0.extend(p); t.1.extend(s)}
+                    None => terms.push((p, s.collect(), q))
+                }
+            }
+        }
+        PairingCheck {terms}
+    }
+
+    // This function evaluates the pairing equation with a single final exponentiation
+    //     RETURN: verification status
+    pub fn verify(&self) -> bool
+    {
+        let table = self.terms.iter().map
+        (
+            |(p, s, q)|
+            (
+                VariableBaseMSM::multi_scalar_mul(p, &s.iter().map(|s| s.into_repr()).collect::<Vec<_>>()).into_affine().into(),
+                (*q).into()
+            )
+        ).collect::<Vec<(E::G1Prepared, E::G2Prepared)>>();
+        E::final_exponentiation(&E::miller_loop(table.iter())).unwrap() == E::Fqk::one()
+    }
+}
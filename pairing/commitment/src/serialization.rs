@@ -0,0 +1,224 @@
+/*****************************************************************************************************************
+
+This source file implements the compact byte encoding shared by the pairing proofs and verifier indexes.
+
+Every encoded object starts with the two byte header of its type tag and the encoding version. The group
+elements are encoded compressed, as the x coordinate with the sign of y, and the field elements in their
+canonical form. The decoding rejects the points that are not on the curve or not in the prime order
+subgroup and the non-canonical field elements, so that a decoded object can be handed to the verifier
+as is. The collections are prefixed with their u32 length.
+
+*****************************************************************************************************************/
+
+use algebra::{AffineCurve, PrimeField, FpParameters, PairingEngine, CanonicalSerialize, CanonicalDeserialize, Zero};
+use std::io::{Read, Write, Result as IoResult, Error as IoError, ErrorKind};
+use std::collections::HashMap;
+use crate::urs::URS;
+use crate::multipoint::MultiPointProof;
+use poly_commitment::commitment::PolyComm;
+
+// current version of the encoding
+pub const VERSION: u8 = 1;
+
+// type tags of the encoded objects
+pub const TAG_URS: u8 = 1;
+pub const TAG_PLONK_PROOF: u8 = 2;
+pub const TAG_PLONK_INDEX: u8 = 3;
+pub const TAG_MARLIN_PROOF: u8 = 4;
+pub const TAG_MARLIN_INDEX: u8 = 5;
+
+pub fn invalid(msg: &str) -> IoError
+{
+    IoError::new(ErrorKind::InvalidData, msg)
+}
+
+pub fn write_header<W: Write>(writer: &mut W, tag: u8) -> IoResult<()>
+{
+    writer.write_all(&[tag, VERSION])
+}
+
+pub fn read_header<R: Read>(reader: &mut R, tag: u8) -> IoResult<()>
+{
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header)?;
+    if header[0] != tag {return Err(invalid("unexpected object type"))}
+    if header[1] != VERSION {return Err(invalid("unsupported encoding version"))}
+    Ok(())
+}
+
+pub fn write_u8<W: Write>(writer: &mut W, x: u8) -> IoResult<()>
+{
+    writer.write_all(&[x])
+}
+
+pub fn read_u8<R: Read>(reader: &mut R) -> IoResult<u8>
+{
+    let mut x = [0u8; 1];
+    reader.read_exact(&mut x)?;
+    Ok(x[0])
+}
+
+pub fn write_u32<W: Write>(writer: &mut W, x: usize) -> IoResult<()>
+{
+    if x > u32::MAX as usize {return Err(invalid("length overflow"))}
+    writer.write_all(&(x as u32).to_le_bytes())
+}
+
+pub fn read_u32<R: Read>(reader: &mut R) -> IoResult<usize>
+{
+    let mut x = [0u8; 4];
+    reader.read_exact(&mut x)?;
+    Ok(u32::from_le_bytes(x) as usize)
+}
+
+// This function writes the field element in its canonical form
+pub fn write_scalar<W: Write, F: PrimeField>(writer: &mut W, x: &F) -> IoResult<()>
+{
+    x.serialize(&mut *writer).map_err(|e| invalid(&e.to_string()))
+}
+
+// This function reads the field element rejecting the non-canonical encoding
+pub fn read_scalar<R: Read, F: PrimeField>(reader: &mut R) -> IoResult<F>
+{
+    F::deserialize(&mut *reader).map_err(|e| invalid(&e.to_string()))
+}
+
+// This function writes the compressed group element
+pub fn write_point<W: Write, C: AffineCurve>(writer: &mut W, p: &C) -> IoResult<()>
+{
+    p.serialize(&mut *writer).map_err(|e| invalid(&e.to_string()))
+}
+
+// This function checks that the group element is in the prime order subgroup
+pub fn in_subgroup<C: AffineCurve>(p: &C) -> bool
+{
+    p.mul(<C::ScalarField as PrimeField>::Params::MODULUS).is_zero()
+}
+
+// This function reads the compressed group element checking that it is in the prime order subgroup
+pub fn read_point<R: Read, C: AffineCurve>(reader: &mut R) -> IoResult<C>
+{
+    let p = C::deserialize(&mut *reader).map_err(|e| invalid(&e.to_string()))?;
+    if !in_subgroup(&p) {return Err(invalid("point is not in the prime order subgroup"))}
+    Ok(p)
+}
+
+pub fn write_scalars<W: Write, F: PrimeField>(writer: &mut W, x: &[F]) -> IoResult<()>
+{
+    write_u32(writer, x.len())?;
+    for s in x.iter() {write_scalar(writer, s)?}
+    Ok(())
+}
+
+pub fn read_scalars<R: Read, F: PrimeField>(reader: &mut R) -> IoResult<Vec<F>>
+{
+    let n = read_u32(reader)?;
+    (0..n).map(|_| read_scalar(reader)).collect()
+}
+
+pub fn write_points<W: Write, C: AffineCurve>(writer: &mut W, p: &[C]) -> IoResult<()>
+{
+    write_u32(writer, p.len())?;
+    for x in p.iter() {write_point(writer, x)?}
+    Ok(())
+}
+
+pub fn read_points<R: Read, C: AffineCurve>(reader: &mut R) -> IoResult<Vec<C>>
+{
+    let n = read_u32(reader)?;
+    (0..n).map(|_| read_point(reader)).collect()
+}
+
+// This function writes the polynomial commitment as its segment commitments followed by the flag
+// of the shifted commitment and the shifted commitment, if any
+pub fn write_comm<W: Write, C: AffineCurve>(writer: &mut W, comm: &PolyComm<C>) -> IoResult<()>
+{
+    write_points(writer, &comm.unshifted)?;
+    match comm.shifted
+    {
+        None => write_u8(writer, 0),
+        Some(s) => {write_u8(writer, 1)?; write_point(writer, &s)}
+    }
+}
+
+pub fn read_comm<R: Read, C: AffineCurve>(reader: &mut R) -> IoResult<PolyComm<C>>
+{
+    let unshifted = read_points(reader)?;
+    let shifted = match read_u8(reader)?
+    {
+        0 => None,
+        1 => Some(read_point(reader)?),
+        _ => return Err(invalid("invalid shifted commitment flag")),
+    };
+    Ok(PolyComm {unshifted, shifted})
+}
+
+impl<E: PairingEngine> URS<E>
+{
+    // This function writes the URS with compressed points
+    pub fn write_compressed<W: Write>(&self, mut writer: W) -> IoResult<()>
+    {
+        write_header(&mut writer, TAG_URS)?;
+        write_u32(&mut writer, self.depth)?;
+        write_points(&mut writer, &self.gp)?;
+
+        let mut hn = self.hn.iter().collect::<Vec<_>>();
+        hn.sort_by_key(|(key, _)| **key);
+        write_u32(&mut writer, hn.len())?;
+        for (key, value) in hn.iter()
+        {
+            write_u32(&mut writer, **key)?;
+            write_point(&mut writer, *value)?;
+        }
+
+        write_point(&mut writer, &self.hx)?;
+        write_point(&mut writer, &self.prf)
+    }
+
+    // This function reads the URS with compressed points checking the points are in the prime order subgroups
+    pub fn read_compressed<R: Read>(mut reader: R) -> IoResult<Self>
+    {
+        read_header(&mut reader, TAG_URS)?;
+        let depth = read_u32(&mut reader)?;
+        let gp = read_points(&mut reader)?;
+        if gp.len() > depth {return Err(invalid("URS size exceeds its depth"))}
+
+        let m = read_u32(&mut reader)?;
+        let mut hn = HashMap::new();
+        for _ in 0..m
+        {
+            let key = read_u32(&mut reader)?;
+            if key > depth {return Err(invalid("URS degree exceeds its depth"))}
+            hn.insert(key, read_point(&mut reader)?);
+        }
+
+        let hx = read_point(&mut reader)?;
+        let prf = read_point(&mut reader)?;
+        Ok(URS {depth, gp, hn, hx, prf})
+    }
+}
+
+impl<E: PairingEngine> MultiPointProof<E>
+{
+    pub fn write<W: Write>(&self, writer: &mut W) -> IoResult<()>
+    {
+        write_point(writer, &self.w)?;
+        match self.w1
+        {
+            None => write_u8(writer, 0),
+            Some(w1) => {write_u8(writer, 1)?; write_point(writer, &w1)}
+        }
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> IoResult<Self>
+    {
+        let w = read_point(reader)?;
+        let w1 = match read_u8(reader)?
+        {
+            0 => None,
+            1 => Some(read_point(reader)?),
+            _ => return Err(invalid("invalid opening proof"))
+        };
+        Ok(MultiPointProof {w, w1})
+    }
+}
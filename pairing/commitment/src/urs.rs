@@ -4,10 +4,12 @@ This source file implements the Marlin universal reference string primitive
 
 *****************************************************************************************************************/
 
-use algebra::{ToBytes, FromBytes, VariableBaseMSM, FixedBaseMSM, AffineCurve, ProjectiveCurve, Field, PrimeField, PairingEngine, One, UniformRand};
+use algebra::{ToBytes, FromBytes, VariableBaseMSM, FixedBaseMSM, AffineCurve, ProjectiveCurve, Field, PrimeField, FpParameters, PairingEngine, CanonicalDeserialize, One, Zero, UniformRand};
+use crate::serialization::{read_point, write_point};
 use std::collections::HashMap;
 use rand_core::RngCore;
-use std::io::{Read, Write, Result as IoResult};
+use std::io::{Read, Write, Result as IoResult, Error as IoError, ErrorKind};
+use std::fmt;
 
 // check pairing of a&b vs c
 macro_rules! pairing_check
@@ -15,6 +17,113 @@ macro_rules! pairing_check
     ($a:expr, $b:expr, $c:expr) => {if <E>::pairing($a, $b) != $c {return false;}};
 }
 
+// length of the powers of tau transcript header hash
+pub const POWERS_OF_TAU_HASH_SIZE: usize = 64;
+
+#[derive(Debug)]
+pub enum PowersOfTauError
+{
+    Io(IoError),                // transcript read failure
+    Size,                       // transcript too short for the requested depth
+    Generator,                  // transcript powers do not start from the group generators
+    Point(usize),               // transcript point of the given index is malformed, off the curve or out of the subgroup
+    InversePowers(usize),       // h^(x^-i) is not computable from the transcript for i > 0
+    Check,                      // URS consistency check failure
+}
+
+impl From<IoError> for PowersOfTauError
+{
+    fn from(e: IoError) -> Self {PowersOfTauError::Io(e)}
+}
+
+impl fmt::Display for PowersOfTauError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            PowersOfTauError::Io(e) => write!(f, "powers of tau read failure: {}", e),
+            PowersOfTauError::Size => write!(f, "powers of tau transcript is too short for the URS depth"),
+            PowersOfTauError::Generator => write!(f, "powers of tau transcript does not start from the group generators"),
+            PowersOfTauError::Point(i) => write!(f, "powers of tau point {} is malformed, not on the curve or not in the prime order subgroup", i),
+            PowersOfTauError::InversePowers(i) => write!(f, "h^(x^-{}) can not be computed from the powers of tau, only the degree equal to the depth is supported", i),
+            PowersOfTauError::Check => write!(f, "URS consistency check failure"),
+        }
+    }
+}
+
+// byte size of the base field element of the powers of tau point encoding
+fn ptau_element_size<E: PairingEngine>() -> usize
+{
+    (<E::Fq as PrimeField>::Params::MODULUS_BITS as usize + 7) / 8
+}
+
+// byte size of the powers of tau point encoding with the coordinates of the given number of base field components
+fn ptau_point_size<E: PairingEngine>(components: usize) -> usize
+{
+    2 * components * ptau_element_size::<E>()
+}
+
+// byte size of the ToBytes encoding
+fn byte_size<T: ToBytes>(x: T) -> usize
+{
+    let mut bytes = Vec::new();
+    x.write(&mut bytes).unwrap();
+    bytes.len()
+}
+
+// This function reads the uncompressed group element of the powers of tau transcript. The point is
+// encoded as its coordinates x || y, each one as its base field components from the highest one down
+// to c0, each component big-endian. The two top bits of the first byte flag the compressed encoding
+// (0x80), which the transcripts do not use, and the point at infinity (0x40), all the other bits of which
+// are zero. The point is rejected unless it is on the curve and in the prime order subgroup: its compressed
+// encoding, y being recomputed from x, has to decode with the subgroup check of read_point back to the
+// same point.
+//     reader: transcript source
+//     components: number of the base field components of the coordinates
+//     index: position of the point in the transcript
+//     RETURN: decoded point
+fn read_ptau_point<E: PairingEngine, C: AffineCurve, R: Read>
+(
+    reader: &mut R,
+    components: usize,
+    index: usize
+) -> Result<C, PowersOfTauError>
+{
+    let n = ptau_element_size::<E>();
+    let mut bytes = vec![0u8; ptau_point_size::<E>(components)];
+    reader.read_exact(&mut bytes)?;
+    if bytes[0] & 0x80 != 0 {return Err(PowersOfTauError::Point(index))}
+    let infinity = bytes[0] & 0x40 != 0;
+    bytes[0] &= 0x3f;
+    if infinity
+    {
+        return if bytes.iter().all(|b| *b == 0) {Ok(C::zero())} else {Err(PowersOfTauError::Point(index))}
+    }
+
+    // re-encode the coordinates in the layout of the curve FromBytes implementation
+    let mut point = Vec::new();
+    for coordinate in bytes.chunks(components * n)
+    {
+        for component in coordinate.chunks(n).rev()
+        {
+            let le = component.iter().rev().cloned().collect::<Vec<_>>();
+            let x = E::Fq::deserialize(&mut &le[..]).map_err(|_| PowersOfTauError::Point(index))?;
+            x.write(&mut point)?;
+        }
+    }
+    false.write(&mut point)?;
+    let p = C::read(&mut &point[..])?;
+
+    let mut compressed = Vec::new();
+    write_point(&mut compressed, &p).map_err(|_| PowersOfTauError::Point(index))?;
+    match read_point::<_, C>(&mut &compressed[..])
+    {
+        Ok(q) if q == p => Ok(p),
+        _ => Err(PowersOfTauError::Point(index))
+    }
+}
+
 #[derive(Clone)]
 pub struct URS<E: PairingEngine>
 {
@@ -141,6 +250,66 @@ impl<E: PairingEngine> URS<E>
         }
     }
 
+    // This function imports URS instance from the powers of tau transcript of the layout
+    //
+    //     hash of POWERS_OF_TAU_HASH_SIZE bytes
+    //     g^(x^i)       for 0 <= i < 2^(power+1) - 1
+    //     h^(x^i)       for 0 <= i < 2^power
+    //     alpha and beta powers, ignored
+    //
+    // with the points encoded uncompressed as read by read_ptau_point. The negative powers h^(x^-i)
+    // are not part of the transcript, so that only the degree equal to the depth (i = 0) is supported.
+    //     reader: transcript source
+    //     power: log2 of the transcript size
+    //     depth: maximal depth of the supported circuits, not larger than 2^(power+1) - 1
+    //     degrees: commitment degrees of the committed polynomials for supported circuits
+    //     rng: randomness source context
+    //     RETURN: URS instance checked with URS::check
+    pub fn from_powers_of_tau<R : Read>
+    (
+        mut reader: R,
+        power: usize,
+        depth: usize,
+        degrees: Vec<usize>,
+        rng: &mut dyn RngCore
+    ) -> Result<Self, PowersOfTauError>
+    {
+        let g1_size = (1 << (power + 1)) - 1;
+        let g2_size = 1 << power;
+        if depth < 2 || depth > g1_size || g2_size < 2 {return Err(PowersOfTauError::Size)}
+        for d in degrees.iter()
+        {
+            if *d > depth {return Err(PowersOfTauError::Size)}
+            if *d != depth {return Err(PowersOfTauError::InversePowers(depth - *d))}
+        }
+
+        let mut hash = [0u8; POWERS_OF_TAU_HASH_SIZE];
+        reader.read_exact(&mut hash)?;
+
+        // number of the base field components of the G2 coordinates
+        let components = byte_size(E::Fqe::zero()) / byte_size(E::Fq::zero());
+
+        let mut gp = Vec::with_capacity(depth);
+        for i in 0..depth {gp.push(read_ptau_point::<E, E::G1Affine, R>(&mut reader, 1, i)?)}
+        let skip = ((g1_size - depth) * ptau_point_size::<E>(1)) as u64;
+        if std::io::copy(&mut (&mut reader).take(skip), &mut std::io::sink())? != skip
+        {
+            return Err(PowersOfTauError::Io(IoError::new(ErrorKind::UnexpectedEof, "truncated powers of tau transcript")))
+        }
+        let h0 = read_ptau_point::<E, E::G2Affine, R>(&mut reader, components, g1_size)?;
+        let hx = read_ptau_point::<E, E::G2Affine, R>(&mut reader, components, g1_size + 1)?;
+
+        let h = E::G2Affine::prime_subgroup_generator();
+        if gp[0] != E::G1Affine::prime_subgroup_generator() || h0 != h {return Err(PowersOfTauError::Generator)}
+
+        let mut hn = HashMap::new();
+        if !degrees.is_empty() {hn.insert(0, h);}
+
+        // the transcript is the update of the trivial URS with x
+        let mut urs = URS {depth, prf: gp[1], gp, hn, hx};
+        if urs.check(h, rng) {Ok(urs)} else {Err(PowersOfTauError::Check)}
+    }
+
     // This function updates URS instance and computes the update proof
     //     rng: randomness source context
     //     RETURN: computed zk-proof
@@ -150,7 +319,17 @@ impl<E: PairingEngine> URS<E>
         rng: &mut dyn RngCore
     )
     {
-        let mut x = E::Fr::rand(rng);
+        self.update_with(E::Fr::rand(rng))
+    }
+
+    // This function updates URS instance with the given update scalar
+    //     x: non-zero update scalar, the toxic waste of the update
+    pub fn update_with
+    (
+        &mut self,
+        mut x: E::Fr
+    )
+    {
         let mut cur = E::Fr::one();
         for i in 0..self.gp.len()
         {
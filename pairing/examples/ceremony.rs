@@ -0,0 +1,51 @@
+/*****************************************************************************************************************
+
+This source file implements the local simulation of the URS ceremony:
+
+    cargo run --release --example ceremony -- [participants] [depth] [directory]
+
+The participants update the URS one after another. The transcript and the final URS are written into
+the directory, read back and verified.
+
+*****************************************************************************************************************/
+
+use algebra::bn_382::Bn_382;
+use commitment_pairing::ceremony::Transcript;
+use commitment_pairing::urs::URS;
+use colored::Colorize;
+use std::{env, fs::File, io::{BufReader, BufWriter}, path::PathBuf, time::Instant};
+use rand_core::OsRng;
+
+fn main()
+{
+    let args: Vec<String> = env::args().collect();
+    let participants = args.get(1).map_or(4, |a| a.parse().expect("participants"));
+    let depth = args.get(2).map_or(64, |a| a.parse().expect("depth"));
+    let dir = args.get(3).map_or(env::temp_dir(), PathBuf::from);
+    let degrees = vec![depth / 2, depth - 1];
+    let rng = &mut OsRng;
+
+    let mut transcript = Transcript::<Bn_382>::new(depth, degrees);
+    let mut urs = transcript.initial();
+    for i in 0..participants
+    {
+        let start = Instant::now();
+        transcript.contribute(&mut urs, rng);
+        println!("{}{} {:?}", "Contribution ".green(), i, start.elapsed());
+    }
+
+    let (transcript_path, urs_path) = (dir.join("ceremony.transcript"), dir.join("ceremony.urs"));
+    transcript.write(BufWriter::new(File::create(&transcript_path).unwrap())).unwrap();
+    urs.write(BufWriter::new(File::create(&urs_path).unwrap())).unwrap();
+    println!("{}{:?}, {:?}", "Written ".yellow(), transcript_path, urs_path);
+
+    let transcript = Transcript::<Bn_382>::read(BufReader::new(File::open(&transcript_path).unwrap())).unwrap();
+    let urs = URS::<Bn_382>::read(BufReader::new(File::open(&urs_path).unwrap())).unwrap();
+
+    let start = Instant::now();
+    match transcript.verify_transcript(&urs, rng)
+    {
+        Ok(()) => println!("{}{:?}", "Transcript verified: ".green(), start.elapsed()),
+        Err(e) => {println!("{}{}", "Transcript verification failure: ".red(), e); std::process::exit(1)}
+    }
+}
@@ -13,7 +13,9 @@ use algebra::{AffineCurve, PairingEngine, curves::models::short_weierstrass_jaco
 use oracle::rndoracle::ProofError;
 use oracle::poseidon::ArithmeticSpongeParams;
 pub use super::compiled::Compiled;
-use ff_fft::EvaluationDomain;
+use ff_fft::{EvaluationDomain, Radix2EvaluationDomain as D};
+use commitment_pairing::serialization::{TAG_MARLIN_INDEX, invalid, write_header, read_header, write_u32, read_u32, write_point, read_point};
+use std::io::{Read, Write, Result as IoResult};
 
 pub trait CoordinatesCurve: AffineCurve {
     fn to_coordinates(&self) -> Option<(Self::BaseField, Self::BaseField)>;
@@ -283,3 +285,77 @@ impl<'a, E: PairingEngine> Index<'a, E>
         true
     }
 }
+
+impl<E: PairingEngine> VerifierIndex<E>
+where E::G1Affine: CoordinatesCurve
+{
+    // This function writes the verifier index with compressed points. The curve endomorphism
+    // coefficients are recomputed and the random oracle parameters are to be supplied on read
+    //     writer: destination of the encoding
+    pub fn write<W: Write>(&self, mut writer: W) -> IoResult<()>
+    {
+        write_header(&mut writer, TAG_MARLIN_INDEX)?;
+        for m in self.matrix_commitments.iter()
+        {
+            for comm in [m.row, m.col, m.val, m.rc].iter() {write_point(&mut writer, comm)?}
+        }
+        for size in [self.domains.h.size(), self.domains.k.size(), self.domains.b.size(), self.domains.x.size()].iter()
+        {
+            write_u32(&mut writer, *size)?;
+        }
+        write_u32(&mut writer, self.public_inputs)?;
+        write_u32(&mut writer, self.max_degree)?;
+        self.urs.write_compressed(&mut writer)
+    }
+
+    // This function reads the verifier index checking the points are in the prime order subgroups
+    //     reader: source of the encoding
+    //     fr_sponge_params, fq_sponge_params: random oracle argument parameters
+    //     RETURN: verifier index
+    pub fn read<R: Read>
+    (
+        mut reader: R,
+        fr_sponge_params: ArithmeticSpongeParams<E::Fr>,
+        fq_sponge_params: ArithmeticSpongeParams<E::Fq>,
+    ) -> IoResult<Self>
+    {
+        read_header(&mut reader, TAG_MARLIN_INDEX)?;
+        let mut matrix = || -> IoResult<MatrixValues<E::G1Affine>>
+        {
+            Ok(MatrixValues
+            {
+                row: read_point(&mut reader)?,
+                col: read_point(&mut reader)?,
+                val: read_point(&mut reader)?,
+                rc: read_point(&mut reader)?,
+            })
+        };
+        let matrix_commitments = [matrix()?, matrix()?, matrix()?];
+
+        let mut domain = || -> IoResult<D<E::Fr>>
+        {
+            let size = read_u32(&mut reader)?;
+            D::<E::Fr>::new(size).filter(|d| d.size() == size).ok_or(invalid("invalid evaluation domain size"))
+        };
+        let domains = EvaluationDomains {h: domain()?, k: domain()?, b: domain()?, x: domain()?};
+
+        let public_inputs = read_u32(&mut reader)?;
+        let max_degree = read_u32(&mut reader)?;
+        let urs = URS::<E>::read_compressed(&mut reader)?;
+        if urs.depth != max_degree {return Err(invalid("URS depth mismatch"))}
+        let (endo_q, endo_r) = endos::<E>();
+
+        Ok(VerifierIndex
+        {
+            matrix_commitments,
+            domains,
+            public_inputs,
+            max_degree,
+            urs,
+            fr_sponge_params,
+            fq_sponge_params,
+            endo_r,
+            endo_q,
+        })
+    }
+}
@@ -11,6 +11,9 @@ use oracle::sponge::{FqSponge, ScalarChallenge};
 use marlin_circuits::domains::EvaluationDomains;
 use crate::marlin_sponge::{FrSponge};
 use oracle::utils::PolyUtils;
+use commitment_pairing::{multipoint::MultiPointProof, serialization::{TAG_MARLIN_PROOF, write_header, read_header, write_point, read_point,
+    write_scalar, read_scalar, write_scalars, read_scalars}};
+use std::io::{Read, Write, Result as IoResult};
 pub use super::index::Index;
 
 #[derive(Clone)]
@@ -44,10 +47,8 @@ pub struct ProverProof<E: PairingEngine>
     pub h3_comm: E::G1Affine,
     pub g3_comm: (E::G1Affine, E::G1Affine),
 
-    // batched commitment opening proofs
-    pub proof1: E::G1Affine,
-    pub proof2: E::G1Affine,
-    pub proof3: E::G1Affine,
+    // batched commitment opening proof at beta[0], beta[1] and beta[2]
+    pub proof: MultiPointProof<E>,
 
     // polynomial evaluations
     pub evals : ProofEvaluations<E::Fr>,
@@ -60,6 +61,88 @@ pub struct ProverProof<E: PairingEngine>
     pub public: Vec<E::Fr>
 }
 
+impl<E: PairingEngine> ProverProof<E>
+{
+    // This function writes the proof with compressed points
+    //     writer: destination of the encoding
+    pub fn write<W: Write>(&self, mut writer: W) -> IoResult<()>
+    {
+        write_header(&mut writer, TAG_MARLIN_PROOF)?;
+        for comm in
+        [
+            self.w_comm, self.za_comm, self.zb_comm,
+            self.h1_comm, self.g1_comm.0, self.g1_comm.1,
+            self.h2_comm, self.g2_comm.0, self.g2_comm.1,
+            self.h3_comm, self.g3_comm.0, self.g3_comm.1,
+        ].iter() {write_point(&mut writer, comm)?}
+        self.proof.write(&mut writer)?;
+
+        let e = &self.evals;
+        for x in [e.w, e.za, e.zb, e.h1, e.g1, e.h2, e.g2, e.h3, e.g3].iter()
+            .chain(e.row.iter()).chain(e.col.iter()).chain(e.val.iter()).chain(e.rc.iter())
+        {
+            write_scalar(&mut writer, x)?
+        }
+        write_scalar(&mut writer, &self.sigma2)?;
+        write_scalar(&mut writer, &self.sigma3)?;
+        write_scalars(&mut writer, &self.public)
+    }
+
+    // This function reads the proof checking the points are in the prime order subgroup
+    //     reader: source of the encoding
+    //     RETURN: prover's zk-proof
+    pub fn read<R: Read>(mut reader: R) -> IoResult<Self>
+    {
+        read_header(&mut reader, TAG_MARLIN_PROOF)?;
+        let mut comm = (0..12).map(|_| read_point(&mut reader)).collect::<IoResult<Vec<E::G1Affine>>>()?.into_iter();
+        let mut point = || comm.next().unwrap();
+        let (w_comm, za_comm, zb_comm) = (point(), point(), point());
+        let (h1_comm, g1_comm) = (point(), (point(), point()));
+        let (h2_comm, g2_comm) = (point(), (point(), point()));
+        let (h3_comm, g3_comm) = (point(), (point(), point()));
+        let proof = MultiPointProof::read(&mut reader)?;
+
+        let mut scalar = || read_scalar::<R, E::Fr>(&mut reader);
+        let evals = ProofEvaluations
+        {
+            w: scalar()?,
+            za: scalar()?,
+            zb: scalar()?,
+            h1: scalar()?,
+            g1: scalar()?,
+            h2: scalar()?,
+            g2: scalar()?,
+            h3: scalar()?,
+            g3: scalar()?,
+            row: [scalar()?, scalar()?, scalar()?],
+            col: [scalar()?, scalar()?, scalar()?],
+            val: [scalar()?, scalar()?, scalar()?],
+            rc: [scalar()?, scalar()?, scalar()?],
+        };
+        let sigma2 = scalar()?;
+        let sigma3 = scalar()?;
+        let public = read_scalars(&mut reader)?;
+
+        Ok(ProverProof
+        {
+            w_comm,
+            za_comm,
+            zb_comm,
+            h1_comm,
+            g1_comm,
+            h2_comm,
+            g2_comm,
+            h3_comm,
+            g3_comm,
+            proof,
+            evals,
+            sigma2,
+            sigma3,
+            public
+        })
+    }
+}
+
 impl<E: PairingEngine> ProverProof<E>
 {
     // This function constructs prover's zk-proof from the witness & the Index against URS instance
@@ -282,51 +365,41 @@ impl<E: PairingEngine> ProverProof<E>
             g3_comm,
 
             // polynomial commitment batched opening proofs
-            proof1: urs.open
-            (
-                vec!
-                [
-                    &x_hat,
-                    &w,
-                    &za,
-                    &zb,
-                    &g1,
-                    &h1,
-                ],
-                batch_chal,
-                beta[0]
-            )?,
-            proof2: urs.open
+            proof: urs.open_multipoint
             (
                 vec!
                 [
-                    &g2,
-                    &h2,
+                    (&x_hat, vec![beta[0]]),
+                    (&w, vec![beta[0]]),
+                    (&za, vec![beta[0]]),
+                    (&zb, vec![beta[0]]),
+                    (&g1, vec![beta[0]]),
+                    (&h1, vec![beta[0]]),
+
+                    (&g2, vec![beta[1]]),
+                    (&h2, vec![beta[1]]),
+
+                    (&g3, vec![beta[2]]),
+                    (&h3, vec![beta[2]]),
+                    (&index.compiled[0].row, vec![beta[2]]),
+                    (&index.compiled[1].row, vec![beta[2]]),
+                    (&index.compiled[2].row, vec![beta[2]]),
+                    (&index.compiled[0].col, vec![beta[2]]),
+                    (&index.compiled[1].col, vec![beta[2]]),
+                    (&index.compiled[2].col, vec![beta[2]]),
+                    (&index.compiled[0].val, vec![beta[2]]),
+                    (&index.compiled[1].val, vec![beta[2]]),
+                    (&index.compiled[2].val, vec![beta[2]]),
+                    (&index.compiled[0].rc, vec![beta[2]]),
+                    (&index.compiled[1].rc, vec![beta[2]]),
+                    (&index.compiled[2].rc, vec![beta[2]]),
                 ],
                 batch_chal,
-                beta[1]
-            )?,
-            proof3: urs.open
-            (
-                vec!
-                [
-                    &g3,
-                    &h3,
-                    &index.compiled[0].row,
-                    &index.compiled[1].row,
-                    &index.compiled[2].row,
-                    &index.compiled[0].col,
-                    &index.compiled[1].col,
-                    &index.compiled[2].col,
-                    &index.compiled[0].val,
-                    &index.compiled[1].val,
-                    &index.compiled[2].val,
-                    &index.compiled[0].rc,
-                    &index.compiled[1].rc,
-                    &index.compiled[2].rc,
-                ],
-                batch_chal,
-                beta[2]
+                {
+                    let mut s = EFqSponge::new(index.fq_sponge_params.clone());
+                    s.absorb_fr(&[digest_before_evaluations]);
+                    s
+                }
             )?,
 
             // polynomial evaluations
@@ -12,6 +12,47 @@ use algebra::{Field, PairingEngine, Zero};
 use ff_fft::{DensePolynomial, Evaluations, EvaluationDomain, GeneralEvaluationDomain};
 use oracle::sponge::{FqSponge, ScalarChallenge};
 use crate::marlin_sponge::{FrSponge};
+use commitment_pairing::multipoint::PairingCheck;
+
+// The verification plan of the proof lays out the whole verifier computation: the random oracles derived
+// from the transcript, the public input polynomial with its commitment, the sumcheck argument equations
+// and the opening of the committed polynomials reduced to the pairing equation. The verifier checks it
+// natively, while another system, e.g. an in-circuit verifier or a proof aggregator, may check the same
+// plan without reproducing the argument.
+pub struct VerificationPlan<E: PairingEngine>
+{
+    pub x_hat: DensePolynomial<E::Fr>,  // public input polynomial over the public input domain
+    pub x_hat_comm: E::G1Affine,        // public input polynomial commitment
+    pub oracles: RandomOracles<E::Fr>,  // random oracles of the argument
+    pub beta: [E::Fr; 3],               // evaluation points, the beta oracles mapped with the endomorphism
+    pub batch: E::Fr,                   // polynomial scaling factor of the opening, the batch oracle mapped with the endomorphism
+    pub sumcheck: [(E::Fr, E::Fr); 3],  // left and right hand sides of the sumcheck argument equations
+
+    // committed polynomial openings of
+    //     commitment value
+    //     polynomial evaluations at the points the polynomial is opened at
+    //     optional shifted commitment with the max positive powers size of the polynomial
+    pub openings: Vec<(E::G1Affine, Vec<(E::Fr, E::Fr)>, Option<(E::G1Affine, usize)>)>,
+    pub challenge: Option<E::Fr>,       // challenge point of the multi-point opening
+    pub pairing: PairingCheck<E>,       // pairing equation of the opening proof
+}
+
+impl<E: PairingEngine> VerificationPlan<E>
+{
+    // This function checks the sumcheck argument equations of the plan
+    //     RETURN: verification status
+    pub fn sumcheck_verify(&self) -> bool
+    {
+        self.sumcheck.iter().all(|(lhs, rhs)| lhs == rhs)
+    }
+
+    // This function checks the plan natively
+    //     RETURN: verification status
+    pub fn verify(&self) -> bool
+    {
+        self.sumcheck_verify() && self.pairing.verify()
+    }
+}
 
 impl<E: PairingEngine> ProverProof<E>
 {
@@ -25,10 +66,25 @@ impl<E: PairingEngine> ProverProof<E>
         index: &Index<E>,
         oracles: &RandomOracles<E::Fr>,
     ) -> bool
+    {
+        let (lhs, rhs) = self.sumcheck_1_terms(index, oracles);
+        lhs == rhs
+    }
+
+    // This function computes the sides of the prover's first sumcheck argument equation
+    //     index: Index
+    //     oracles: random oracles of the argument
+    //     RETURN: left and right hand sides of the equation
+    pub fn sumcheck_1_terms
+    (
+        &self,
+        index: &Index<E>,
+        oracles: &RandomOracles<E::Fr>,
+    ) -> (E::Fr, E::Fr)
     {
         let beta0 = oracles.beta[0].to_field(&index.endo_r);
         // compute ra*zm - ram*z ?= h*v + b*g to verify the first sumcheck argument
-        (oracles.alpha.pow([index.domains.h.size]) - &beta0.pow([index.domains.h.size])) *
+        ((oracles.alpha.pow([index.domains.h.size]) - &beta0.pow([index.domains.h.size])) *
             &(0..3).map
             (
                 |i|
@@ -42,7 +98,7 @@ impl<E: PairingEngine> ProverProof<E>
                     }
                 }
             ).fold(E::Fr::zero(), |x, y| x + &y)
-        ==
+        ,
         (oracles.alpha - &beta0) *
         &(
             self.evals.h1 * &index.domains.h.evaluate_vanishing_polynomial(beta0) +
@@ -50,7 +106,7 @@ impl<E: PairingEngine> ProverProof<E>
             &(self.sigma2 * &index.domains.h.size_as_field_element *
             &(self.evals.w * &index.domains.x.evaluate_vanishing_polynomial(beta0) +
             &oracles.x_hat_beta1))
-        )
+        ))
     }
 
     // This function verifies the prover's second sumcheck argument values
@@ -63,14 +119,29 @@ impl<E: PairingEngine> ProverProof<E>
         index: &Index<E>,
         oracles: &RandomOracles<E::Fr>,
     ) -> bool
+    {
+        let (lhs, rhs) = self.sumcheck_2_terms(index, oracles);
+        lhs == rhs
+    }
+
+    // This function computes the sides of the prover's second sumcheck argument equation
+    //     index: Index
+    //     oracles: random oracles of the argument
+    //     RETURN: left and right hand sides of the equation
+    pub fn sumcheck_2_terms
+    (
+        &self,
+        index: &Index<E>,
+        oracles: &RandomOracles<E::Fr>,
+    ) -> (E::Fr, E::Fr)
     {
         let beta1 = oracles.beta[1].to_field(&index.endo_r);
-        self.sigma3 * &index.domains.k.size_as_field_element *
+        (self.sigma3 * &index.domains.k.size_as_field_element *
             &((oracles.alpha.pow([index.domains.h.size]) - &beta1.pow([index.domains.h.size])))
-        ==
+        ,
         (oracles.alpha - &beta1) * &(self.evals.h2 *
             &index.domains.h.evaluate_vanishing_polynomial(beta1) +
-            &self.sigma2 + &(self.evals.g2 * &beta1))
+            &self.sigma2 + &(self.evals.g2 * &beta1)))
     }
 
     // This function verifies the prover's third sumcheck argument values
@@ -81,8 +152,23 @@ impl<E: PairingEngine> ProverProof<E>
     (
         &self,
         index: &Index<E>,
-        oracles: &RandomOracles<E::Fr>
+        oracles: &RandomOracles<E::Fr>,
     ) -> bool
+    {
+        let (lhs, rhs) = self.sumcheck_3_terms(index, oracles);
+        lhs == rhs
+    }
+
+    // This function computes the sides of the prover's third sumcheck argument equation
+    //     index: Index
+    //     oracles: random oracles of the argument
+    //     RETURN: left and right hand sides of the equation
+    pub fn sumcheck_3_terms
+    (
+        &self,
+        index: &Index<E>,
+        oracles: &RandomOracles<E::Fr>
+    ) -> (E::Fr, E::Fr)
     {
         let beta0 = oracles.beta[0].to_field(&index.endo_r);
         let beta1 = oracles.beta[1].to_field(&index.endo_r);
@@ -109,12 +195,96 @@ impl<E: PairingEngine> ProverProof<E>
             }
         ).fold(E::Fr::zero(), |x, y| x + &y);
 
-        index.domains.k.evaluate_vanishing_polynomial(beta2) * &self.evals.h3
-        ==
+        (index.domains.k.evaluate_vanishing_polynomial(beta2) * &self.evals.h3
+        ,
         index.domains.h.evaluate_vanishing_polynomial(beta0) *
             &(index.domains.h.evaluate_vanishing_polynomial(beta1)) *
             &acc - &((beta2 * &self.evals.g3 + &self.sigma3) *
-            &crb[0] * &crb[1] * &crb[2])
+            &crb[0] * &crb[1] * &crb[2]))
+    }
+
+    // This function computes the verification plan of the proof: the random oracles, the public input
+    // polynomial, the sumcheck argument equations and the pairing equation of the opening proof
+    //     index: Index
+    //     rng: randomness source context
+    //     RETURN: verification plan
+    pub fn plan
+        <EFqSponge: FqSponge<E::Fq, E::G1Affine, E::Fr>,
+         EFrSponge: FrSponge<E::Fr>,
+        >
+    (
+        &self,
+        index: &Index<E>,
+        rng: &mut dyn RngCore
+    ) -> Result<VerificationPlan<E>, ProofError>
+    {
+        // TODO: Cache this interpolated polynomial.
+        let x_hat = Evaluations::<E::Fr>::from_vec_and_domain(
+            self.public.clone(), GeneralEvaluationDomain::Radix2(index.domains.x)
+        ).interpolate();
+        let x_hat_comm = index.urs.commit(&x_hat)?;
+
+        let oracles = self.oracles::<EFqSponge, EFrSponge>(index, x_hat_comm, &x_hat)?;
+        let sumcheck =
+        [
+            self.sumcheck_1_terms(index, &oracles),
+            self.sumcheck_2_terms(index, &oracles),
+            self.sumcheck_3_terms(index, &oracles),
+        ];
+
+        let batch = oracles.batch.to_field(&index.endo_r);
+        let beta =
+        [
+            oracles.beta[0].to_field(&index.endo_r),
+            oracles.beta[1].to_field(&index.endo_r),
+            oracles.beta[2].to_field(&index.endo_r),
+        ];
+        let mut fq_sponge = EFqSponge::new(index.fq_sponge_params.clone());
+        fq_sponge.absorb_fr(&[oracles.digest_before_evaluations]);
+
+        let openings = vec!
+        [
+            (x_hat_comm,        vec![(beta[0], oracles.x_hat_beta1)], None),
+            (self.w_comm,       vec![(beta[0], self.evals.w)],  None),
+            (self.za_comm,      vec![(beta[0], self.evals.za)], None),
+            (self.zb_comm,      vec![(beta[0], self.evals.zb)], None),
+            (self.g1_comm.0,    vec![(beta[0], self.evals.g1)], Some((self.g1_comm.1, index.domains.h.size()-1))),
+            (self.h1_comm,      vec![(beta[0], self.evals.h1)], None),
+
+            (self.g2_comm.0,    vec![(beta[1], self.evals.g2)], Some((self.g2_comm.1, index.domains.h.size()-1))),
+            (self.h2_comm,      vec![(beta[1], self.evals.h2)], None),
+
+            (self.g3_comm.0,    vec![(beta[2], self.evals.g3)], Some((self.g3_comm.1, index.domains.k.size()-1))),
+            (self.h3_comm,      vec![(beta[2], self.evals.h3)], None),
+            (index.matrix_commitments[0].row, vec![(beta[2], self.evals.row[0])], None),
+            (index.matrix_commitments[1].row, vec![(beta[2], self.evals.row[1])], None),
+            (index.matrix_commitments[2].row, vec![(beta[2], self.evals.row[2])], None),
+            (index.matrix_commitments[0].col, vec![(beta[2], self.evals.col[0])], None),
+            (index.matrix_commitments[1].col, vec![(beta[2], self.evals.col[1])], None),
+            (index.matrix_commitments[2].col, vec![(beta[2], self.evals.col[2])], None),
+            (index.matrix_commitments[0].val, vec![(beta[2], self.evals.val[0])], None),
+            (index.matrix_commitments[1].val, vec![(beta[2], self.evals.val[1])], None),
+            (index.matrix_commitments[2].val, vec![(beta[2], self.evals.val[2])], None),
+            (index.matrix_commitments[0].rc, vec![(beta[2], self.evals.rc[0])], None),
+            (index.matrix_commitments[1].rc, vec![(beta[2], self.evals.rc[1])], None),
+            (index.matrix_commitments[2].rc, vec![(beta[2], self.evals.rc[2])], None),
+        ];
+
+        let (pairing, challenge) = index.urs.multipoint_check(&mut fq_sponge, batch, &openings, &self.proof, rng)
+            .ok_or(ProofError::OpenProof)?;
+
+        Ok(VerificationPlan
+        {
+            x_hat,
+            x_hat_comm,
+            oracles,
+            beta,
+            batch,
+            sumcheck,
+            openings,
+            challenge,
+            pairing,
+        })
     }
 
     // This function verifies the batch of zk-proofs
@@ -132,81 +302,17 @@ impl<E: PairingEngine> ProverProof<E>
         rng: &mut dyn RngCore
     ) -> Result<bool, ProofError>
     {
-        let mut batch = Vec::new();
+        let mut checks = Vec::new();
         for proof in proofs.iter()
         {
-            let proof = proof.clone();
-            // TODO: Cache this interpolated polynomial.
-            let x_hat = Evaluations::<E::Fr>::from_vec_and_domain(
-                proof.public.clone(), GeneralEvaluationDomain::Radix2(index.domains.x)
-            ).interpolate();
-            let x_hat_comm = index.urs.commit(&x_hat)?;
-
-            let oracles = proof.oracles::<EFqSponge, EFrSponge>(index, x_hat_comm, &x_hat)?;
+            let plan = proof.plan::<EFqSponge, EFrSponge>(index, rng)?;
 
             // first, verify the sumcheck argument values
-            if 
-                !proof.sumcheck_1_verify (index, &oracles) ||
-                !proof.sumcheck_2_verify (index, &oracles) ||
-                !proof.sumcheck_3_verify (index, &oracles)
-            {
-                return Err(ProofError::ProofVerification)
-            }
-
-            let batch_chal = oracles.batch.to_field(&index.endo_r);
-
-            batch.push
-            ((
-                oracles.beta[0].to_field(&index.endo_r),
-                batch_chal,
-                vec!
-                [
-                    (x_hat_comm,        oracles.x_hat_beta1, None),
-                    (proof.w_comm,      proof.evals.w,  None),
-                    (proof.za_comm,     proof.evals.za, None),
-                    (proof.zb_comm,     proof.evals.zb, None),
-                    (proof.g1_comm.0,   proof.evals.g1, Some((proof.g1_comm.1, index.domains.h.size()-1))),
-                    (proof.h1_comm,     proof.evals.h1, None),
-                ],
-                proof.proof1
-            ));
-            batch.push
-            ((
-                oracles.beta[1].to_field(&index.endo_r),
-                batch_chal,
-                vec!
-                [
-                    (proof.g2_comm.0,   proof.evals.g2, Some((proof.g2_comm.1, index.domains.h.size()-1))),
-                    (proof.h2_comm,     proof.evals.h2, None),
-                ],
-                proof.proof2
-            ));
-            batch.push
-            ((
-                oracles.beta[2].to_field(&index.endo_r),
-                batch_chal,
-                vec!
-                [
-                    (proof.g3_comm.0, proof.evals.g3, Some((proof.g3_comm.1, index.domains.k.size()-1))),
-                    (proof.h3_comm, proof.evals.h3, None),
-                    (index.matrix_commitments[0].row, proof.evals.row[0], None),
-                    (index.matrix_commitments[1].row, proof.evals.row[1], None),
-                    (index.matrix_commitments[2].row, proof.evals.row[2], None),
-                    (index.matrix_commitments[0].col, proof.evals.col[0], None),
-                    (index.matrix_commitments[1].col, proof.evals.col[1], None),
-                    (index.matrix_commitments[2].col, proof.evals.col[2], None),
-                    (index.matrix_commitments[0].val, proof.evals.val[0], None),
-                    (index.matrix_commitments[1].val, proof.evals.val[1], None),
-                    (index.matrix_commitments[2].val, proof.evals.val[2], None),
-                    (index.matrix_commitments[0].rc, proof.evals.rc[0], None),
-                    (index.matrix_commitments[1].rc, proof.evals.rc[1], None),
-                    (index.matrix_commitments[2].rc, proof.evals.rc[2], None),
-        ],
-                proof.proof3
-            ));
+            if !plan.sumcheck_verify() {return Err(ProofError::ProofVerification)}
+            checks.push(plan.pairing);
         }
         // second, verify the commitment opening proofs
-        match index.urs.verify(&batch, rng)
+        match PairingCheck::combine(checks, rng).verify()
         {
             false => Err(ProofError::OpenProof),
             true => Ok(true)
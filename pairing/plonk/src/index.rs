@@ -1,187 +1,142 @@
 /*****************************************************************************************************************
 
-This source file implements Plonk Protocol Index primitive.
+This source file instantiates the Plonk Protocol Index primitive with the pairing (KZG) commitment scheme
+and implements the compressed encoding of the verifier index.
 
 *****************************************************************************************************************/
 
-use rand_core::RngCore;
 use commitment_pairing::urs::URS;
 use oracle::poseidon::ArithmeticSpongeParams;
-use plonk_circuits::constraints::ConstraintSystem;
-use ff_fft::{DensePolynomial, EvaluationDomain, Radix2EvaluationDomain as D};
-use algebra::{AffineCurve, PairingEngine, curves::models::short_weierstrass_jacobian::{GroupAffine as SWJAffine}, Zero, One};
-use oracle::rndoracle::ProofError;
-
-pub trait CoordinatesCurve: AffineCurve {
-    fn to_coordinates(&self) -> Option<(Self::BaseField, Self::BaseField)>;
-    fn of_coordinates(x:Self::BaseField, y:Self::BaseField) -> Self;
+use plonk_circuits::constraints::{zk_w, zk_polynomial, GateRegistry, Selector};
+use ff_fft::{EvaluationDomain, Radix2EvaluationDomain as D};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use algebra::PairingEngine;
+use commitment_pairing::serialization::{TAG_PLONK_INDEX, invalid, write_header, read_header, write_u32, read_u32,
+    write_comm, read_comm, write_scalar, read_scalar};
+use std::io::{Read, Write, Result as IoResult};
+
+pub type Index<E> = plonk_protocol::index::Index<<E as PairingEngine>::G1Affine, URS<E>>;
+pub type VerifierIndex<E> = plonk_protocol::index::VerifierIndex<<E as PairingEngine>::G1Affine, URS<E>>;
+
+// order of the built-in gate selector polynomial commitments in the encoding
+const SELECTORS: [Selector; 15] =
+[
+    Selector::Ql, Selector::Qr, Selector::Qo, Selector::Qm, Selector::Qc,
+    Selector::Rc0, Selector::Rc1, Selector::Rc2, Selector::Ps,
+    Selector::Add, Selector::Mul1, Selector::Mul2, Selector::Emul1, Selector::Emul2, Selector::Emul3,
+];
+
+// Compressed encoding of the verifier index
+pub trait VerifierIndexEncoding<E: PairingEngine>: Sized
+{
+    // This function writes the verifier index with compressed points. The zero-knowledge polynomial
+    // is recomputed on read, the custom gate implementations and the random oracle parameters are to
+    // be supplied on read and only the custom gate names are written
+    //     writer: destination of the encoding
+    fn write<W: Write>(&self, writer: W) -> IoResult<()>;
+
+    // This function reads the verifier index checking the points are in the prime order subgroups
+    //     reader: source of the encoding
+    //     custom: custom gates registered with the constraint system the index is compiled from
+    //     fr_sponge_params, fq_sponge_params: random oracle argument parameters
+    //     RETURN: verifier index
+    fn read<R: Read>
+    (
+        reader: R,
+        custom: GateRegistry<E::Fr>,
+        fr_sponge_params: ArithmeticSpongeParams<E::Fr>,
+        fq_sponge_params: ArithmeticSpongeParams<E::Fq>,
+    ) -> IoResult<Self>;
 }
 
-impl<P: algebra::SWModelParameters> CoordinatesCurve for SWJAffine<P> {
-    fn to_coordinates(&self) -> Option<(Self::BaseField, Self::BaseField)>{
-        if self.infinity {
-            None
-        } else {
-            Some((self.x, self.y))
-        }
-    }
-
-    fn of_coordinates(x:Self::BaseField, y:Self::BaseField) -> Self {
-        SWJAffine::<P>::new(x, y, false)
-    }
-}
+impl<E: PairingEngine> VerifierIndexEncoding<E> for VerifierIndex<E>
+{
+    fn write<W: Write>(&self, mut writer: W) -> IoResult<()>
+    {
+        write_header(&mut writer, TAG_PLONK_INDEX)?;
+        write_u32(&mut writer, self.domain.size())?;
+        write_u32(&mut writer, self.max_quot_size)?;
 
-pub enum URSValue<'a, E : PairingEngine> {
-    Value(URS<E>),
-    Ref(&'a URS<E>)
-}
+        for comm in self.sigma_comm.iter() {write_comm(&mut writer, comm)?}
+        for sel in SELECTORS.iter() {write_comm(&mut writer, &self.sel_comm[sel])?}
 
-impl<'a, E : PairingEngine> URSValue<'a, E> {
-    pub fn get_ref(&self) -> & URS<E> {
-        match self {
-            URSValue::Value(x) => &x,
-            URSValue::Ref(x) => x
+        write_u32(&mut writer, self.custom.gates.len())?;
+        for (name, _) in self.custom.gates.iter()
+        {
+            write_u32(&mut writer, name.len())?;
+            writer.write_all(name.as_bytes())?;
         }
-    }
-}
-
-pub enum URSSpec <'a, 'b, E:PairingEngine>{
-    Use(&'a URS<E>),
-    Generate(&'b mut dyn RngCore)
-}
-
-impl<'a, E: PairingEngine> URSValue<'a, E> {
-    pub fn generate<'b>(
-        degree: usize,
-        rng : &'b mut dyn RngCore) -> URS<E> {
-
-        URS::<E>::create
-        (
-
-            degree,
-            vec![],
-        rng )
-    }
-
-    pub fn create<'b>(degree: usize, spec : URSSpec<'a, 'b, E>) -> URSValue<'a, E>{
-        match spec {
-            URSSpec::Use(x) => URSValue::Ref(x),
-            URSSpec::Generate(rng) => URSValue::Value(Self::generate(degree, rng))
+        write_u32(&mut writer, self.custom_comm.len())?;
+        for (id, comm) in self.custom_comm.iter()
+        {
+            write_u32(&mut writer, *id)?;
+            write_u32(&mut writer, comm.len())?;
+            for c in comm.iter() {write_comm(&mut writer, c)?}
         }
-    }
-}
-
-pub struct Index<'a, E: PairingEngine>
-{
-    // constraints as Lagrange-based polynoms
-    pub cs: ConstraintSystem<E::Fr>,
 
-    // polynomial commitment keys
-    pub urs: URSValue<'a, E>,
-
-    // random oracle argument parameters
-    pub fq_sponge_params: ArithmeticSpongeParams<E::Fq>,
-
-    // Coefficients for the curve endomorphism
-    pub endo_r: E::Fr,
-    pub endo_q: E::Fq,
-}
-
-pub struct VerifierIndex<E: PairingEngine>
-{
-    pub domain: D<E::Fr>, // evaluation domain
-
-    // index polynomial commitments
-    pub sigma_comm:  [E::G1Affine; 3],   // permutation commitment array
-    pub sid_comm:    E::G1Affine,        // SID commitment
-    pub ql_comm:     E::G1Affine,        // left input wire commitment
-    pub qr_comm:     E::G1Affine,        // right input wire commitment
-    pub qo_comm:     E::G1Affine,        // output wire commitment
-    pub qm_comm:     E::G1Affine,        // multiplication commitment
-    pub qc_comm:     E::G1Affine,        // constant wire commitment
-
-    pub r: E::Fr,   // coordinate shift for right wires
-    pub o: E::Fr,   // coordinate shift for output wires
-
-    // polynomial commitment keys, trimmed
-    pub urs: URS<E>,
-
-    // random oracle argument parameters
-    pub fr_sponge_params: ArithmeticSpongeParams<E::Fr>,
-    pub fq_sponge_params: ArithmeticSpongeParams<E::Fq>,
-
-    // Coefficients for the curve endomorphism
-    pub endo_r: E::Fr,
-    pub endo_q: E::Fq,
-}
-
-pub fn endos<E:PairingEngine>() -> (E::Fq, E::Fr) where E::G1Affine : CoordinatesCurve {
-    let endo_q : E::Fq = oracle::sponge::endo_coefficient();
-    let endo_r = {
-        let potential_endo_r : E::Fr = oracle::sponge::endo_coefficient();
-        let t = E::G1Affine::prime_subgroup_generator();
-        let (x, y) = t.to_coordinates().unwrap();
-        let phi_t = E::G1Affine::of_coordinates(x * &endo_q, y);
-        if t.mul(potential_endo_r) == phi_t.into_projective() {
-            potential_endo_r
-        } else {
-            potential_endo_r * &potential_endo_r
-        }
-    };
-    (endo_q, endo_r)
-}
+        for x in [self.r, self.o, self.endo].iter() {write_scalar(&mut writer, x)?}
+        self.pcs.write_compressed(&mut writer)
+    }
 
-impl<'a, E: PairingEngine> Index<'a, E>
-where E::G1Affine: CoordinatesCurve
-{
-    // this function compiles the circuit from constraints
-    pub fn create<'b>
+    fn read<R: Read>
     (
-        cs: ConstraintSystem<E::Fr>,
+        mut reader: R,
+        custom: GateRegistry<E::Fr>,
+        fr_sponge_params: ArithmeticSpongeParams<E::Fr>,
         fq_sponge_params: ArithmeticSpongeParams<E::Fq>,
-        urs : URSSpec<'a, 'b, E>
-    ) -> Self
+    ) -> IoResult<Self>
     {
-        let urs = URSValue::create(cs.domain.d1.size()+3, urs);
-        let (endo_q, endo_r) = endos::<E>();
-
-        Index
+        read_header(&mut reader, TAG_PLONK_INDEX)?;
+        let size = read_u32(&mut reader)?;
+        let domain = D::<E::Fr>::new(size).filter(|d| d.size() == size && size >= 4).ok_or(invalid("invalid evaluation domain size"))?;
+        let max_quot_size = read_u32(&mut reader)?;
+
+        let sigma_comm = [read_comm(&mut reader)?, read_comm(&mut reader)?, read_comm(&mut reader)?];
+        let mut sel_comm = BTreeMap::new();
+        for sel in SELECTORS.iter() {sel_comm.insert(*sel, read_comm(&mut reader)?);}
+
+        let gates = read_u32(&mut reader)?;
+        if gates != custom.gates.len() {return Err(invalid("custom gates mismatch"))}
+        for (name, _) in custom.gates.iter()
         {
-            fq_sponge_params,
-            endo_q,
-            endo_r,
-            urs,
-            cs,
+            if read_u32(&mut reader)? != name.len() {return Err(invalid("custom gates mismatch"))}
+            let mut bytes = vec![0u8; name.len()];
+            reader.read_exact(&mut bytes)?;
+            if bytes != name.as_bytes() {return Err(invalid("custom gates mismatch"))}
+        }
+        let mut custom_comm = BTreeMap::new();
+        for _ in 0..read_u32(&mut reader)?
+        {
+            let id = read_u32(&mut reader)?;
+            if id >= gates {return Err(invalid("custom gates mismatch"))}
+            let comm = (0..read_u32(&mut reader)?).map(|_| read_comm(&mut reader)).collect::<IoResult<Vec<_>>>()?;
+            custom_comm.insert(id, comm);
         }
-    }
 
-    pub fn verifier_index(&self) -> Result<VerifierIndex<E>, ProofError>
-    {
-        let urs = self.urs.get_ref().clone();
-        Ok(VerifierIndex
+        let r = read_scalar(&mut reader)?;
+        let o = read_scalar(&mut reader)?;
+        let endo = read_scalar(&mut reader)?;
+        let pcs = Arc::new(URS::<E>::read_compressed(&mut reader)?);
+
+        Ok(VerifierIndex::<E>
         {
-            domain: self.cs.domain.d1,
-
-            sid_comm: urs.commit(&DensePolynomial::from_coefficients_slice(&[E::Fr::zero(), E::Fr::one()]))?,
-            sigma_comm:
-            [
-                urs.commit(&self.cs.sigmam[0])?,
-                urs.commit(&self.cs.sigmam[1])?,
-                urs.commit(&self.cs.sigmam[2])?,
-            ],
-            ql_comm: urs.commit(&self.cs.qlm)?,
-            qr_comm: urs.commit(&self.cs.qrm)?,
-            qo_comm: urs.commit(&self.cs.qom)?,
-            qm_comm: urs.commit(&self.cs.qmm)?,
-            qc_comm: urs.commit(&self.cs.qc)?,
-
-            fr_sponge_params: self.cs.fr_sponge_params.clone(),
-            fq_sponge_params: self.fq_sponge_params.clone(),
-            endo_q: self.endo_q,
-            endo_r: self.endo_r,
-            urs,
-            r: self.cs.r,
-            o: self.cs.o,
+            domain,
+            max_poly_size: pcs.depth,
+            max_quot_size,
+            pcs,
+            sigma_comm,
+            sel_comm,
+            custom,
+            custom_comm,
+            tables: None,
+            r,
+            o,
+            zkpm: zk_polynomial(domain),
+            w: zk_w(domain),
+            endo,
+            fr_sponge_params,
+            fq_sponge_params,
         })
     }
 }
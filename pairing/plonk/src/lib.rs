@@ -1,4 +1,3 @@
 pub mod prover;
-pub mod verifier;
-pub mod plonk_sponge;
 pub mod index;
+pub use plonk_protocol::{verifier, plonk_sponge, range};
@@ -0,0 +1,112 @@
+/*****************************************************************************************************************
+
+This source file implements the URS ceremony test suite driver. The following tests are implemented:
+
+1. ceremony_test
+   This unit test simulates the URS ceremony with several participants, verifies the transcript
+   and the final URS, read back from their serialized form, and checks that the tampered, replayed
+   and truncated transcripts are rejected, as well as the serialized transcripts with the counts out
+   of range and the contributions with a point out of the prime order subgroup.
+
+*****************************************************************************************************************/
+
+use algebra::{PairingEngine, bn_382::{Bn_382, G2Affine, Fq2}, AffineCurve, ProjectiveCurve, UniformRand};
+use commitment_pairing::ceremony::{simulate, Transcript, CeremonyError, MAX_DEPTH, MAX_CONTRIBUTIONS};
+use commitment_pairing::serialization::in_subgroup;
+use commitment_pairing::urs::URS;
+use colored::Colorize;
+use std::time::Instant;
+use rand_core::OsRng;
+
+#[test]
+fn ceremony_test()
+{
+    test::<Bn_382>();
+
+    // the G2 point of the twist out of the prime order subgroup is rejected on read and by the verification
+    let rng = &mut OsRng;
+    let (transcript, urs) = simulate::<Bn_382>(30, vec![3,7], 2, rng);
+    let mut t = Vec::new();
+    transcript.write(&mut t).unwrap();
+    let mut tampered = Transcript::<Bn_382>::read(&t[..]).unwrap();
+    tampered.contributions[1].hx = loop
+    {
+        if let Some(p) = G2Affine::get_point_from_x(Fq2::rand(rng), false) {if !in_subgroup(&p) {break p}}
+    };
+    assert_eq!(tampered.verify_transcript(&urs, rng), Err(CeremonyError::Subgroup(1)));
+    let mut t = Vec::new();
+    tampered.write(&mut t).unwrap();
+    assert!(Transcript::<Bn_382>::read(&t[..]).is_err());
+}
+
+fn test<E: PairingEngine>()
+{
+    let depth = 30;
+    let participants = 3;
+    let rng = &mut OsRng;
+
+    println!("{}", "Simulating the URS ceremony".green());
+    let mut start = Instant::now();
+    let (transcript, urs) = simulate::<E>(depth, vec![3,7], participants, rng);
+    println!("{}{:?}", "Execution time: ".yellow(), start.elapsed());
+
+    // serialization round trip
+    let mut t = Vec::new();
+    transcript.write(&mut t).unwrap();
+    let transcript = Transcript::<E>::read(&t[..]).unwrap();
+    let mut u = Vec::new();
+    urs.write(&mut u).unwrap();
+    let urs = URS::<E>::read(&u[..]).unwrap();
+    assert_eq!(transcript.contributions.len(), participants);
+
+    println!("{}", "Verifying the transcript".green());
+    start = Instant::now();
+    assert_eq!(transcript.verify_transcript(&urs, rng), Ok(()));
+    println!("{}{:?}", "Execution time: ".yellow(), start.elapsed());
+
+    // the empty transcript verifies the initial URS only
+    let empty = Transcript::<E>::new(depth, vec![3,7]);
+    assert_eq!(empty.verify_transcript(&empty.initial(), rng), Ok(()));
+    assert_eq!(empty.verify_transcript(&urs, rng), Err(CeremonyError::FinalMismatch));
+
+    // the truncated transcript does not match the final URS
+    let mut truncated = Transcript::<E>::read(&t[..]).unwrap();
+    truncated.contributions.pop();
+    assert_eq!(truncated.verify_transcript(&urs, rng), Err(CeremonyError::FinalMismatch));
+
+    // the tampered update scalar commitment breaks the proof of knowledge
+    let mut tampered = Transcript::<E>::read(&t[..]).unwrap();
+    tampered.contributions[1].prf = (tampered.contributions[1].prf.into_projective() + &E::G1Affine::prime_subgroup_generator().into_projective()).into_affine();
+    assert_eq!(tampered.verify_transcript(&urs, rng), Err(CeremonyError::ProofOfKnowledge(1)));
+
+    // the contribution replayed at another position of the transcript is rejected
+    let mut replayed = Transcript::<E>::read(&t[..]).unwrap();
+    let c = replayed.contributions[0].clone();
+    replayed.contributions.insert(1, c);
+    assert_eq!(replayed.verify_transcript(&urs, rng), Err(CeremonyError::ProofOfKnowledge(1)));
+
+    // the URS inconsistent with the transcript is rejected
+    let mut wrong = urs.clone();
+    wrong.gp[2] = wrong.gp[3];
+    assert_eq!(transcript.verify_transcript(&wrong, rng), Err(CeremonyError::URSCheck));
+
+    // the counts out of range are rejected before the allocation
+    let counts = |depth: u64, degrees: &[u64], contributions: u64|
+    {
+        let mut t = Vec::new();
+        t.extend_from_slice(&depth.to_le_bytes());
+        t.extend_from_slice(&(degrees.len() as u64).to_le_bytes());
+        degrees.iter().for_each(|d| t.extend_from_slice(&d.to_le_bytes()));
+        t.extend_from_slice(&contributions.to_le_bytes());
+        Transcript::<E>::read(&t[..]).is_err()
+    };
+    assert!(!counts(depth as u64, &[3, 7], 0));
+    assert!(counts(MAX_DEPTH as u64 + 1, &[3, 7], 0));
+    assert!(counts(depth as u64, &[3, depth as u64 + 1], 0));
+    assert!(counts(depth as u64, &[3, 7], MAX_CONTRIBUTIONS as u64 + 1));
+    assert!(counts(depth as u64, &[3, 7], u64::MAX));
+    let mut t = Vec::new();
+    t.extend_from_slice(&(depth as u64).to_le_bytes());
+    t.extend_from_slice(&u64::MAX.to_le_bytes());
+    assert!(Transcript::<E>::read(&t[..]).is_err());
+}
@@ -0,0 +1,102 @@
+/*********************************************************************************************************
+
+This source file tests the Plonk protocol generic over the polynomial commitment scheme with the pairing
+(KZG) commitment backend over Bn_382. The circuit computes:
+
+1. Poseidon hash function permutation of the public input via custom Plonk constraints
+
+2. short Weierstrass curve y^2 = x^3 + 7 (the Bn_382 cycle curve over Fp) group addition and point doubling
+
+3. the same curve group endomorphism optimised variable base scalar multiplication
+
+**********************************************************************************************************/
+
+use plonk_circuits::gadgets::{builder::{CircuitBuilder, Circuit}, poseidon::SpongeGadget, endo};
+use oracle::{poseidon::{ArithmeticSponge, ArithmeticSpongeParams, Sponge, SpongeConstants, PlonkSpongeConstants as SC}, sponge::{DefaultFqSponge, DefaultFrSponge}};
+use commitment_pairing::urs::URS;
+use plonk_protocol::{prover::ProverProof, index::Index};
+use algebra::{bn_382::{Fp, Fq, Bn_382, g::Affine as GAffine, g1::Bn_382G1Parameters}, AffineCurve, ProjectiveCurve, PairingEngine, One, Zero, UniformRand};
+use std::sync::Arc;
+use rand_core::OsRng;
+use rand::Rng;
+
+type G1Affine = <Bn_382 as PairingEngine>::G1Affine;
+
+fn circuit(endo: Fp) -> Circuit<Fp>
+{
+    let rng = &mut OsRng;
+    let params: ArithmeticSpongeParams<Fp> = oracle::bn_382::fp::params();
+    let mut cb = CircuitBuilder::<Fp>::new();
+
+    // Poseidon hash of the public input
+    let x = Fp::rand(rng);
+    let xv = cb.witness(x);
+    cb.public(xv);
+    let mut sponge = SpongeGadget::new(&mut cb, params.clone());
+    sponge.absorb(&mut cb, &[xv]);
+    let h = sponge.squeeze(&mut cb);
+    let mut native = ArithmeticSponge::<Fp, SC>::new();
+    native.absorb(&params, &[x]);
+    assert_eq!(cb.value(h), native.squeeze(&params));
+    cb.public(h);
+
+    // EC addition and doubling
+    let (t, q) = (GAffine::prime_subgroup_generator().mul(Fq::rand(rng)).into_affine(),
+        GAffine::prime_subgroup_generator().mul(Fq::rand(rng)).into_affine());
+    let tv = (cb.witness(t.x), cb.witness(t.y));
+    let qv = (cb.witness(q.x), cb.witness(q.y));
+    let sum = endo::add(&mut cb, tv, qv);
+    let s = (t.into_projective() + &q.into_projective()).into_affine();
+    assert_eq!((cb.value(sum.0), cb.value(sum.1)), (s.x, s.y));
+    let dbl = endo::double(&mut cb, qv);
+    cb.public(dbl.0);
+
+    // group endomorphism optimised scalar multiplication
+    let bits = (0..8).map
+    (
+        |_|
+        {
+            let b = cb.witness(if rng.gen::<bool>() {Fp::one()} else {Fp::zero()});
+            cb.boolean(b);
+            b
+        }
+    ).collect::<Vec<_>>();
+    let r = endo::endo_scale(&mut cb, endo, tv, &bits);
+    cb.public(r.0);
+
+    cb.finalize()
+}
+
+#[test]
+fn generic_plonk_pairing()
+{
+    let endo = oracle::sponge::endo_coefficient::<Fp>();
+    let circuit = circuit(endo);
+    let cs = circuit.constraint_system(oracle::bn_382::fp::params()).unwrap();
+
+    // the URS supports the degree bound of the last quotient segment, the witness polynomials
+    // being blinded with the multiples of the vanishing polynomial for the non-hiding URS
+    let n = cs.domain.d1.size();
+    let urs = Arc::new(URS::<Bn_382>::create(n, vec![SC::SPONGE_BOX * (n + 1) % n], &mut OsRng));
+
+    let index = Index::<G1Affine, URS<Bn_382>>::create(cs, oracle::bn_382::fq::params(), endo, urs);
+    assert_eq!(index.cs.verify(&circuit.witness), true);
+    let verifier_index = index.verifier_index();
+
+    let batch = (0..2).map
+    (
+        |_| ProverProof::create::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&circuit.witness, &index, vec![]).unwrap()
+    ).collect::<Vec<_>>();
+    let proofs = batch.iter().map(|p| (&verifier_index, p)).collect::<Vec<_>>();
+    assert!(ProverProof::verify::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&proofs).is_ok());
+
+    // the tampered public input is rejected
+    let mut proof = batch[0].clone();
+    proof.public[0] += &Fp::one();
+    assert!(ProverProof::verify::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&vec![(&verifier_index, &proof)]).is_err());
+
+    // the tampered quotient evaluation is rejected
+    let mut proof = batch[0].clone();
+    proof.evals[0].t[0] += &Fp::one();
+    assert!(ProverProof::verify::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&vec![(&verifier_index, &proof)]).is_err());
+}
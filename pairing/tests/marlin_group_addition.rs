@@ -22,7 +22,7 @@ of non-special pairs of points
 
 use sprs::{CsMat, CsVecView};
 use oracle::{poseidon::{ArithmeticSpongeParams, MarlinSpongeConstants as SC}, sponge::{DefaultFqSponge, DefaultFrSponge}};
-use marlin_protocol_pairing::{prover::{ProverProof}, index::{Index, URSSpec}};
+use marlin_protocol_pairing::{prover::{ProverProof}, index::{Index, VerifierIndex, URSSpec}};
 use algebra::{bn_382::{Fp, Bn_382, g1::Bn_382G1Parameters}, One, Zero};
 use rand_core::{RngCore, OsRng};
 use std::{io, io::Write};
@@ -217,6 +217,34 @@ where <Fp as std::str::FromStr>::Err : std::fmt::Debug
         _ => {panic!("Failure verifying the prover's proof")}
     }
 
+    // the verification plan checks natively and rejects the tampered evaluation
+    let plan = batch[0].plan::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&verifier_index, rng).unwrap();
+    assert!(plan.verify());
+    assert!(plan.challenge.is_some());
+    let mut proof = batch[0].clone();
+    proof.evals.h2 += &Fp::one();
+    let plan = proof.plan::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&verifier_index, rng).unwrap();
+    assert!(!plan.verify());
+
+    // the compressed encodings round trip, the proof size being fixed by the number of public inputs
+    let (g1_size, fr_size) = (48, 48);
+    let mut bytes = Vec::new();
+    batch[0].write(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 2 + 14 * g1_size + 1 + 4 + (23 + batch[0].public.len()) * fr_size);
+
+    let mut index_bytes = Vec::new();
+    verifier_index.write(&mut index_bytes).unwrap();
+    let decoded_index = VerifierIndex::<Bn_382>::read(&index_bytes[..], oracle::bn_382::fp::params(), oracle::bn_382::fq::params()).unwrap();
+    let decoded = ProverProof::<Bn_382>::read(&bytes[..]).unwrap();
+    match ProverProof::verify::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&vec![decoded], &decoded_index, rng)
+    {
+        Ok(_) => {}
+        _ => {panic!("Failure verifying the decoded prover's proof")}
+    }
+    let mut corrupted = bytes.clone();
+    corrupted[0] += 1;
+    assert!(ProverProof::<Bn_382>::read(&corrupted[..]).is_err());
+
     // verify the proofs in batch
     println!("{}", "Verifier zk-proofs verification".green());
     start = Instant::now();
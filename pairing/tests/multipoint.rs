@@ -0,0 +1,68 @@
+/*****************************************************************************************************************
+
+This source file tests the multi-point batched opening of the polynomial commitments against the URS. The
+polynomials of the batch are opened at different sets of points, the batch of the opening proofs being verified
+together with the degree bound of a shifted commitment.
+
+*****************************************************************************************************************/
+
+use algebra::{bn_382::{Fp, Bn_382, g1::Bn_382G1Parameters}, UniformRand, One};
+use oracle::{poseidon::MarlinSpongeConstants as SC, sponge::DefaultFqSponge, FqSponge};
+use commitment_pairing::urs::URS;
+use ff_fft::DensePolynomial;
+use rand_core::OsRng;
+
+type EFqSponge = DefaultFqSponge<Bn_382G1Parameters, SC>;
+
+#[test]
+fn multipoint_opening()
+{
+    let rng = &mut OsRng;
+    let depth = 64;
+    let bound = 20;
+    let urs = URS::<Bn_382>::create(depth, vec![depth - bound], rng);
+    let sponge = EFqSponge::new(oracle::bn_382::fq::params());
+
+    let a = DensePolynomial::<Fp>::rand(bound - 1, rng);
+    let b = DensePolynomial::<Fp>::rand(depth - 1, rng);
+    let c = DensePolynomial::<Fp>::rand(bound - 1, rng);
+    let (a_comm, b_comm) = (urs.commit(&a).unwrap(), urs.commit(&b).unwrap());
+    let (c_comm, c_shifted) = urs.commit_with_degree_bound(&c, bound).unwrap();
+    let (x, y, z, gamma) = (Fp::rand(rng), Fp::rand(rng), Fp::rand(rng), Fp::rand(rng));
+
+    // polynomials opened at different sets of points
+    let proof = urs.open_multipoint
+    (
+        vec![(&a, vec![x, y]), (&b, vec![y]), (&c, vec![z])],
+        gamma,
+        sponge.clone()
+    ).unwrap();
+    assert!(proof.w1.is_some());
+
+    // polynomials opened at the same single point
+    let single = urs.open_multipoint(vec![(&a, vec![x]), (&b, vec![x])], gamma, sponge.clone()).unwrap();
+    assert!(single.w1.is_none());
+
+    let multi = vec!
+    [
+        (a_comm, vec![(x, a.evaluate(x)), (y, a.evaluate(y))], None),
+        (b_comm, vec![(y, b.evaluate(y))], None),
+        (c_comm, vec![(z, c.evaluate(z))], Some((c_shifted, bound))),
+    ];
+    let mut batch = vec!
+    [
+        (sponge.clone(), gamma, multi.clone(), &proof),
+        (sponge.clone(), gamma, vec![(a_comm, vec![(x, a.evaluate(x))], None), (b_comm, vec![(x, b.evaluate(x))], None)], &single),
+    ];
+    assert!(urs.verify_multipoint(&mut batch, rng));
+
+    // the tampered evaluation is rejected
+    let mut tampered = multi.clone();
+    tampered[0].1[1].1 += &Fp::one();
+    assert!(!urs.verify_multipoint(&mut vec![(sponge.clone(), gamma, tampered, &proof)], rng));
+
+    // the shifted commitment not matching the unshifted one is rejected
+    let mut tampered = multi.clone();
+    tampered[2].2 = Some((a_comm, bound));
+    assert!(!urs.verify_multipoint(&mut vec![(sponge.clone(), gamma, tampered, &proof)], rng));
+}
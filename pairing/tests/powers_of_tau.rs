@@ -0,0 +1,136 @@
+/*****************************************************************************************************************
+
+This source file implements the powers of tau import test suite driver. The following tests are implemented:
+
+1. powers_of_tau_test
+   This unit test writes the powers of tau transcript for a random tau, imports the URS from it,
+   verifies the round trip through the URS serialization and checks that the unsupported degrees,
+   too short and inconsistent transcripts and the malformed or off-curve points are rejected.
+
+*****************************************************************************************************************/
+
+use algebra::{PairingEngine, bn_382::Bn_382, AffineCurve, ProjectiveCurve, ToBytes, Field, PrimeField, FpParameters, Zero, UniformRand};
+use commitment_pairing::urs::{URS, PowersOfTauError, POWERS_OF_TAU_HASH_SIZE};
+use rand_core::OsRng;
+
+const POWER: usize = 3;
+
+#[test]
+fn powers_of_tau_test()
+{
+    test::<Bn_382>();
+}
+
+// This function writes the point in the powers of tau encoding: x || y big-endian, the highest base
+// field component of the coordinates first, the point at infinity flagged with 0x40 in the first byte
+fn write<E: PairingEngine, C: AffineCurve>(p: C, t: &mut Vec<u8>)
+{
+    let n = (<E::Fq as PrimeField>::Params::MODULUS_BITS as usize + 7) / 8;
+    let mut m = Vec::new();
+    E::Fq::zero().write(&mut m).unwrap();
+    let m = m.len();
+
+    // x, y little-endian components and the infinity flag
+    let mut bytes = Vec::new();
+    p.write(&mut bytes).unwrap();
+    let components = (bytes.len() - 1) / (2 * m);
+    let start = t.len();
+    for coordinate in bytes[0..2 * components * m].chunks(components * m)
+    {
+        for component in coordinate.chunks(m).rev()
+        {
+            t.extend(component[0..n].iter().rev());
+        }
+    }
+    if p.is_zero()
+    {
+        for b in t[start..].iter_mut() {*b = 0}
+        t[start] = 0x40;
+    }
+}
+
+// This function writes the powers of tau transcript
+fn transcript<E: PairingEngine>(tau: E::Fr, corrupt: Option<usize>) -> Vec<u8>
+{
+    let mut t = vec![0u8; POWERS_OF_TAU_HASH_SIZE];
+    let (g, h) = (E::G1Affine::prime_subgroup_generator(), E::G2Affine::prime_subgroup_generator());
+    for i in 0..(1 << (POWER + 1)) - 1
+    {
+        let mut x = tau.pow([i as u64]);
+        if corrupt == Some(i) {x.double_in_place();}
+        write::<E, _>(g.mul(x).into_affine(), &mut t);
+    }
+    for i in 0..1 << POWER {write::<E, _>(h.mul(tau.pow([i as u64])).into_affine(), &mut t);}
+    // alpha, beta powers
+    for i in 0..2 << POWER {write::<E, _>(g.mul(tau.pow([i as u64])).into_affine(), &mut t);}
+    write::<E, _>(h, &mut t);
+    t
+}
+
+fn test<E: PairingEngine>()
+{
+    let rng = &mut OsRng;
+    let tau = E::Fr::rand(rng);
+    let depth = 10;
+    let t = transcript::<E>(tau, None);
+    // byte size of the G1 point encoding
+    let g1 = 2 * ((<E::Fq as PrimeField>::Params::MODULUS_BITS as usize + 7) / 8);
+
+    let urs = URS::<E>::from_powers_of_tau(&t[..], POWER, depth, vec![depth], rng).unwrap();
+    assert_eq!(urs.gp.len(), depth);
+    for i in 0..depth {assert_eq!(urs.gp[i], E::G1Affine::prime_subgroup_generator().mul(tau.pow([i as u64])).into_affine());}
+    assert_eq!(urs.hx, E::G2Affine::prime_subgroup_generator().mul(tau).into_affine());
+    assert_eq!(urs.hn.len(), 1);
+
+    // URS serialization round trip
+    let mut u = Vec::new();
+    urs.write(&mut u).unwrap();
+    let read = URS::<E>::read(&u[..]).unwrap();
+    assert!(read.gp == urs.gp && read.hn == urs.hn && read.hx == urs.hx && read.prf == urs.prf);
+
+    // the whole G1 powers
+    assert!(URS::<E>::from_powers_of_tau(&t[..], POWER, (2 << POWER) - 1, vec![], rng).is_ok());
+
+    match URS::<E>::from_powers_of_tau(&t[..], POWER, depth, vec![depth - 3], rng)
+    {
+        Err(PowersOfTauError::InversePowers(3)) => {}
+        _ => panic!("negative powers of tau have to be rejected")
+    }
+    match URS::<E>::from_powers_of_tau(&t[..], POWER, 2 << POWER, vec![], rng)
+    {
+        Err(PowersOfTauError::Size) => {}
+        _ => panic!("too short transcript has to be rejected")
+    }
+    match URS::<E>::from_powers_of_tau(&t[0..POWERS_OF_TAU_HASH_SIZE + g1 * depth / 2], POWER, depth, vec![], rng)
+    {
+        Err(PowersOfTauError::Io(_)) => {}
+        _ => panic!("truncated transcript has to be rejected")
+    }
+    match URS::<E>::from_powers_of_tau(&transcript::<E>(tau, Some(depth - 2))[..], POWER, depth, vec![], rng)
+    {
+        Err(PowersOfTauError::Check) => {}
+        _ => panic!("inconsistent transcript has to be rejected")
+    }
+    match URS::<E>::from_powers_of_tau(&transcript::<E>(tau, Some(0))[..], POWER, depth, vec![], rng)
+    {
+        Err(PowersOfTauError::Generator) => {}
+        _ => panic!("transcript not starting from the generator has to be rejected")
+    }
+
+    // g^x off the curve, the lowest bit of its y being flipped
+    let mut off = t.clone();
+    off[POWERS_OF_TAU_HASH_SIZE + 2 * g1 - 1] ^= 1;
+    match URS::<E>::from_powers_of_tau(&off[..], POWER, depth, vec![], rng)
+    {
+        Err(PowersOfTauError::Point(1)) => {}
+        _ => panic!("point off the curve has to be rejected")
+    }
+    // compressed encoding of g^x
+    let mut compressed = t.clone();
+    compressed[POWERS_OF_TAU_HASH_SIZE + g1] |= 0x80;
+    match URS::<E>::from_powers_of_tau(&compressed[..], POWER, depth, vec![], rng)
+    {
+        Err(PowersOfTauError::Point(1)) => {}
+        _ => panic!("compressed point encoding has to be rejected")
+    }
+}
@@ -0,0 +1,211 @@
+/*********************************************************************************************************
+
+This source file tests the pairing Plonk protocol over Bn_382 with the full turbo-Plonk gate set:
+
+1. Poseidon hash function permutation via custom Plonk constraints
+
+2. short Weierstrass curve y^2 = x^3 + 7 (the Bn_382 cycle curve over Fp) group addition via custom
+   Plonk constraints and point doubling via generic Plonk constraints
+
+3. short Weierstrass curve variable base scalar multiplication via custom Plonk constraints
+
+4. short Weierstrass curve group endomorphism optimised variable base scalar multiplication via
+   custom Plonk constraints
+
+5. scalar challenge to endomorphism scalar conversion via the registered endoscalar custom gate
+
+The circuit is built with the same CircuitBuilder gadgets as the ones proven with the dlog backend. The proof
+and the verifier index are also checked to round trip through their compressed encoding.
+
+**********************************************************************************************************/
+
+use plonk_circuits::{gate::GateType, gadgets::{builder::{CircuitBuilder, Circuit}, poseidon::SpongeGadget, endo::{self, Point}, endoscalar::to_field}};
+use oracle::{poseidon::{ArithmeticSponge, ArithmeticSpongeParams, Sponge, SpongeConstants, PlonkSpongeConstants as SC}, sponge::{DefaultFqSponge, DefaultFrSponge}};
+use plonk_protocol_pairing::{prover::{ProverProof, ProofEncoding}, index::{Index, VerifierIndex, VerifierIndexEncoding}};
+use commitment_pairing::urs::URS;
+use algebra::{bn_382::{Fp, Fq, Bn_382, g::Affine as GAffine, g1::Bn_382G1Parameters}, AffineCurve, ProjectiveCurve, Field, One, Zero, UniformRand};
+use std::time::Instant;
+use std::sync::Arc;
+use colored::Colorize;
+use rand_core::OsRng;
+use rand::Rng;
+
+// This function constrains the variable base scalar multiplication step for the bit b
+//     t: point to multiply
+//     p: accumulator point
+//     RETURN: (P + Q) + P, Q = (xT, (2b - 1) * yT)
+fn vbmul(cb: &mut CircuitBuilder<Fp>, t: Point, p: Point, b: bool) -> Point
+{
+    let (xt, yt, xp, yp) = (cb.value(t.0), cb.value(t.1), cb.value(p.0), cb.value(p.1));
+    let yq = if b {yt} else {-yt};
+    let s1 = (yp - &yq) / &(xp - &xt);
+    let xr = s1.square() - &xp - &xt;
+    let yr = s1 * &(xp - &xr) - &yp;
+    let s2 = (yp - &yr) / &(xp - &xr);
+    let xs = s2.square() - &xr - &xp;
+    let ys = s2 * &(xp - &xs) - &yp;
+
+    let a = cb.row(GateType::Vbmul1, Vec::new(), [xt, if b {Fp::one()} else {Fp::zero()}, yt]);
+    let c = cb.row(GateType::Vbmul2, Vec::new(), [xp, s1, yp]);
+    let d = cb.row(GateType::Vbmul3, Vec::new(), [xs, xt, ys]);
+    cb.copy(a[0], t.0);
+    cb.copy(a[2], t.1);
+    cb.copy(c[0], p.0);
+    cb.copy(c[2], p.1);
+    cb.copy(d[1], t.0);
+    (d[0], d[2])
+}
+
+fn point(rng: &mut OsRng) -> GAffine
+{
+    GAffine::prime_subgroup_generator().mul(Fq::rand(rng)).into_affine()
+}
+
+fn value(cb: &CircuitBuilder<Fp>, p: Point) -> (Fp, Fp) {(cb.value(p.0), cb.value(p.1))}
+
+// This function builds the circuit exercising all the gates
+fn circuit(endo: Fp) -> Circuit<Fp>
+{
+    let rng = &mut OsRng;
+    let params: ArithmeticSpongeParams<Fp> = oracle::bn_382::fp::params();
+    let mut cb = CircuitBuilder::<Fp>::new();
+
+    // Poseidon hash of the public input
+    let x = Fp::rand(rng);
+    let xv = cb.witness(x);
+    cb.public(xv);
+    let mut sponge = SpongeGadget::new(&mut cb, params.clone());
+    sponge.absorb(&mut cb, &[xv]);
+    let h = sponge.squeeze(&mut cb);
+    let mut native = ArithmeticSponge::<Fp, SC>::new();
+    native.absorb(&params, &[x]);
+    assert_eq!(cb.value(h), native.squeeze(&params));
+    cb.public(h);
+
+    // EC addition and doubling
+    let (t, q) = (point(rng), point(rng));
+    let tv = (cb.witness(t.x), cb.witness(t.y));
+    let qv = (cb.witness(q.x), cb.witness(q.y));
+    let sum = endo::add(&mut cb, tv, qv);
+    let s = (t.into_projective() + &q.into_projective()).into_affine();
+    assert_eq!(value(&cb, sum), (s.x, s.y));
+    let dbl = endo::double(&mut cb, tv);
+    let d = t.into_projective().double().into_affine();
+    assert_eq!(value(&cb, dbl), (d.x, d.y));
+
+    // variable base scalar multiplication steps
+    let mut acc = qv;
+    let mut expected = q.into_projective();
+    for b in [true, false, true].iter()
+    {
+        acc = vbmul(&mut cb, tv, acc, *b);
+        expected = expected.double() + &(if *b {t.into_projective()} else {-t.into_projective()});
+    }
+    let e = expected.into_affine();
+    assert_eq!(value(&cb, acc), (e.x, e.y));
+
+    // group endomorphism optimised scalar multiplication
+    let bits = (0..8).map
+    (
+        |_|
+        {
+            let b = cb.witness(if rng.gen::<bool>() {Fp::one()} else {Fp::zero()});
+            cb.boolean(b);
+            b
+        }
+    ).collect::<Vec<_>>();
+    let r = endo::endo_scale(&mut cb, endo, tv, &bits);
+    cb.public(r.0);
+
+    // scalar challenge conversion with the registered custom gate
+    let two: Fp = (2 as u64).into();
+    let c = cb.witness(Fp::from(rng.gen::<u64>()) * &two.pow(&[64]) + &Fp::from(rng.gen::<u64>()));
+    let y = to_field(&mut cb, endo, c);
+    cb.public(y);
+
+    cb.finalize()
+}
+
+#[test]
+fn turbo_plonk_pairing()
+{
+    let rng = &mut OsRng;
+    let endo = oracle::sponge::endo_coefficient::<Fp>();
+    let circuit = circuit(endo);
+
+    let cs = circuit.constraint_system(oracle::bn_382::fp::params()).unwrap();
+
+    // the URS of the circuit size supports the degree bound of the last quotient segment, the witness
+    // polynomials being blinded with the multiples of the vanishing polynomial
+    let n = cs.domain.d1.size();
+    let urs = Arc::new(URS::<Bn_382>::create(n, vec![SC::SPONGE_BOX * (n + 1) % n], rng));
+
+    let index = Index::<Bn_382>::create(cs, oracle::bn_382::fq::params(), endo, urs);
+    assert_eq!(index.cs.verify(&circuit.witness), true);
+    let verifier_index = index.verifier_index();
+
+    println!("{}", "Prover zk-proofs computation".green());
+    let start = Instant::now();
+    let batch = (0..4).map
+    (
+        |_| ProverProof::create::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&circuit.witness, &index, vec![]).unwrap()
+    ).collect::<Vec<_>>();
+    println!("{}{:?}", "Execution time: ".yellow(), start.elapsed());
+
+    println!("{}", "Verifier zk-proofs verification".green());
+    let start = Instant::now();
+    let proofs = batch.iter().map(|p| (&verifier_index, p)).collect::<Vec<_>>();
+    match ProverProof::verify::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&proofs)
+    {
+        Err(error) => {panic!("Failure verifying the prover's proofs in batch: {}", error)},
+        Ok(_) => {println!("{}{:?}", "Execution time: ".yellow(), start.elapsed());}
+    }
+
+    // the compressed encodings round trip, the proof size being fixed by the circuit: the blinded witness,
+    // permutation and linearization polynomials take two segments of the URS depth, the sigma polynomials
+    // one and the quotient the chunks of its maximal size with the shifted last one
+    let (g1_size, fr_size) = (48, 48);
+    let chunks = (verifier_index.max_quot_size + n - 1) / n;
+    let comms = 4 * (4 + 2 * g1_size + 1) + 4 + (chunks + 1) * g1_size + 1;
+    let evals = 2 * (8 * 4 + (2 * 5 + 2 + chunks) * fr_size);
+    let mut bytes = Vec::new();
+    batch[0].write(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 2 + comms + 2 * g1_size + 1 + evals + 4 + batch[0].public.len() * fr_size + 4);
+
+    let mut index_bytes = Vec::new();
+    verifier_index.write(&mut index_bytes).unwrap();
+    let decoded_index = VerifierIndex::<Bn_382>::read
+    (
+        &index_bytes[..],
+        verifier_index.custom.clone(),
+        oracle::bn_382::fp::params(),
+        oracle::bn_382::fq::params()
+    ).unwrap();
+    let decoded = ProverProof::<Bn_382>::read(&bytes[..]).unwrap();
+    assert!(ProverProof::verify::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&vec![(&decoded_index, &decoded)]).is_ok());
+
+    // the unknown encoding version and the corrupted commitment are rejected
+    let mut corrupted = bytes.clone();
+    corrupted[1] += 1;
+    assert!(ProverProof::<Bn_382>::read(&corrupted[..]).is_err());
+    let mut corrupted = bytes.clone();
+    corrupted[6] ^= 1;
+    assert!(ProverProof::<Bn_382>::read(&corrupted[..]).map_or
+        (true, |p| ProverProof::verify::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&vec![(&verifier_index, &p)]).is_err()));
+
+    // the tampered next row evaluation breaks the linearization consistency
+    let mut proof = batch[0].clone();
+    proof.evals[1].l[0] += &Fp::one();
+    assert!(ProverProof::verify::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&vec![(&verifier_index, &proof)]).is_err());
+
+    // the dropped quotient chunk is rejected
+    let mut proof = batch[0].clone();
+    proof.commitments.t_comm.unshifted.pop();
+    assert!(ProverProof::verify::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&vec![(&verifier_index, &proof)]).is_err());
+
+    // the non-satisfying witness is rejected by the prover
+    let mut witness = circuit.witness.clone();
+    witness[0] += &Fp::one();
+    assert_eq!(index.cs.verify(&witness), false);
+    assert!(ProverProof::create::<DefaultFqSponge<Bn_382G1Parameters, SC>, DefaultFrSponge<Fp, SC>>(&witness, &index, vec![]).is_err());
+}
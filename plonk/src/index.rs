@@ -0,0 +1,176 @@
+/*****************************************************************************************************************
+
+This source file implements Plonk Protocol Index primitive generic over the polynomial commitment scheme.
+
+*****************************************************************************************************************/
+
+use ff_fft::{DensePolynomial, Evaluations, Radix2EvaluationDomain as D};
+use oracle::poseidon::{ArithmeticSpongeParams, SpongeConstants, PlonkSpongeConstants};
+use poly_commitment::{commitment::{PolyComm, PolynomialCommitment}, fixed::PolyCommTable};
+use plonk_circuits::constraints::{zk_w, ConstraintSystem, GateRegistry, Selector};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use rayon::prelude::*;
+use algebra::AffineCurve;
+
+type Fr<G> = <G as AffineCurve>::ScalarField;
+type Fq<G> = <G as AffineCurve>::BaseField;
+
+pub struct Index<G: AffineCurve, PC: PolynomialCommitment<G>>
+{
+    // constraints system polynoms
+    pub cs: ConstraintSystem<Fr<G>>,
+
+    // polynomial commitment keys
+    pub pcs: Arc<PC>,
+
+    // maximal size of polynomial section
+    pub max_poly_size: usize,
+
+    // maximal size of the quotient polynomial according to the supported constraints
+    pub max_quot_size: usize,
+
+    // random oracle argument parameters
+    pub fq_sponge_params: ArithmeticSpongeParams<Fq<G>>,
+}
+
+pub struct VerifierIndex<G: AffineCurve, PC: PolynomialCommitment<G>>
+{
+    pub domain: D<Fr<G>>,               // evaluation domain
+    pub max_poly_size: usize,           // maximal size of polynomial section
+    pub max_quot_size: usize,           // maximal size of the quotient polynomial according to the supported constraints
+    pub pcs: Arc<PC>,                   // polynomial commitment keys
+
+    // index polynomial commitments
+    pub sigma_comm: [PolyComm<G>; 3],   // permutation commitment array
+
+    // built-in gate selector polynomial commitments keyed by the selector, see Selector
+    pub sel_comm:   BTreeMap<Selector, PolyComm<G>>,
+
+    // custom gate polynomial commitments
+    pub custom:     GateRegistry<Fr<G>>,// registered custom gates
+    pub custom_comm:BTreeMap<usize, Vec<PolyComm<G>>>, // custom gate selector polynomial commitments keyed by the gate id
+
+    // fixed-base tables of the linearization polynomial commitments, if precomputed, see precompute_tables.
+    // The verifier combines the commitments themselves if the tables are not of them
+    pub tables:     Option<Vec<PolyCommTable<G>>>,
+
+    pub r:          Fr<G>,              // coordinate shift for right wires
+    pub o:          Fr<G>,              // coordinate shift for output wires
+    pub zkpm:       DensePolynomial<Fr<G>>, // zero-knowledge polynomial
+    pub w:          Fr<G>,              // root of unity for zero-knowledge
+    pub endo:       Fr<G>,              // endoscalar coefficient
+
+    // random oracle argument parameters
+    pub fr_sponge_params: ArithmeticSpongeParams<Fr<G>>,
+    pub fq_sponge_params: ArithmeticSpongeParams<Fq<G>>,
+}
+
+// This function commits to the public input polynomial, from its evaluations against the Lagrange basis
+// precomputed by Index::create if the scheme supports it, interpolating it otherwise
+//     pcs: polynomial commitment keys
+//     domain: circuit evaluation domain
+//     public: public part of the witness
+//     RETURN: public input polynomial commitment
+pub fn public_comm<G: AffineCurve, PC: PolynomialCommitment<G>>(pcs: &PC, domain: D<Fr<G>>, public: &Vec<Fr<G>>) -> PolyComm<G>
+{
+    let evals = Evaluations::<Fr<G>, D<Fr<G>>>::from_vec_and_domain(public.iter().map(|s| -*s).collect(), domain);
+    pcs.commit_evaluations_non_hiding(&evals).unwrap_or_else(|| pcs.commit_non_hiding(&evals.interpolate(), None))
+}
+
+impl<G: AffineCurve, PC: PolynomialCommitment<G>> VerifierIndex<G, PC>
+{
+    // This function returns the index polynomial commitments of the linearization polynomial
+    // in the order of their linearization scalars, following the permutation polynomial commitment
+    pub fn linearization_comm(&self) -> Vec<&PolyComm<G>>
+    {
+        let mut p = vec!
+        [
+            // permutation polynomial commitments
+            &self.sigma_comm[2],
+        ];
+        // built-in gate selector polynomial commitments, keyed in the order of their scalars
+        p.extend(self.sel_comm.values());
+        // custom constraint polynomial commitments
+        p.extend(self.custom_comm.values().flatten());
+        p
+    }
+
+    // This function precomputes the fixed-base tables of the linearization polynomial commitments
+    // for the verifier to combine them without the doublings, worth it when verifying many proofs
+    // against the index. The tables take (2^window - 1) ceil(b/window) points per commitment segment,
+    // b the bit size of the scalar field
+    //     window: window bit size of the tables
+    pub fn precompute_tables(&mut self, window: usize)
+    {
+        self.tables = Some(self.linearization_comm().par_iter().map(|c| PolyCommTable::create(c, window)).collect());
+    }
+}
+
+impl<G: AffineCurve, PC: PolynomialCommitment<G>> Index<G, PC>
+{
+    pub fn verifier_index(&self) -> VerifierIndex<G, PC> {
+        let pcs = self.pcs.clone();
+        let commit = |p: &DensePolynomial<Fr<G>>| pcs.commit_non_hiding(p, None);
+
+        VerifierIndex
+        {
+            domain: self.cs.domain.d1,
+
+            sigma_comm: [commit(&self.cs.sigmam[0]), commit(&self.cs.sigmam[1]), commit(&self.cs.sigmam[2])],
+            sel_comm: self.cs.selm.iter().map(|(sel, p)| (*sel, commit(p))).collect(),
+
+            custom: self.cs.custom.clone(),
+            custom_comm: self.cs.customm.iter().map
+                (|(id, s)| (*id, s.iter().map(|s| commit(s)).collect())).collect(),
+            tables: None,
+
+            w: zk_w(self.cs.domain.d1),
+            fr_sponge_params: self.cs.fr_sponge_params.clone(),
+            fq_sponge_params: self.fq_sponge_params.clone(),
+            endo: self.cs.endo,
+            max_poly_size: self.max_poly_size,
+            max_quot_size: self.max_quot_size,
+            zkpm: self.cs.zkpm.clone(),
+            r: self.cs.r,
+            o: self.cs.o,
+            pcs: self.pcs.clone(),
+        }
+    }
+
+    // this function compiles the index from constraints
+    //     cs: constraint system
+    //     fq_sponge_params: parameters of the base field random oracle
+    //     endo: endoscalar coefficient of the group endomorphism optimised scalar multiplication constraints
+    //     pcs: polynomial commitment keys, possibly shared with other indexes
+    pub fn create
+    (
+        mut cs: ConstraintSystem<Fr<G>>,
+        fq_sponge_params: ArithmeticSpongeParams<Fq<G>>,
+        endo: Fr<G>,
+        pcs: Arc<PC>
+    ) -> Self
+    {
+        let max_poly_size = pcs.max_poly_size();
+        if cs.public > 0
+        {
+            // the verifier evaluates the public input polynomial with the Lagrange formula as a single segment
+            assert!(max_poly_size >= cs.domain.d1.size as usize, "polynomial segment size has to be not smaller that that of the circuit!");
+        }
+        cs.endo = endo;
+        // the witness columns and the public input are committed from their evaluations over the circuit domain,
+        // if the scheme supports it and the domain fits into a single segment
+        if cs.domain.d1.size as usize <= max_poly_size {pcs.add_lagrange_basis(cs.domain.d1)}
+        Index
+        {
+            // the witness polynomials of the non-hiding schemes are of degree n+1, being blinded with the
+            // multiples of the vanishing polynomial
+            max_quot_size: PlonkSpongeConstants::SPONGE_BOX *
+                (if pcs.hiding() {cs.domain.d1.size as usize - 1} else {cs.domain.d1.size as usize + 1}),
+            fq_sponge_params,
+            max_poly_size,
+            pcs,
+            cs,
+        }
+    }
+}
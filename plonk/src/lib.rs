@@ -0,0 +1,5 @@
+pub mod prover;
+pub mod verifier;
+pub mod plonk_sponge;
+pub mod index;
+pub mod range;
@@ -5,6 +5,8 @@ use algebra::{
 use oracle::poseidon::{ArithmeticSponge, ArithmeticSpongeParams, Sponge, PlonkSpongeConstants as SC};
 use oracle::sponge::{DefaultFrSponge, ScalarChallenge};
 
+// The challenges are squeezed as the scalar challenges, the commitment scheme converting them into the
+// field elements, see PolynomialCommitment::challenge
 pub trait FrSponge<Fr: Field> {
     fn new(p: ArithmeticSpongeParams<Fr>) -> Self;
     fn absorb(&mut self, x: &Fr);
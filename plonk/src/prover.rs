@@ -0,0 +1,489 @@
+/********************************************************************************************
+
+This source file implements prover's zk-proof primitive generic over the polynomial
+commitment scheme.
+
+*********************************************************************************************/
+
+use algebra::{Field, AffineCurve, Zero, One, UniformRand};
+use ff_fft::{DensePolynomial, DenseOrSparsePolynomial, Evaluations, Radix2EvaluationDomain as D};
+use oracle::{FqSponge, utils::PolyUtils, rndoracle::ProofError, sponge::ScalarChallenge};
+use poly_commitment::commitment::{CoordinatesCurve, PolyComm, PolynomialCommitment, b_poly_coefficients};
+use plonk_circuits::{scalars::{ProofEvaluations, RandomOracles}, constraints::ConstraintSystem};
+pub use super::{index::Index, range};
+use crate::index::public_comm;
+use crate::plonk_sponge::FrSponge;
+use rand::thread_rng;
+use rand_core::RngCore;
+use std::time::{Duration, Instant};
+
+type Fr<G> = <G as AffineCurve>::ScalarField;
+type Fq<G> = <G as AffineCurve>::BaseField;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "ocaml_types", derive(ocaml::ToValue, ocaml::FromValue))]
+pub struct ProverCommitments<G: AffineCurve>
+{
+    pub l_comm: PolyComm<G>,
+    pub r_comm: PolyComm<G>,
+    pub o_comm: PolyComm<G>,
+    pub z_comm: PolyComm<G>,
+    pub t_comm: PolyComm<G>,
+}
+
+#[cfg_attr(feature = "ocaml_types", derive(ocaml::ToValue, ocaml::FromValue))]
+struct CamlProverProof<G: AffineCurve, P>
+{
+    pub commitments: ProverCommitments<G>,
+    pub proof: P,
+    // OCaml doesn't have sized arrays, so we have to convert to a tuple..
+    pub evals: (ProofEvaluations<Vec<Fr<G>>>, ProofEvaluations<Vec<Fr<G>>>),
+    pub public: Vec<Fr<G>>,
+    pub prev_challenges: Vec<(Vec<Fr<G>>, PolyComm<G>)>,
+}
+
+// Prover time spent in the phases of the proof computation
+#[derive(Clone, Debug, Default)]
+pub struct ProverProfile
+{
+    pub commitments: Duration,      // witness, permutation and quotient polynomial interpolation and commitments
+    pub z: Duration,                // permutation polynomial computation
+    pub quotient: Duration,         // quotient polynomial computation
+    pub linearization: Duration,    // polynomial evaluations and linearization polynomial computation
+    pub opening: Duration,          // batched opening proof computation
+}
+
+impl ProverProfile
+{
+    pub fn total(&self) -> Duration
+    {
+        self.commitments + self.z + self.quotient + self.linearization + self.opening
+    }
+}
+
+pub struct ProverProof<G: AffineCurve, PC: PolynomialCommitment<G>>
+{
+    // polynomial commitments
+    pub commitments: ProverCommitments<G>,
+
+    // batched commitment opening proof
+    pub proof: PC::Proof,
+
+    // polynomial evaluations
+    pub evals: [ProofEvaluations<Vec<Fr<G>>>; 2],
+
+    // public part of the witness
+    pub public: Vec<Fr<G>>,
+
+    // The challenges underlying the optional polynomials folded into the proof
+    pub prev_challenges: Vec<(Vec<Fr<G>>, PolyComm<G>)>,
+}
+
+// implemented by hand so that the commitment scheme itself is not required to be cloneable
+impl<G: AffineCurve, PC: PolynomialCommitment<G>> Clone for ProverProof<G, PC>
+{
+    fn clone(&self) -> Self
+    {
+        ProverProof
+        {
+            commitments: self.commitments.clone(),
+            proof: self.proof.clone(),
+            evals: self.evals.clone(),
+            public: self.public.clone(),
+            prev_challenges: self.prev_challenges.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "ocaml_types")]
+unsafe impl<G: AffineCurve + ocaml::ToValue, PC: PolynomialCommitment<G>> ocaml::ToValue for ProverProof<G, PC> where
+    G::ScalarField: ocaml::ToValue, PC::Proof: ocaml::ToValue {
+    fn to_value(self) -> ocaml::Value {
+        ocaml::ToValue::to_value(
+            CamlProverProof{
+                commitments: self.commitments,
+                proof: self.proof,
+                evals: {
+                    let [evals0, evals1] = self.evals;
+                    (evals0, evals1)
+                },
+                public: self.public,
+                prev_challenges: self.prev_challenges
+            })
+    }
+}
+
+#[cfg(feature = "ocaml_types")]
+unsafe impl<G: AffineCurve + ocaml::FromValue, PC: PolynomialCommitment<G>> ocaml::FromValue for ProverProof<G, PC> where
+    G::ScalarField: ocaml::FromValue, PC::Proof: ocaml::FromValue {
+    fn from_value(v: ocaml::Value) -> Self {
+        let p: CamlProverProof<G, PC::Proof> = ocaml::FromValue::from_value(v);
+        ProverProof {
+            commitments: p.commitments,
+            proof: p.proof,
+            evals: {
+                let (evals0, evals1) = p.evals;
+                [evals0, evals1]
+            },
+            public: p.public,
+            prev_challenges: p.prev_challenges
+        }
+    }
+}
+
+// This function absorbs the quotient commitment, padded to the maximal number of segments, into the argument.
+// The padding, the zero points, that the sponge does not absorb, and the missing shifted part are replaced
+// with the point of the zero coordinates, the way the verifier circuit absorbs them
+//     fq_sponge: transcript of the argument
+//     t_comm: quotient polynomial commitment
+//     max_t_size: maximal number of the quotient polynomial segments
+pub fn absorb_quotient<G: CoordinatesCurve, EFqSponge: FqSponge<Fq<G>, G, Fr<G>>>
+    (fq_sponge: &mut EFqSponge, t_comm: &PolyComm<G>, max_t_size: usize)
+{
+    let dummy = G::of_coordinates(Fq::<G>::zero(), Fq::<G>::zero());
+    let nonzero = |g: &G| if g.is_zero() {dummy} else {*g};
+    fq_sponge.absorb_g(&t_comm.unshifted.iter().map(nonzero).collect::<Vec<_>>());
+    fq_sponge.absorb_g(&vec![dummy; max_t_size - t_comm.unshifted.len()]);
+    fq_sponge.absorb_g(&[t_comm.shifted.as_ref().map_or(dummy, nonzero)]);
+}
+
+impl<G: CoordinatesCurve, PC: PolynomialCommitment<G>> ProverProof<G, PC>
+{
+    // This function constructs prover's zk-proof from the witness & the Index against the commitment keys
+    //     witness: computation witness
+    //     index: Index
+    //     prev_challenges: challenges and commitments of the opening proofs of the previous proofs folded into the proof
+    //     RETURN: prover's zk-proof
+    pub fn create
+        <EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
+         EFrSponge: FrSponge<Fr<G>>,
+        >
+    (
+        witness: &Vec::<Fr<G>>,
+        index: &Index<G, PC>,
+        prev_challenges: Vec< (Vec<Fr<G>>, PolyComm<G>) >,
+    )
+    -> Result<Self, ProofError>
+    {
+        Self::create_profiled::<EFqSponge, EFrSponge>(witness, index, prev_challenges).map(|(proof, _)| proof)
+    }
+
+    // This function constructs prover's zk-proof as create does, timing the phases of the computation
+    //     witness: computation witness
+    //     index: Index
+    //     prev_challenges: challenges and commitments of the opening proofs of the previous proofs folded into the proof
+    //     RETURN: prover's zk-proof and the time spent in the phases of its computation
+    pub fn create_profiled
+        <EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
+         EFrSponge: FrSponge<Fr<G>>,
+        >
+    (
+        witness: &Vec::<Fr<G>>,
+        index: &Index<G, PC>,
+        prev_challenges: Vec< (Vec<Fr<G>>, PolyComm<G>) >,
+    )
+    -> Result<(Self, ProverProfile), ProofError>
+    {
+        let mut profile = ProverProfile::default();
+        let n = index.cs.domain.d1.size as usize;
+        if witness.len() != 3*n {return Err(ProofError::WitnessCsInconsistent)}
+
+        let mut oracles = RandomOracles::<Fr<G>>::zero();
+
+        // the transcript of the random oracle non-interactive argument
+        let mut fq_sponge = EFqSponge::new(index.fq_sponge_params.clone());
+
+        // compute public input polynomial
+        let public = witness[0..index.cs.public].to_vec();
+        let p = Evaluations::<Fr<G>, D<Fr<G>>>::from_vec_and_domain(public.iter().map(|s| -*s).collect(), index.cs.domain.d1).interpolate();
+
+        let rng = &mut thread_rng();
+
+        // This closure interpolates the column and commits to it, from the evaluations against the Lagrange basis
+        // precomputed by Index::create for a hiding scheme. The polynomials committed with a non-hiding scheme are
+        // blinded with the multiples of the vanishing polynomial, so they are committed in the coefficient form
+        let commit = |e: Vec<Fr<G>>, mut rng: &mut dyn RngCore|
+        {
+            let evals = Evaluations::<Fr<G>, D<Fr<G>>>::from_vec_and_domain(e, index.cs.domain.d1);
+            if index.pcs.hiding() {index.pcs.commit_interpolate(evals, rng)}
+            else
+            {
+                let p = &evals.interpolate() + &DensePolynomial::rand(1, &mut rng).mul_by_vanishing_poly(index.cs.domain.d1);
+                let (comm, omega) = index.pcs.commit(&p, None, rng);
+                (p, comm, omega)
+            }
+        };
+
+        // compute witness polynomials and commit to the l, r, o wire values
+        let start = Instant::now();
+        let (l, l_comm, omega_l) = commit(index.cs.gates.iter().map(|gate| witness[gate.wires.l.0]).collect(), rng);
+        let (r, r_comm, omega_r) = commit(index.cs.gates.iter().map(|gate| witness[gate.wires.r.0]).collect(), rng);
+        let (o, o_comm, omega_o) = commit(index.cs.gates.iter().map(|gate| witness[gate.wires.o.0]).collect(), rng);
+
+        // absorb the public input, l, r, o polycommitments into the argument
+        fq_sponge.absorb_g(&public_comm(&*index.pcs, index.cs.domain.d1, &public).unshifted);
+        fq_sponge.absorb_g(&l_comm.unshifted);
+        fq_sponge.absorb_g(&r_comm.unshifted);
+        fq_sponge.absorb_g(&o_comm.unshifted);
+        profile.commitments += start.elapsed();
+
+        // sample beta, gamma oracles
+        oracles.beta = fq_sponge.challenge();
+        oracles.gamma = fq_sponge.challenge();
+
+        // compute permutation polynomial
+        let start = Instant::now();
+        let mut z = vec![Fr::<G>::one(); n];
+        (0..n-3).for_each
+        (
+            |j| z[j+1] =
+                (witness[j] + &(index.cs.sigmal1[0][j] * &oracles.beta) + &oracles.gamma) *&
+                (witness[j+n] + &(index.cs.sigmal1[1][j] * &oracles.beta) + &oracles.gamma) *&
+                (witness[j+2*n] + &(index.cs.sigmal1[2][j] * &oracles.beta) + &oracles.gamma)
+        );
+        algebra::fields::batch_inversion::<Fr<G>>(&mut z[1..=n-3]);
+        (0..n-3).for_each
+        (
+            |j|
+            {
+                let x = z[j];
+                z[j+1] *=
+                &(x * &(witness[j] + &(index.cs.sid[j] * &oracles.beta) + &oracles.gamma) *&
+                (witness[j+n] + &(index.cs.sid[j] * &oracles.beta * &index.cs.r) + &oracles.gamma) *&
+                (witness[j+2*n] + &(index.cs.sid[j] * &oracles.beta * &index.cs.o) + &oracles.gamma))
+            }
+        );
+
+        if z[n-3] != Fr::<G>::one() {return Err(ProofError::ProofCreation)};
+        z[n-2] = Fr::<G>::rand(rng);
+        z[n-1] = Fr::<G>::rand(rng);
+        profile.z += start.elapsed();
+
+        // commit to z
+        let start = Instant::now();
+        let (z, z_comm, omega_z) = commit(z, rng);
+        profile.commitments += start.elapsed();
+
+        // absorb the z commitment into the argument and query alpha
+        fq_sponge.absorb_g(&z_comm.unshifted);
+        oracles.alpha_chal = ScalarChallenge(fq_sponge.challenge());
+        oracles.alpha = index.pcs.challenge(&oracles.alpha_chal);
+        let mut alpha = oracles.alpha;
+        let alpha = (0..range::CUSTOM.start + index.cs.custom.alphas()).map(|_| {alpha *= &oracles.alpha; alpha}).collect::<Vec<_>>();
+
+        // compute quotient polynomial, over the resident or the chunked evaluations of the polynomials
+        let start = Instant::now();
+        let mut tp = DensePolynomial::zero();
+        let t = index.cs.quot_evals
+        (
+            &l, &r, &o, &z,
+            |lagrange, sel|
+            {
+                // the contributions are computed in parallel
+                let ((gen, pos), ((mul, emul), (eca, (cst, perm)))) = rayon::join
+                (
+                    || rayon::join
+                    (
+                        // generic constraints contribution
+                        || index.cs.gnrc_quot(lagrange, sel, &p),
+                        // poseidon constraints contribution
+                        || index.cs.psdn_quot(lagrange, sel, &index.cs.fr_sponge_params, &alpha[range::PSDN]),
+                    ),
+                    || rayon::join
+                    (
+                        || rayon::join
+                        (
+                            // variable base scalar multiplication constraints contribution
+                            || index.cs.vbmul_quot(lagrange, sel, &alpha[range::MUL]),
+                            // group endomorphism optimised variable base scalar multiplication constraints contribution
+                            || index.cs.endomul_quot(lagrange, sel, &alpha[range::ENDML]),
+                        ),
+                        || rayon::join
+                        (
+                            // EC addition constraints contribution
+                            || index.cs.ecad_quot(lagrange, sel, &alpha[range::ADD]),
+                            || rayon::join
+                            (
+                                // registered custom constraints contribution
+                                || index.cs.custom_quot(lagrange, sel, &alpha[range::CUSTOM]),
+                                // permutation check contribution
+                                || index.cs.perm_quot(lagrange, sel, &oracles),
+                            ),
+                        ),
+                    ),
+                );
+
+                // the monomial contributions are the same over all the chunks
+                tp = &gen.1 + &pos.2;
+
+                // accumulate the contribution evaluations in place
+                let (mut t4, mut t8) = (gen.0, perm);
+                for e in [&pos.0, &mul.0, &emul.0, &cst.0].iter() {t4 += *e}
+                for e in [&pos.1, &eca, &mul.1, &emul.1, &cst.1].iter() {t8 += *e}
+                (t4, t8)
+            }
+        );
+
+        // divide contributions with vanishing polynomial
+        let (mut t, res) = (&t + &tp).
+            divide_by_vanishing_poly(index.cs.domain.d1).map_or(Err(ProofError::PolyDivision), |s| Ok(s))?;
+        if res.is_zero() == false {return Err(ProofError::PolyDivision)}
+
+        // permutation boundary condition check contribution
+        let (bnd1, res) =
+            DenseOrSparsePolynomial::divide_with_q_and_r(&(&z - &DensePolynomial::from_coefficients_slice(&[Fr::<G>::one()])).into(),
+                &DensePolynomial::from_coefficients_slice(&[-Fr::<G>::one(), Fr::<G>::one()]).into()).
+                map_or(Err(ProofError::PolyDivision), |s| Ok(s))?;
+        if res.is_zero() == false {return Err(ProofError::PolyDivision)}
+
+        let (bnd2, res) =
+            DenseOrSparsePolynomial::divide_with_q_and_r(&(&z - &DensePolynomial::from_coefficients_slice(&[Fr::<G>::one()])).into(),
+                &DensePolynomial::from_coefficients_slice(&[-index.cs.sid[n-3], Fr::<G>::one()]).into()).
+                map_or(Err(ProofError::PolyDivision), |s| Ok(s))?;
+        if res.is_zero() == false {return Err(ProofError::PolyDivision)}
+
+        t += &(&bnd1.scale(alpha[3]) + &bnd2.scale(alpha[4]));
+        t.coeffs.resize(index.max_quot_size, Fr::<G>::zero());
+        profile.quotient += start.elapsed();
+
+        // commit to t
+        let start = Instant::now();
+        let (t_comm, omega_t) = index.pcs.commit(&t, Some(index.max_quot_size), rng);
+        profile.commitments += start.elapsed();
+
+        // absorb the polycommitments into the argument and sample zeta
+        absorb_quotient(&mut fq_sponge, &t_comm, (index.max_quot_size + index.max_poly_size - 1) / index.max_poly_size);
+        oracles.zeta_chal = ScalarChallenge(fq_sponge.challenge());
+        oracles.zeta = index.pcs.challenge(&oracles.zeta_chal);
+
+        // evaluate the polynomials
+        let start = Instant::now();
+        let evlp = [oracles.zeta, oracles.zeta * &index.cs.domain.d1.group_gen];
+        let evals = evlp.iter().map
+        (
+            |e| ProofEvaluations::<Vec<Fr<G>>>
+            {
+                l : l.eval(*e, index.max_poly_size),
+                r : r.eval(*e, index.max_poly_size),
+                o : o.eval(*e, index.max_poly_size),
+                z : z.eval(*e, index.max_poly_size),
+                t : t.eval(*e, index.max_poly_size),
+
+                sigma1: index.cs.sigmam[0].eval(*e, index.max_poly_size),
+                sigma2: index.cs.sigmam[1].eval(*e, index.max_poly_size),
+
+                f: Vec::new(),
+            }
+        ).collect::<Vec<_>>();
+        let mut evals = [evals[0].clone(), evals[1].clone()];
+        let e = (0..2).map(|i| evals[i].combine(evlp[i].pow(&[index.max_poly_size as u64]))).collect::<Vec<_>>();
+
+        // compute and evaluate linearization polynomial
+
+        let f =
+            &(&(&(&(&index.cs.gnrc_lnrz(&e[0]) +
+            &index.cs.psdn_lnrz(&e, &index.cs.fr_sponge_params, &alpha[range::PSDN])) +
+            &index.cs.ecad_lnrz(&e, &alpha[range::ADD])) +
+            &index.cs.vbmul_lnrz(&e, &alpha[range::MUL])) +
+            &index.cs.endomul_lnrz(&e, &alpha[range::ENDML])) +
+            &(&index.cs.custom_lnrz(&e, &alpha[range::CUSTOM]) +
+            &index.cs.perm_lnrz(&e, &z, &oracles, &alpha[range::PERM]));
+
+        evals[0].f = f.eval(evlp[0], index.max_poly_size);
+        evals[1].f = f.eval(evlp[1], index.max_poly_size);
+        profile.linearization += start.elapsed();
+
+        let fq_sponge_before_evaluations = fq_sponge.clone();
+        let mut fr_sponge =
+        {
+            let mut s = EFrSponge::new(index.cs.fr_sponge_params.clone());
+            s.absorb(&fq_sponge.digest());
+            s
+        };
+        // the public input polynomial is of a single segment, see Index::create
+        let p_eval = if p.is_zero() {[Vec::new(), Vec::new()]}
+            else {[vec![p.evaluate(evlp[0])], vec![p.evaluate(evlp[1])]]};
+        for i in 0..2 {fr_sponge.absorb_evaluations(&p_eval[i], &evals[i])}
+
+        // query opening scaler challenges
+        oracles.v_chal = fr_sponge.challenge();
+        oracles.v = index.pcs.challenge(&oracles.v_chal);
+        oracles.u_chal = fr_sponge.challenge();
+        oracles.u = index.pcs.challenge(&oracles.u_chal);
+
+        // construct the proof
+        // --------------------------------------------------------------------
+        let start = Instant::now();
+        let polys = prev_challenges.iter().map(|(chals, comm)| {
+            (DensePolynomial::from_coefficients_vec(b_poly_coefficients(chals)), comm.unshifted.len())
+        }).collect::<Vec<_>>();
+
+        let non_hiding = |n : usize| PolyComm { unshifted: vec![Fr::<G>::zero(); n], shifted: None };
+        let segments = |p : &DensePolynomial<Fr<G>>| if p.is_zero() {0} else {(p.coeffs.len() + index.max_poly_size - 1) / index.max_poly_size};
+
+        // The verifier computes the commitment to f as
+        //
+        // perm_scalars[0] * z_comm + terms involving unblinded commitments
+        //
+        // Therefore, the coefficient of the blinding base in the f commitment is
+        // perm_scalars[0] * the coefficient in the z commitment.
+        let omega_f = {
+            let zkp = index.cs.zkpm.evaluate(oracles.zeta);
+            let perm_scalar0 = ConstraintSystem::perm_scalars
+            (
+                &e,
+                &oracles,
+                (index.cs.r, index.cs.o),
+                &alpha[range::PERM],
+                n as u64,
+                zkp,
+                // TODO: This 3 is the zero knowledge padding offset. Should be pulled out into
+                // a variable.
+                index.cs.sid[n - 3],
+            )[0];
+            omega_z.map(|x| perm_scalar0 * x)
+        };
+
+        let mut polynoms = polys.iter().map(|(p, n)| (p, None, non_hiding(*n) )).collect::<Vec<_>>();
+        polynoms.extend(
+            vec!
+            [
+                (&p, None, non_hiding(segments(&p))),
+                (&l, None, omega_l),
+                (&r, None, omega_r),
+                (&o, None, omega_o),
+                (&z, None, omega_z),
+                (&f, None, omega_f),
+                (&index.cs.sigmam[0], None, non_hiding(segments(&index.cs.sigmam[0]))),
+                (&index.cs.sigmam[1], None, non_hiding(segments(&index.cs.sigmam[1]))),
+                (&t, Some(index.max_quot_size), omega_t),
+            ]);
+
+        let proof =
+            Self
+            {
+                commitments: ProverCommitments {
+                    l_comm,
+                    r_comm,
+                    o_comm,
+                    z_comm,
+                    t_comm,
+                },
+                proof: index.pcs.open
+                (
+                    polynoms,
+                    &evlp.to_vec(),
+                    oracles.v,
+                    oracles.u,
+                    fq_sponge_before_evaluations,
+                    rng
+                )?,
+                evals,
+                public,
+                prev_challenges,
+            };
+        profile.opening += start.elapsed();
+
+        Ok((proof, profile))
+    }
+}
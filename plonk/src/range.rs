@@ -1,7 +1,10 @@
-use std::ops::Range;
+use std::ops::{Range, RangeFrom};
 
 pub const PSDN:     Range<usize> = 0..3;
 pub const PERM:     Range<usize> = 3..5;
 pub const ADD:      Range<usize> = 5..7;
 pub const ENDML:    Range<usize> = 7..13;
 pub const MUL:      Range<usize> = 13..17;
+
+// custom gates consume the powers of alpha following the built-in constraints
+pub const CUSTOM:   RangeFrom<usize> = 17..;
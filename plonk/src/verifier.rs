@@ -1,15 +1,17 @@
 /********************************************************************************************
 
-This source file implements zk-proof batch verifier functionality.
+This source file implements zk-proof batch verifier functionality generic over the
+polynomial commitment scheme.
 
 *********************************************************************************************/
 
 pub use super::prover::{ProverProof, range};
 pub use super::index::VerifierIndex as Index;
+use crate::{prover::absorb_quotient, index::public_comm};
 use oracle::{FqSponge, rndoracle::ProofError, sponge::ScalarChallenge};
+use poly_commitment::{commitment::{CoordinatesCurve, PolyComm, PolynomialCommitment, b_poly, b_poly_coefficients}, fixed::PolyCommTable};
 use plonk_circuits::{scalars::RandomOracles, constraints::ConstraintSystem};
-use commitment_dlog::commitment::{CommitmentField, CommitmentCurve, PolyComm, b_poly, b_poly_coefficients, combined_inner_product};
-use ff_fft::{EvaluationDomain};
+use ff_fft::EvaluationDomain;
 use algebra::{Field, AffineCurve, Zero, One};
 use crate::plonk_sponge::FrSponge;
 use rand::thread_rng;
@@ -24,9 +26,20 @@ pub struct CachedValues<Fs> {
     pub alpha: Vec<Fs>,
 }
 
-impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
+impl<G: AffineCurve, PC: PolynomialCommitment<G>> Index<G, PC>
 {
-    pub fn prev_chal_evals(&self, index: &Index<G>, evaluation_points: &[Fr<G>], evlp : &[Fr<G>]) -> Vec<Vec<Vec<Fr<G>>>> {
+    // This function commits to the public input polynomial
+    //     public: public part of the witness
+    //     RETURN: public input polynomial commitment
+    pub fn public_comm(&self, public: &Vec<Fr<G>>) -> PolyComm<G>
+    {
+        public_comm(&*self.pcs, self.domain, public)
+    }
+}
+
+impl<G: CoordinatesCurve, PC: PolynomialCommitment<G>> ProverProof<G, PC>
+{
+    pub fn prev_chal_evals(&self, index: &Index<G, PC>, evaluation_points: &[Fr<G>], evlp : &[Fr<G>]) -> Vec<Vec<Vec<Fr<G>>>> {
         self.prev_challenges.iter().map(|(chals, _poly)| {
             // No need to check the correctness of poly explicitly. Its correctness is assured by the
             // checking of the inner product argument.
@@ -38,7 +51,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
                 |i|
                 {
                     let full = b_poly(&chals, evaluation_points[i]);
-                    if index.max_poly_size == b_len {
+                    if b_len <= index.max_poly_size {
                         return vec![full]
                     }
                     let mut betaacc = Fr::<G>::one();
@@ -65,15 +78,20 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
     }
 
     // This function runs random oracle argument
+    //     index: verifier index
+    //     p_comm: public input polynomial commitment
+    //     RETURN: sponge state before the evaluations, its digest, oracles, powers of alpha, public input
+    //             polynomial evaluations, evaluation points raised to the segment size, commitments and
+    //             evaluations of the previous challenge polynomials, zeta raised to the domain size
     pub fn oracles
         <EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
          EFrSponge: FrSponge<Fr<G>>,
         >
     (
         &self,
-        index: &Index<G>,
+        index: &Index<G, PC>,
         p_comm: &PolyComm<G>,
-    ) -> (EFqSponge, Fr<G>, RandomOracles<Fr<G>>, Vec<Fr<G>>, [Vec<Fr<G>>; 2], [Fr<G>; 2], Vec<(PolyComm<G>, Vec<Vec<Fr<G>>>)>, Fr<G>, Fr<G>)
+    ) -> (EFqSponge, Fr<G>, RandomOracles<Fr<G>>, Vec<Fr<G>>, [Vec<Fr<G>>; 2], [Fr<G>; 2], Vec<(PolyComm<G>, Vec<Vec<Fr<G>>>)>, Fr<G>)
     {
         let n = index.domain.size;
         // Run random oracle argument to sample verifier oracles
@@ -90,23 +108,11 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         // absorb the z commitment into the argument and query alpha
         fq_sponge.absorb_g(&self.commitments.z_comm.unshifted);
         oracles.alpha_chal = ScalarChallenge(fq_sponge.challenge());
-        oracles.alpha = oracles.alpha_chal.to_field(&index.srs.get_ref().endo_r);
+        oracles.alpha = index.pcs.challenge(&oracles.alpha_chal);
         // absorb the polycommitments into the argument and sample zeta
-        let max_t_size = (index.max_quot_size + index.max_poly_size - 1) / index.max_poly_size;
-        let dummy = G::of_coordinates(Fq::<G>::zero(), Fq::<G>::zero());
-        fq_sponge.absorb_g(&self.commitments.t_comm.unshifted);
-        fq_sponge.absorb_g(&vec![dummy; max_t_size - self.commitments.t_comm.unshifted.len()]);
-        {
-            let s = self.commitments.t_comm.shifted.unwrap();
-            if s.is_zero() {
-                fq_sponge.absorb_g(&[dummy])
-            } else {
-                fq_sponge.absorb_g(&[s])
-            }
-        };
-
+        absorb_quotient(&mut fq_sponge, &self.commitments.t_comm, (index.max_quot_size + index.max_poly_size - 1) / index.max_poly_size);
         oracles.zeta_chal = ScalarChallenge(fq_sponge.challenge());
-        oracles.zeta = oracles.zeta_chal.to_field(&index.srs.get_ref().endo_r);
+        oracles.zeta = index.pcs.challenge(&oracles.zeta_chal);
         let digest = fq_sponge.clone().digest();
         let mut fr_sponge =
         {
@@ -119,7 +125,7 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         let zeta1 = oracles.zeta.pow(&[n]);
         let zetaw = oracles.zeta * &index.domain.group_gen;
         let mut alpha = oracles.alpha;
-        let alpha = (0..17).map(|_| {alpha *= &oracles.alpha; alpha}).collect::<Vec<_>>();
+        let alpha = (0..range::CUSTOM.start + index.custom.alphas()).map(|_| {alpha *= &oracles.alpha; alpha}).collect::<Vec<_>>();
 
         // compute Lagrange base evaluation denominators
         let w = (0..self.public.len()).zip(index.domain.elements()).map(|(_,w)| w).collect::<Vec<_>>();
@@ -128,7 +134,8 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
         algebra::fields::batch_inversion::<Fr<G>>(&mut lagrange);
 
         // evaluate public input polynomials
-        // NOTE: this works only in the case when the poly segment size is not smaller than that of the domain 
+        // NOTE: this works only in the case when the poly segment size is not smaller than that of the domain,
+        // see Index::create
         let p_eval = if self.public.len() > 0
         {[
             vec![(self.public.iter().zip(lagrange.iter()).
@@ -143,9 +150,9 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
 
         // query opening scaler challenges
         oracles.v_chal = fr_sponge.challenge();
-        oracles.v = oracles.v_chal.to_field(&index.srs.get_ref().endo_r);
+        oracles.v = index.pcs.challenge(&oracles.v_chal);
         oracles.u_chal = fr_sponge.challenge();
-        oracles.u = oracles.u_chal.to_field(&index.srs.get_ref().endo_r);
+        oracles.u = index.pcs.challenge(&oracles.u_chal);
 
         let ep = [oracles.zeta, zetaw];
 
@@ -160,44 +167,18 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
             .zip(self.prev_chal_evals(index, &ep, &evlp))
             .map(|(c, e)| (c.1.clone(), e)).collect();
 
-        let combined_inner_product = {
-            let mut es : Vec<(Vec<&Vec<Fr<G>>>, Option<usize>)> = polys.iter().map(|(_, e)| (e.iter().map(|x| x).collect(), None)).collect();
-            es.extend(
-                vec!
-                [
-                    (p_eval.iter().map(|e| e).collect::<Vec<_>>(), None),
-
-                    (self.evals.iter().map(|e| &e.l).collect::<Vec<_>>(), None),
-                    (self.evals.iter().map(|e| &e.r).collect::<Vec<_>>(), None),
-                    (self.evals.iter().map(|e| &e.o).collect::<Vec<_>>(), None),
-                    (self.evals.iter().map(|e| &e.z).collect::<Vec<_>>(), None),
-
-                    (self.evals.iter().map(|e| &e.f).collect::<Vec<_>>(), None),
-
-                    (self.evals.iter().map(|e| &e.sigma1).collect::<Vec<_>>(), None),
-                    (self.evals.iter().map(|e| &e.sigma2).collect::<Vec<_>>(), None),
-
-                    (self.evals.iter().map(|e| &e.t).collect::<Vec<_>>(), Some(index.max_quot_size)),
-                ]
-            );
-
-            combined_inner_product::<G>(&ep, &oracles.v, &oracles.u, &es, index.srs.get_ref().g.len())
-        };
-
-        (fq_sponge, digest, oracles, alpha, p_eval, evlp, polys, zeta1, combined_inner_product)
+        (fq_sponge, digest, oracles, alpha, p_eval, evlp, polys, zeta1)
     }
 
     // This function verifies the batch of zk-proofs
-    //     proofs: vector of Plonk proofs
-    //     index: Index
+    //     proofs: vector of Plonk proofs with their verifier indexes sharing the commitment keys
     //     RETURN: verification status
     pub fn verify
         <EFqSponge: Clone + FqSponge<Fq<G>, G, Fr<G>>,
          EFrSponge: FrSponge<Fr<G>>,
         >
     (
-        group_map: &G::Map,
-        proofs: &Vec<(&Index<G>, &Vec<PolyComm<G>>, &ProverProof<G>)>,
+        proofs: &Vec<(&Index<G, PC>, &ProverProof<G, PC>)>,
     ) -> Result<bool, ProofError>
     {
         if proofs.len() == 0 {
@@ -206,35 +187,21 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
 
         let params = proofs.iter().map
         (
-            |(index, lgr_comm, proof)|
+            |(index, proof)|
             {
                 let n = index.domain.size;
+                let max_t_size = (index.max_quot_size + index.max_poly_size - 1) / index.max_poly_size;
+                if proof.commitments.t_comm.unshifted.len() > max_t_size {return Err(ProofError::ProofVerification)}
+                if proof.public.len() > n as usize {return Err(ProofError::ProofVerification)}
+
                 // commit to public input polynomial
-                let p_comm = PolyComm::<G>::multi_scalar_mul
-                    (& lgr_comm.iter().take(proof.public.len()).map(|l| l).collect(), &proof.public.iter().map(|s| -*s).collect());
+                let p_comm = index.public_comm(&proof.public);
 
-                let (fq_sponge, _, oracles, alpha, p_eval, evlp, polys, zeta1, _) = proof.oracles::<EFqSponge, EFrSponge>(index, &p_comm);
+                let (fq_sponge, _, oracles, alpha, p_eval, evlp, polys, zeta1) = proof.oracles::<EFqSponge, EFrSponge>(index, &p_comm);
 
                 // evaluate committed polynoms
                 let evals = (0..2).map(|i| proof.evals[i].combine(evlp[i])).collect::<Vec<_>>();
 
-                // compute linearization polynomial commitment
-                let p = vec!
-                [
-                    // permutation polynomial commitments
-                    &proof.commitments.z_comm, &index.sigma_comm[2],
-                    // generic constraint polynomial commitments
-                    &index.qm_comm, &index.ql_comm, &index.qr_comm, &index.qo_comm, &index.qc_comm,
-                    // poseidon constraint polynomial commitments
-                    &index.psm_comm, &index.rcm_comm[0], &index.rcm_comm[1], &index.rcm_comm[2],
-                    // EC addition constraint polynomial commitments
-                    &index.add_comm,
-                    // EC variable base scalar multiplication constraint polynomial commitments
-                    &index.mul1_comm, &index.mul2_comm,
-                    // group endomorphism optimised variable base scalar multiplication constraint polynomial commitments
-                    &index.emul1_comm, &index.emul2_comm, &index.emul3_comm,
-                ];
-
                 // permutation linearization scalars
                 let zkp = index.zkpm.evaluate(oracles.zeta);
                 let mut s = ConstraintSystem::perm_scalars
@@ -257,8 +224,24 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
                 s.extend(&ConstraintSystem::vbmul_scalars(&evals, &alpha[range::MUL]));
                 // group endomorphism optimised variable base scalar multiplication constraint linearization scalars
                 s.extend(&ConstraintSystem::endomul_scalars(&evals, index.endo, &alpha[range::ENDML]));
+                // custom constraint linearization scalars
+                s.extend(&ConstraintSystem::custom_scalars(&index.custom, &evals, &alpha[range::CUSTOM]));
 
-                let f_comm = PolyComm::multi_scalar_mul(&p, &s);
+                // compute linearization polynomial commitment, from the tables only if they are of the index
+                // commitments, these being public and possibly changed after the tables were precomputed
+                let lnrz = index.linearization_comm();
+                let f_comm = match &index.tables
+                {
+                    Some(tables) if tables.len() == lnrz.len() && tables.iter().zip(lnrz.iter()).all(|(t, c)| t.is_of(c)) =>
+                        PolyCommTable::multi_scalar_mul
+                            (&vec![&proof.commitments.z_comm], &s[0..1].to_vec(), &tables.iter().collect(), &s[1..].to_vec()),
+                    _ =>
+                    {
+                        let mut p = vec![&proof.commitments.z_comm];
+                        p.extend(lnrz);
+                        PolyComm::multi_scalar_mul(&p, &s)
+                    }
+                };
 
                 // check linearization polynomial evaluation consistency
                 if
@@ -278,10 +261,10 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
                 Ok((p_eval, p_comm, f_comm, fq_sponge, oracles, polys))
             }
         ).collect::<Result<Vec<_>, _>>()?;
-        
+
         let mut batch = proofs.iter().zip(params.iter()).map
         (
-            |((index, _lgr_comm, proof), (p_eval, p_comm, f_comm, fq_sponge, oracles, polys))|
+            |((index, proof), (p_eval, p_comm, f_comm, fq_sponge, oracles, polys))|
             {
                 let mut polynoms = polys.iter().map
                 (
@@ -323,14 +306,13 @@ impl<G: CommitmentCurve> ProverProof<G> where G::ScalarField : CommitmentField
             }
         ).collect::<Vec<_>>();
 
-        // verify the opening proofs
-        // TODO: Account for the different SRS lengths
-        let srs = proofs[0].0.srs.get_ref();
-        for (index, _, _) in proofs.iter() {
-            assert_eq!(index.srs.get_ref().g.len(), srs.g.len());
+        // verify the opening proofs against the commitment keys shared by the indexes
+        let pcs = &proofs[0].0.pcs;
+        for (index, _) in proofs.iter() {
+            if index.max_poly_size != pcs.max_poly_size() {return Err(ProofError::OpenProof)}
         }
 
-        match srs.verify::<EFqSponge>(group_map, &mut batch, &mut thread_rng())
+        match pcs.verify::<EFqSponge>(&mut batch, &mut thread_rng())
         {
             false => Err(ProofError::OpenProof),
             true => Ok(true)